@@ -0,0 +1,418 @@
+use clap::{ArgGroup, Parser};
+use log::LevelFilter;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use geode_cli_installer::errors::InstallerError;
+use geode_cli_installer::utils::checksum::Checksum;
+use geode_cli_installer::utils::config::validate_dll_override;
+use geode_cli_installer::utils::geode_installer::{GeodeInstaller, DEFAULT_RETRIES, DEFAULT_TIMEOUT, InstallOptions, InstallTarget};
+use geode_cli_installer::utils::steam_game_finder::FinderOptions;
+use geode_cli_installer::utils::wine_registry::{RegistryMethod, RegistryScope};
+
+const NO_COLOR_ENV: &str = "NO_COLOR";
+
+/// Install Geode into Geometry Dash running under Steam/Proton or Wine
+#[derive(Debug, Parser)]
+#[command(version, about, disable_version_flag = true)]
+#[command(group(ArgGroup::new("install").args(["steam", "wine"])))]
+#[command(group(ArgGroup::new("batch").args(["target", "all"])))]
+#[command(group(ArgGroup::new("action").multiple(false).args([
+    "steam", "wine", "doctor", "verify", "status", "changelog", "uninstall", "rollback", "restore_registry",
+    "reset", "update_all", "target", "all", "clear_cache", "check_update", "create_desktop_entry", "list_mods",
+])))]
+pub struct Cli {
+    /// Print the installer's own version
+    #[arg(short = 'V', long, action = clap::ArgAction::Version)]
+    pub installer_version: Option<bool>,
+
+    /// Install to Steam's Geometry Dash (Proton) without the menu
+    #[arg(long)]
+    pub steam: bool,
+
+    /// Install to a Wine prefix without the menu (needs --prefix and --game-dir)
+    #[arg(long, requires_all = ["prefix", "game_dir"])]
+    pub wine: bool,
+
+    /// With --steam or --wine, print only a JSON object with the outcome: `success`,
+    /// `version`, `game_path`, `prefix_path` and `error` (`kind` and `message`). Implies --yes
+    #[arg(long, requires = "install")]
+    pub json: bool,
+
+    /// Diagnose Steam/Wine detection problems and Geode API access, then exit
+    #[arg(long)]
+    pub doctor: bool,
+
+    /// Check that Geode's files and the DLL override are in place (in --prefix and --game-dir,
+    /// or Steam's copy), then exit
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Show the installed and latest Geode versions and exit
+    #[arg(long)]
+    pub status: bool,
+
+    /// With --status, print one stable tab-separated line for scripts:
+    /// `<installed|-> <latest> <update available: true|false>`
+    #[arg(long, requires = "status")]
+    pub porcelain: bool,
+
+    /// Show the release notes between the installed and the latest Geode version and exit
+    #[arg(long)]
+    pub changelog: bool,
+
+    /// Update Geode and every installed mod that has a compatible newer version, then exit
+    #[arg(long)]
+    pub update_all: bool,
+
+    /// Remove Geode, its mods and the DLL override (from Steam's copy, or --prefix and --game-dir), then exit
+    #[arg(long)]
+    pub uninstall: bool,
+
+    /// Restore the files and Wine registry from before the last install (from Steam's copy, or --game-dir), then exit
+    #[arg(long)]
+    pub rollback: bool,
+
+    /// Put back the newest user.reg backup taken before patching (in --prefix, or Steam's Proton prefix), then exit
+    #[arg(long)]
+    pub restore_registry: bool,
+
+    /// Delete Geode, all mods and their data, the per-install config and the DLL override, then exit
+    #[arg(long)]
+    pub reset: bool,
+
+    /// List the mods installed in geode/mods and exit
+    #[arg(long)]
+    pub list_mods: bool,
+
+    /// Delete the cached release archives and exit
+    #[arg(long)]
+    pub clear_cache: bool,
+
+    /// Check whether a newer version of this installer is out and exit
+    #[arg(long)]
+    pub check_update: bool,
+
+    /// Add a launcher for this installer to your application menu and exit
+    #[arg(long)]
+    pub create_desktop_entry: bool,
+
+    /// Install to a prefix and game directory pair; repeat to install to several at once
+    #[arg(long, num_args = 2, value_names = ["PREFIX", "GAME_DIR"], action = clap::ArgAction::Append)]
+    pub target: Vec<PathBuf>,
+
+    /// Install to every Steam copy of Geometry Dash (one per library), downloading once
+    #[arg(long, conflicts_with = "target")]
+    pub all: bool,
+
+    /// With several --target pairs or --all, keep installing after a failure and report all errors at the end
+    #[arg(long, requires = "batch")]
+    pub keep_going: bool,
+
+    /// Wine prefix to operate on (auto-detected from Steam if omitted)
+    #[arg(long, value_name = "PATH")]
+    pub prefix: Option<PathBuf>,
+
+    /// Geometry Dash directory to operate on (auto-detected from Steam if omitted)
+    #[arg(long, value_name = "PATH")]
+    pub game_dir: Option<PathBuf>,
+
+    /// Use this directory as home when detecting Steam (instead of $HOME)
+    #[arg(long, value_name = "PATH")]
+    pub home: Option<PathBuf>,
+
+    /// Use this Steam installation instead of auto-detecting it (must contain steamapps).
+    /// GEODE_STEAM_ROOT does the same when this isn't given
+    #[arg(long, value_name = "PATH")]
+    pub steam_root: Option<PathBuf>,
+
+    /// Also search every drive mounted under /run/media, /media and /mnt for Steam libraries
+    /// (slower), for ones libraryfolders.vdf doesn't know about
+    #[arg(long)]
+    pub deep_scan: bool,
+
+    /// Also look for the Proton prefix in <PATH>/<app id>/pfx, for compat data kept outside
+    /// the libraries. STEAM_COMPAT_DATA_PATH is searched the same way
+    #[arg(long, value_name = "PATH")]
+    pub prefix_root: Option<PathBuf>,
+
+    /// Look for the game under this Steam app id instead of Geometry Dash's (322170)
+    #[arg(long, value_name = "ID", value_parser = parse_app_id)]
+    pub app_id: Option<String>,
+
+    /// Install this Geode release (e.g. v4.2.0) instead of the latest one
+    #[arg(long, value_name = "TAG", value_parser = parse_tag)]
+    pub version: Option<String>,
+
+    /// Expected SHA-256 of the downloaded release archive (overrides the hash from the Geode API)
+    #[arg(long, value_name = "HEX", value_parser = parse_sha256)]
+    pub sha256: Option<String>,
+
+    /// Install from a release archive on disk instead of downloading one. The version comes
+    /// from --version, or from a geode-<tag>-win.zip file name
+    #[arg(long, value_name = "PATH")]
+    pub zip: Option<PathBuf>,
+
+    /// How many times to try each network request before giving up
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_RETRIES)]
+    pub retries: u32,
+
+    /// Seconds to wait for a connection, or for a server that stops sending data
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_TIMEOUT.as_secs(), value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: u64,
+
+    /// Download the release archive even if it's cached, and don't cache it
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Ask the Geode API for the latest release even if it was asked less than an hour ago
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Don't back up the game files an install replaces to <game dir>/.geode_backup
+    #[arg(long)]
+    pub no_backup: bool,
+
+    /// Install even if the game directory has no GeometryDash.exe, or while the game is running
+    #[arg(long)]
+    pub force: bool,
+
+    /// Send requests through this HTTP(S) proxy; HTTP_PROXY, HTTPS_PROXY and ALL_PROXY are
+    /// used when this isn't given
+    #[arg(long, value_name = "URL", value_parser = parse_proxy)]
+    pub proxy: Option<String>,
+
+    /// Download releases from this mirror (<URL>/<tag>/geode-<tag>-win.zip) instead of GitHub.
+    /// GEODE_MIRROR does the same when this isn't given
+    #[arg(long, value_name = "URL", value_parser = parse_mirror)]
+    pub mirror: Option<String>,
+
+    /// Show what would be downloaded, extracted and written to the registry without doing it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't check the installed files and DLL override at the end of a Wine install
+    #[arg(long)]
+    pub no_verify: bool,
+
+    /// Which Wine registry files get the DLL override; some Proton setups only honor system.reg
+    #[arg(long, value_enum, default_value_t = RegistryScope::User)]
+    pub registry_scope: RegistryScope,
+
+    /// How to write DLL overrides: edit the .reg files, or import them with `wine regedit` (needs Wine on PATH)
+    #[arg(long, value_enum, default_value_t = RegistryMethod::File)]
+    pub registry_method: RegistryMethod,
+
+    /// Also override this DLL in the Wine registry, as NAME=VALUE (e.g. d3d9=native,builtin); repeatable
+    #[arg(long, value_name = "NAME=VALUE", value_parser = parse_dll_override)]
+    pub dll_override: Vec<(String, String)>,
+
+    /// Download into this directory instead of the game directory (useful if the game is on slow storage);
+    /// the finished download is still moved into the cache unless --no-cache is given
+    #[arg(long, value_name = "PATH")]
+    pub temp_dir: Option<PathBuf>,
+
+    /// Only accept TLS certificates issued by the CAs the Geode API and GitHub use,
+    /// to guard against interception on untrusted networks (may break if a host changes CA)
+    #[arg(long)]
+    pub pin_certs: bool,
+
+    /// Check that the extracted DLLs are 64-bit Windows binaries (slower)
+    #[arg(long)]
+    pub verify_arch: bool,
+
+    /// Answer yes to every confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Print debug detail, including every path probed while looking for Steam and the game
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Only print errors (and what a command was asked to show)
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Plain output without colors (also when NO_COLOR is set or stdout isn't a terminal)
+    #[arg(long)]
+    pub no_color: bool,
+}
+
+/// Actions that run once without the interactive menu
+pub enum Command {
+    InstallSteam,
+    InstallWine { prefix: PathBuf, game_dir: PathBuf },
+    ListMods { game_dir: Option<PathBuf> },
+    Doctor { prefix: Option<PathBuf>, game_dir: Option<PathBuf> },
+    Verify { prefix: Option<PathBuf>, game_dir: Option<PathBuf> },
+    Status { game_dir: Option<PathBuf>, porcelain: bool },
+    Changelog { game_dir: Option<PathBuf> },
+    UpdateAll { game_dir: Option<PathBuf> },
+    Uninstall { prefix: Option<PathBuf>, game_dir: Option<PathBuf> },
+    Rollback { game_dir: Option<PathBuf> },
+    RestoreRegistry { prefix: Option<PathBuf> },
+    Reset { prefix: Option<PathBuf>, game_dir: Option<PathBuf> },
+    Batch { targets: Vec<InstallTarget>, keep_going: bool },
+    InstallAll { keep_going: bool },
+    ClearCache,
+    CheckUpdate,
+    CreateDesktopEntry,
+}
+
+impl Cli {
+    /// See https://no-color.org: NO_COLOR disables color when set to anything but an empty string
+    pub fn color_enabled(&self) -> bool {
+        let no_color_env = std::env::var_os(NO_COLOR_ENV).is_some_and(|value| !value.is_empty());
+        !self.no_color && !no_color_env && io::stdout().is_terminal()
+    }
+
+    pub fn log_level(&self) -> LevelFilter {
+        if self.json {
+            LevelFilter::Off
+        } else if self.quiet {
+            LevelFilter::Error
+        } else if self.verbose {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Info
+        }
+    }
+
+    pub fn install_options(&self) -> InstallOptions {
+        InstallOptions {
+            verify_architecture: self.verify_arch,
+            verbose: self.verbose,
+            temp_dir: self.temp_dir.clone(),
+            pin_certs: self.pin_certs,
+            version: self.version.clone(),
+            sha256: self.sha256.clone(),
+            archive: self.zip.clone(),
+            retries: self.retries,
+            timeout: Some(Duration::from_secs(self.timeout)),
+            no_cache: self.no_cache,
+            refresh: self.refresh,
+            no_backup: self.no_backup,
+            force: self.force,
+            dry_run: self.dry_run,
+            no_verify: self.no_verify,
+            mirror: self.mirror.clone(),
+            proxy: self.proxy.clone(),
+            registry_scope: self.registry_scope,
+            dll_overrides: self.dll_override.clone(),
+            registry_method: self.registry_method,
+            app_id: self.app_id.clone(),
+            finder: FinderOptions {
+                home: self.home.clone(),
+                steam_root: self.steam_root.clone(),
+                deep_scan: self.deep_scan,
+                prefix_root: self.prefix_root.clone(),
+            },
+        }
+    }
+
+    /// The action flags are mutually exclusive (the `action` group), so at most one of these matches
+    pub fn command(&self) -> Option<Command> {
+        if self.steam {
+            return Some(Command::InstallSteam);
+        }
+        if self.wine
+            && let (Some(prefix), Some(game_dir)) = (&self.prefix, &self.game_dir)
+        {
+            return Some(Command::InstallWine { prefix: prefix.clone(), game_dir: game_dir.clone() });
+        }
+        if self.doctor {
+            return Some(Command::Doctor { prefix: self.prefix.clone(), game_dir: self.game_dir.clone() });
+        }
+        if self.verify {
+            return Some(Command::Verify { prefix: self.prefix.clone(), game_dir: self.game_dir.clone() });
+        }
+        if self.status {
+            return Some(Command::Status { game_dir: self.game_dir.clone(), porcelain: self.porcelain });
+        }
+        if self.changelog {
+            return Some(Command::Changelog { game_dir: self.game_dir.clone() });
+        }
+        if self.uninstall {
+            return Some(Command::Uninstall { prefix: self.prefix.clone(), game_dir: self.game_dir.clone() });
+        }
+        if self.rollback {
+            return Some(Command::Rollback { game_dir: self.game_dir.clone() });
+        }
+        if self.restore_registry {
+            return Some(Command::RestoreRegistry { prefix: self.prefix.clone() });
+        }
+        if self.reset {
+            return Some(Command::Reset { prefix: self.prefix.clone(), game_dir: self.game_dir.clone() });
+        }
+        if self.update_all {
+            return Some(Command::UpdateAll { game_dir: self.game_dir.clone() });
+        }
+        if !self.target.is_empty() {
+            return Some(Command::Batch { targets: self.targets(), keep_going: self.keep_going });
+        }
+        if self.all {
+            return Some(Command::InstallAll { keep_going: self.keep_going });
+        }
+        if self.clear_cache {
+            return Some(Command::ClearCache);
+        }
+        if self.check_update {
+            return Some(Command::CheckUpdate);
+        }
+        if self.create_desktop_entry {
+            return Some(Command::CreateDesktopEntry);
+        }
+        if self.list_mods {
+            return Some(Command::ListMods { game_dir: self.game_dir.clone() });
+        }
+        None
+    }
+
+    fn targets(&self) -> Vec<InstallTarget> {
+        self.target
+            .chunks_exact(2)
+            .map(|pair| InstallTarget {
+                prefix: pair[0].clone(),
+                game_dir: pair[1].clone(),
+            })
+            .collect()
+    }
+}
+
+fn parse_tag(tag: &str) -> Result<String, String> {
+    GeodeInstaller::validate_tag(tag).map_err(|e| e.to_string())?;
+    Ok(tag.to_string())
+}
+
+fn parse_app_id(app_id: &str) -> Result<String, String> {
+    GeodeInstaller::validate_app_id(app_id).map_err(|_| "expected a numeric Steam app id".to_string())?;
+    Ok(app_id.to_string())
+}
+
+fn parse_proxy(url: &str) -> Result<String, String> {
+    GeodeInstaller::parse_proxy(url).map_err(|e| match e {
+        InstallerError::InvalidProxy { reason, .. } => reason,
+        e => e.to_string(),
+    })?;
+    Ok(url.to_string())
+}
+
+fn parse_mirror(url: &str) -> Result<String, String> {
+    GeodeInstaller::validate_mirror(url).map_err(|_| "expected an http:// or https:// URL".to_string())
+}
+
+fn parse_sha256(hash: &str) -> Result<String, String> {
+    Checksum::normalize(hash).map_err(|_| "expected 64 hex digits".to_string())
+}
+
+fn parse_dll_override(arg: &str) -> Result<(String, String), String> {
+    let (name, value) = arg.split_once('=').ok_or("expected NAME=VALUE")?;
+    let name = name.trim();
+    let name = name.strip_suffix(".dll").unwrap_or(name);
+    if name.is_empty() || value.is_empty() {
+        return Err("expected NAME=VALUE".into());
+    }
+    validate_dll_override(name, value)?;
+    Ok((name.to_string(), value.to_string()))
+}
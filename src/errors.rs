@@ -1,5 +1,8 @@
 use std::io;
+use std::path::PathBuf;
 use colored::Colorize;
+use indicatif::HumanBytes;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 
 #[derive(Debug, thiserror::Error)]
 pub enum InstallerError {
@@ -15,6 +18,57 @@ pub enum InstallerError {
     #[error("Installation failed: {0}")]
     Installation(String),
 
+    #[error("{0:?} is not a Geode release tag, expected something like v4.2.0")]
+    InvalidTag(String),
+
+    #[error("Can't use path {path:?}: {reason}")]
+    InvalidPath { path: String, reason: String },
+
+    #[error("Invalid proxy {url:?}: {reason}")]
+    InvalidProxy { url: String, reason: String },
+
+    #[error("Checksum mismatch: expected {expected}, got {got}")]
+    ChecksumMismatch { expected: String, got: String },
+
+    #[error("Timed out: {0}. A slow connection may need a longer --timeout")]
+    Timeout(String),
+
+    #[error("Not enough disk space in {path:?}: need {}, have {}", HumanBytes(*.need), HumanBytes(*.have))]
+    NotEnoughSpace { path: PathBuf, need: u64, have: u64 },
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("HTTP error {status} from {url}")]
+    Http { status: u16, url: String },
+
+    #[error("Download failed: {0}")]
+    Download(String),
+
+    #[error("Extraction failed: {0}")]
+    Extract(String),
+
+    #[error("Registry error: {0}")]
+    Registry(String),
+
+    #[error("Can't find Steam installation in {0:?}, use --steam-root to set it explicitly")]
+    SteamNotFound(PathBuf),
+
+    #[error("Can't find Geometry Dash installation")]
+    GameNotFound,
+
+    #[error("Can't find Proton prefix for Geometry Dash, launch the game once through Steam with Proton first")]
+    PrefixNotFound,
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
     #[error("An error occurred: {0}")]
     Unknown(String),
 }
@@ -23,17 +77,72 @@ impl InstallerError {
     pub fn format(&self) -> String {
         format!("❌ {}", self).red().bold().to_string()
     }
+
+    /// Name of the variant for machine-readable output. These don't change when messages do
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InstallerError::NotANumber => "not_a_number",
+            InstallerError::InvalidNumber => "invalid_number",
+            InstallerError::Init(_) => "init",
+            InstallerError::Installation(_) => "installation",
+            InstallerError::InvalidTag(_) => "invalid_tag",
+            InstallerError::InvalidPath { .. } => "invalid_path",
+            InstallerError::InvalidProxy { .. } => "invalid_proxy",
+            InstallerError::ChecksumMismatch { .. } => "checksum_mismatch",
+            InstallerError::Timeout(_) => "timeout",
+            InstallerError::NotEnoughSpace { .. } => "not_enough_space",
+            InstallerError::RateLimited(_) => "rate_limited",
+            InstallerError::NotFound(_) => "not_found",
+            InstallerError::Network(_) => "network",
+            InstallerError::Http { .. } => "http",
+            InstallerError::Download(_) => "download",
+            InstallerError::Extract(_) => "extract",
+            InstallerError::Registry(_) => "registry",
+            InstallerError::SteamNotFound(_) => "steam_not_found",
+            InstallerError::GameNotFound => "game_not_found",
+            InstallerError::PrefixNotFound => "prefix_not_found",
+            InstallerError::Io(_) => "io",
+            InstallerError::Unknown(_) => "unknown",
+        }
+    }
+}
+
+/// `{"kind": ..., "message": ...}`
+impl Serialize for InstallerError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("InstallerError", 2)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
 }
 
 impl From<io::Error> for InstallerError {
     fn from(e: io::Error) -> Self {
-        InstallerError::Unknown(e.to_string())
+        // Reading a response body that stalls fails with the reqwest timeout wrapped in an io::Error
+        let http_timeout = e.get_ref()
+            .and_then(|inner| inner.downcast_ref::<reqwest::Error>())
+            .is_some_and(reqwest::Error::is_timeout);
+        if http_timeout || e.kind() == io::ErrorKind::TimedOut {
+            return InstallerError::Timeout(e.to_string());
+        }
+        // SteamOS ships with a read-only root, anything outside home and the libraries hits this
+        if e.kind() == io::ErrorKind::ReadOnlyFilesystem {
+            return InstallerError::Io(format!("{}. Only your home directory and game libraries are writable on SteamOS", e));
+        }
+        InstallerError::Io(e.to_string())
     }
 }
 
 impl From<reqwest::Error> for InstallerError {
     fn from(e: reqwest::Error) -> Self {
-        InstallerError::Unknown(e.to_string())
+        if e.is_timeout() {
+            return InstallerError::Timeout(e.to_string());
+        }
+        match (e.status(), e.url()) {
+            (Some(status), Some(url)) => InstallerError::Http { status: status.as_u16(), url: url.to_string() },
+            _ => InstallerError::Network(e.to_string()),
+        }
     }
 }
 
@@ -45,7 +154,7 @@ impl From<serde_json::Error> for InstallerError {
 
 impl From<zip::result::ZipError> for InstallerError {
     fn from(e: zip::result::ZipError) -> Self {
-        InstallerError::Unknown(format!("Zip error: {}", e))
+        InstallerError::Extract(format!("Zip error: {}", e))
     }
 }
 
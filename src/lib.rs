@@ -1,2 +1,10 @@
+//! Install Geode into Geometry Dash running under Steam/Proton or Wine. The CLI is a thin
+//! layer over [`GeodeInstaller`]; launchers can drive it the same way.
+
 pub mod utils;
 pub mod errors;
+
+pub use errors::InstallerError;
+pub use utils::geode_installer::{GeodeInstaller, InstallOptions, InstallationPaths};
+pub use utils::observer::{InstallObserver, InstallStep};
+pub use utils::steam_game_finder::{GameInfo, SteamGameFinder};
@@ -1,20 +1,68 @@
+use clap::Parser;
 use colored::*;
+use log::{info, warn};
+use serde::Serialize;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::SystemTime;
 
-mod utils;
-mod errors;
+mod cli;
 
-use errors::InstallerError;
-use utils::geode_installer::GeodeInstaller;
+use cli::{Cli, Command};
+use geode_cli_installer::errors::InstallerError;
+use geode_cli_installer::utils::geode_installer::{GeodeInstaller, InstallOptions, InstallTarget, RemovalReport, TargetResult, TargetStatus, UpdateOutcome, UpdateStatus, UpdateSummary};
+use geode_cli_installer::utils::desktop_entry::DesktopEntryStatus;
+use geode_cli_installer::utils::diagnostics::{Check, CheckStatus};
+use geode_cli_installer::utils::geode_mods::{ModInfo, ModReader};
+use geode_cli_installer::utils::launch_options::{LaunchOptionsEditor, RECOMMENDED_LAUNCH_OPTIONS};
+use geode_cli_installer::utils::logger::Logger;
+use geode_cli_installer::utils::release_notes::ReleaseNotes;
+use geode_cli_installer::utils::wine_games::WineGame;
+
+/// How many invalid menu entries in a row before showing the error
+const MENU_ATTEMPTS: u32 = 3;
 
 enum MenuChoice {
     InstallToSteam,
     InstallToWine,
+    Uninstall,
+    SetLaunchOptions,
+    Rescan,
+    CheckVersion,
     Quit,
 }
 
+/// Whether an install goes ahead, with the message to show at the end either way
+enum InstallPlan {
+    Install(String),
+    Skip(String),
+}
+
+/// What `--json` prints
+#[derive(Serialize)]
+struct JsonReport {
+    success: bool,
+    /// Geode version in the game directory afterwards
+    version: Option<String>,
+    game_path: Option<PathBuf>,
+    prefix_path: Option<PathBuf>,
+    error: Option<InstallerError>,
+}
+
+impl JsonReport {
+    fn failed(error: InstallerError) -> Self {
+        Self { success: false, version: None, game_path: None, prefix_path: None, error: Some(error) }
+    }
+
+    fn print(&self) {
+        match serde_json::to_string(self) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("{}", InstallerError::from(e).format()),
+        }
+    }
+}
+
 struct UserInterface;
 
 impl UserInterface {
@@ -34,6 +82,10 @@ impl UserInterface {
         println!();
         println!("{} Install to {}", "1.".blue().bold(), "Steam".blue());
         println!("{} Install to {} prefix", "2.".magenta().bold(), "Wine".magenta());
+        println!("{} Uninstall Geode", "3.".red().bold());
+        println!("{} Set Steam launch options", "4.".cyan().bold());
+        println!("{} Rescan Steam installation", "5.".white().bold());
+        println!("{} Check installed Geode version", "6.".green().bold());
         println!("{} Quit", "0.".red().bold());
         println!();
     }
@@ -50,21 +102,215 @@ impl UserInterface {
         input.trim().to_string()
     }
 
+    /// Asks again on a typo, giving up after `MENU_ATTEMPTS` invalid entries
     fn read_menu_choice() -> Result<MenuChoice, InstallerError> {
-        let input = Self::read_input("What do you want to do: ");
+        let mut input = Self::read_input("What do you want to do: ");
+        let mut attempts = 1;
+        loop {
+            match Self::parse_menu_choice(&input) {
+                Err(e) if attempts >= MENU_ATTEMPTS => return Err(e),
+                Err(_) => {
+                    attempts += 1;
+                    input = Self::read_input("Please enter a number from 0 to 6: ");
+                }
+                choice => return choice,
+            }
+        }
+    }
+
+    fn parse_menu_choice(input: &str) -> Result<MenuChoice, InstallerError> {
         let n: i32 = input.parse().map_err(|_| InstallerError::NotANumber)?;
 
         match n {
             1 => Ok(MenuChoice::InstallToSteam),
             2 => Ok(MenuChoice::InstallToWine),
+            3 => Ok(MenuChoice::Uninstall),
+            4 => Ok(MenuChoice::SetLaunchOptions),
+            5 => Ok(MenuChoice::Rescan),
+            6 => Ok(MenuChoice::CheckVersion),
             0 => Ok(MenuChoice::Quit),
             _ => Err(InstallerError::InvalidNumber),
         }
     }
 
-    fn print_success() {
+    fn print_success(message: &str) {
+        println!();
+        println!("{}", format!("✅ {}", message).green().bold());
+        println!();
+        Self::read_input("Press Enter to continue...");
+    }
+
+    /// Like `print_success`, without waiting for Enter. Hidden by --quiet
+    fn print_done(message: &str) {
+        info!("{}", format!("✅ {}", message).green().bold());
+    }
+
+    fn confirm(prompt: &str) -> bool {
+        let input = Self::read_input(&format!("{} [y/N]: ", prompt));
+        matches!(input.to_lowercase().as_str(), "y" | "yes")
+    }
+
+    /// Stronger than `confirm`: the user has to type `word` exactly
+    fn confirm_typed(prompt: &str, word: &str) -> bool {
+        Self::read_input(&format!("{} Type '{}' to continue: ", prompt, word)) == word
+    }
+
+    /// "5 minutes ago", "3 days ago"
+    fn format_age(time: SystemTime) -> String {
+        let secs = SystemTime::now().duration_since(time).unwrap_or_default().as_secs();
+        let (n, unit) = match secs {
+            0..60 => return "just now".into(),
+            60..3600 => (secs / 60, "minute"),
+            3600..86400 => (secs / 3600, "hour"),
+            _ => (secs / 86400, "day"),
+        };
+        format!("{} {}{} ago", n, unit, if n == 1 { "" } else { "s" })
+    }
+
+    fn print_mods(game_dir: &Path, mods: &[ModInfo]) {
+        println!("{} {:?}", "📦 Mods installed in".white().bold(), ModReader::mods_dir(game_dir));
+        println!();
+
+        if mods.is_empty() {
+            println!("{}", "No mods installed.".yellow());
+            return;
+        }
+
+        let id_width = mods.iter().map(|m| m.id.len()).max().unwrap_or(0);
+        let name_width = mods.iter().map(|m| m.name.len()).max().unwrap_or(0);
+        for info in mods {
+            println!(
+                "{:<id_width$}  {:<name_width$}  {}",
+                info.id.cyan(),
+                info.name,
+                info.version.green(),
+            );
+        }
+    }
+
+    fn print_checks(title: &str, checks: &[Check]) {
+        println!("{}", title.white().bold());
+        println!();
+
+        for check in checks {
+            let icon = match check.status {
+                CheckStatus::Ok => "✅",
+                CheckStatus::Warning => "⚠️ ",
+                CheckStatus::Failed => "❌",
+            };
+            println!("{} {}: {}", icon, check.name.bold(), check.detail);
+        }
+    }
+
+    fn print_batch_results(results: &[TargetResult]) {
+        println!();
+        println!("{}", "📋 Batch results".white().bold());
         println!();
-        println!("{}", "✅ Geode has been successfully installed!".green().bold());
+
+        for result in results {
+            let status = match &result.status {
+                TargetStatus::Installed => "installed".green().bold().to_string(),
+                TargetStatus::Failed(e) => format!("{} ({})", "failed".red().bold(), e),
+                TargetStatus::Skipped => "skipped".yellow().bold().to_string(),
+            };
+            println!("{:?} [prefix {:?}]: {}", result.target.game_dir, result.target.prefix, status);
+        }
+    }
+
+    fn print_status(status: &UpdateStatus) {
+        let installed = match &status.installed {
+            Some(installed) => installed.to_string(),
+            None => "not installed".to_string(),
+        };
+        println!("{} {}", "Installed:".white().bold(), installed);
+        println!("{} {}", "Latest:   ".white().bold(), status.latest.tag);
+        println!();
+
+        if status.installed.is_none() {
+            println!("{}", "Geode is not installed.".yellow().bold());
+        } else if status.update_available() {
+            println!("{}", "⬆️  Update available!".yellow().bold());
+        } else {
+            println!("{}", "✅ Geode is up to date.".green().bold());
+        }
+    }
+
+    /// Stable single-line format for scripts, don't change the field order
+    fn print_status_porcelain(status: &UpdateStatus) {
+        let installed = status.installed.as_ref().map_or("-", |installed| installed.version.as_str());
+        println!("{}\t{}\t{}", installed, status.latest.tag, status.update_available());
+    }
+
+    fn print_changelog(status: &UpdateStatus, releases: &[ReleaseNotes]) {
+        if releases.is_empty() {
+            println!("{}", "✅ No new releases since the installed version.".green().bold());
+            return;
+        }
+
+        if status.installed.is_none() {
+            println!("{}", "Geode is not installed, showing the latest release only.".yellow());
+            println!();
+        }
+
+        for release in releases {
+            let date = release.published_at.as_deref().and_then(|date| date.get(..10)).unwrap_or("");
+            println!("{} {}", release.name.cyan().bold(), date.dimmed());
+            println!("{}", "─".repeat(40).dimmed());
+            Self::print_markdown(&release.body);
+            println!();
+        }
+    }
+
+    /// Just enough Markdown rendering for GitHub release notes
+    fn print_markdown(text: &str) {
+        for line in text.lines() {
+            let trimmed = line.trim_end().replace("**", "");
+            let indent = trimmed.len() - trimmed.trim_start().len();
+            let content = trimmed.trim_start();
+
+            if let Some(heading) = content.strip_prefix('#') {
+                println!("{}", heading.trim_start_matches('#').trim().white().bold());
+            } else if let Some(item) = content.strip_prefix("- ").or_else(|| content.strip_prefix("* ")) {
+                println!("{}  • {}", " ".repeat(indent), item);
+            } else {
+                println!("{}", trimmed);
+            }
+        }
+    }
+
+    fn print_update_summary(summary: &[UpdateSummary]) {
+        let name_width = summary.iter().map(|row| row.name.chars().count()).max().unwrap_or(0).max(4);
+        println!("{}", format!("{:<name_width$}  {:<12}  {:<12}  Result", "Name", "From", "To").white().bold());
+
+        for row in summary {
+            let outcome = match &row.outcome {
+                UpdateOutcome::Updated => "updated".green().bold().to_string(),
+                UpdateOutcome::UpToDate => "up to date".dimmed().to_string(),
+                UpdateOutcome::Skipped(reason) => format!("{} ({})", "skipped".yellow().bold(), reason),
+                UpdateOutcome::Failed(e) => format!("{} ({})", "failed".red().bold(), e),
+            };
+            let to = row.to.as_deref().unwrap_or("-");
+            println!("{:<name_width$}  {:<12}  {:<12}  {}", row.name, row.from, to, outcome);
+        }
+    }
+
+    fn print_removal_report(report: &RemovalReport) {
+        if report.removed.is_empty() && report.registry.is_empty() {
+            println!("{}", "Nothing to remove, the game directory is already vanilla.".green().bold());
+            return;
+        }
+
+        println!("{}", "🗑️  Removed:".white().bold());
+        for path in &report.removed {
+            println!("  {:?}", path);
+        }
+        for registry in &report.registry {
+            println!("  DLL override in {:?}", registry);
+        }
+    }
+
+    fn print_warning(message: &str) {
+        warn!("{}", message);
     }
 
     fn print_error(message: &InstallerError) {
@@ -77,42 +323,473 @@ impl UserInterface {
 
 struct InstallationHandler {
     installer: GeodeInstaller,
+    /// Answer yes to every confirmation prompt (`--yes`)
+    assume_yes: bool,
+    /// Release picked with `--version`, skips the version prompt
+    pinned_version: Option<String>,
+    /// `--dry-run`: installs only report what they would do
+    dry_run: bool,
 }
 
 impl InstallationHandler {
-    fn new() -> Result<Self, InstallerError> {
+    fn new(options: InstallOptions, assume_yes: bool) -> Result<Self, InstallerError> {
         Ok(Self {
-            installer: GeodeInstaller::new()?,
+            pinned_version: options.version.clone(),
+            dry_run: options.dry_run,
+            installer: GeodeInstaller::with_options(options)?,
+            assume_yes,
         })
     }
 
-    fn handle_steam_installation(&self) -> Result<(), InstallerError> {
-        println!("{}", "🎮 Installing to Steam...".blue().bold());
-        self.installer.install_to_steam()
+    fn confirm(&self, prompt: &str) -> bool {
+        self.assume_yes || UserInterface::confirm(prompt)
     }
 
-    fn handle_wine_installation(&self) -> Result<(), InstallerError> {
-        println!("{}", "🍷 Wine Installation".magenta().bold());
+    fn confirm_typed(&self, prompt: &str, word: &str) -> bool {
+        self.assume_yes || UserInterface::confirm_typed(prompt, word)
+    }
 
-        let game_path = UserInterface::read_input("Enter your Geometry Dash path: ");
-        let wine_prefix = UserInterface::read_input("Enter your Wine prefix path: ");
+    /// Ask which release to install, unless --version already picked one
+    fn prompt_version(&mut self) -> Result<(), InstallerError> {
+        if self.pinned_version.is_some() {
+            return Ok(());
+        }
+        let tag = UserInterface::read_input("Geode version to install (e.g. v4.2.0, leave empty for latest): ");
+        self.installer.set_version((!tag.is_empty()).then_some(tag))
+    }
 
-        self.installer.install_to_wine(
-            Path::new(&wine_prefix),
-            Path::new(&game_path),
-        )
+    /// Ask which copy to install to when Steam has more than one
+    fn choose_game(&mut self) -> Result<(), InstallerError> {
+        let installs = self.installer.game_installs();
+        if installs.len() < 2 || self.assume_yes {
+            return Ok(());
+        }
+
+        println!("Found {} copies of Geometry Dash:", installs.len());
+        for (i, info) in installs.iter().enumerate() {
+            let prefix = if info.proton_prefix.is_some() { "" } else { " (no Proton prefix)" };
+            println!("{}. {:?}{}", i + 1, info.game_path, prefix);
+        }
+        let input = UserInterface::read_input("Which one do you want to install to: ");
+        let n: usize = input.parse().map_err(|_| InstallerError::NotANumber)?;
+        let info = n.checked_sub(1).and_then(|i| installs.get(i)).ok_or(InstallerError::InvalidNumber)?;
+
+        self.installer.select_game(info.game_path.clone());
+        Ok(())
     }
 
-    fn execute(&self, choice: MenuChoice) -> Result<(), InstallerError> {
+    /// Ask which Proton prefix to patch when there's more than one, the most recently used by default
+    fn choose_prefix(&mut self) -> Result<(), InstallerError> {
+        let prefixes = self.installer.prefix_candidates();
+        if prefixes.len() < 2 || self.assume_yes {
+            return Ok(());
+        }
+
+        println!("Found {} Proton prefixes:", prefixes.len());
+        for (i, prefix) in prefixes.iter().enumerate() {
+            let age = prefix.modified.map(UserInterface::format_age).unwrap_or_else(|| "unknown".into());
+            println!("{}. {:?} (last used {})", i + 1, prefix.path, age);
+        }
+        let input = UserInterface::read_input("Which one do you want to patch [1]: ");
+        let n: usize = if input.is_empty() { 1 } else { input.parse().map_err(|_| InstallerError::NotANumber)? };
+        let prefix = n.checked_sub(1).and_then(|i| prefixes.get(i)).ok_or(InstallerError::InvalidNumber)?;
+
+        self.installer.select_prefix(prefix.path.clone());
+        Ok(())
+    }
+
+    /// Compare what's in `game_dir` with the release about to be installed, asking before
+    /// replacing a different version
+    fn check_existing_install(&self, game_dir: &Path) -> Result<InstallPlan, InstallerError> {
+        if self.dry_run {
+            return Ok(InstallPlan::Install("Dry run finished, nothing was changed".into()));
+        }
+        let status = self.installer.install_status(game_dir)?;
+        let Some(installed) = &status.installed else {
+            return Ok(InstallPlan::Install(format!("Geode {} has been successfully installed!", status.latest.tag)));
+        };
+
+        if status.up_to_date() {
+            return Ok(InstallPlan::Skip(format!("Geode {} is already installed and up to date", installed.version)));
+        }
+        let prompt = if status.update_available() {
+            format!("Update Geode from {} to {}?", installed.version, status.latest.tag)
+        } else {
+            format!("Geode {} is installed, replace it with {}?", installed.version, status.latest.tag)
+        };
+        if !self.confirm(&prompt) {
+            return Err(InstallerError::Installation("Installation cancelled".into()));
+        }
+        Ok(InstallPlan::Install(format!("Geode has been updated from {} to {}!", installed.version, status.latest.tag)))
+    }
+
+    fn handle_steam_installation(&self) -> Result<String, InstallerError> {
+        info!("{}", "🎮 Installing to Steam...".blue().bold());
+        let game_dir = self.installer.detect_game_dir()?;
+        match self.check_existing_install(&game_dir)? {
+            InstallPlan::Install(message) => {
+                self.installer.install_to_steam()?;
+                Ok(message)
+            }
+            InstallPlan::Skip(message) => Ok(message),
+        }
+    }
+
+    fn handle_wine_installation(&mut self) -> Result<String, InstallerError> {
+        info!("{}", "🍷 Wine Installation".magenta().bold());
+        self.prompt_version()?;
+
+        let (wine_prefix, game_path) = match self.choose_wine_game()? {
+            Some(game) => (game.prefix, game.game_dir),
+            None => {
+                let game_path = self.installer.expand_path(&UserInterface::read_input("Enter your Geometry Dash path: "))?;
+                let wine_prefix = self.installer.expand_path(&UserInterface::read_input("Enter your Wine prefix path: "))?;
+                (wine_prefix, game_path)
+            }
+        };
+
+        self.install_wine(&wine_prefix, &game_path)
+    }
+
+    /// Offer the copies launchers know about, `None` to type the paths in instead
+    fn choose_wine_game(&self) -> Result<Option<WineGame>, InstallerError> {
+        let mut games = self.installer.wine_games();
+        if games.is_empty() {
+            return Ok(None);
+        }
+
+        println!("Found Geometry Dash in these Wine prefixes:");
+        for (i, game) in games.iter().enumerate() {
+            println!("{}. {} ({}): {:?}", i + 1, game.name, game.launcher, game.prefix);
+        }
+        println!("0. Enter the paths myself");
+        let input = UserInterface::read_input("Which one do you want to install to: ");
+        let n: usize = input.parse().map_err(|_| InstallerError::NotANumber)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        if n > games.len() {
+            return Err(InstallerError::InvalidNumber);
+        }
+        Ok(Some(games.swap_remove(n - 1)))
+    }
+
+    fn handle_wine_command(&self, prefix: &Path, game_dir: &Path) -> Result<String, InstallerError> {
+        info!("{}", "🍷 Wine Installation".magenta().bold());
+        self.install_wine(prefix, game_dir)
+    }
+
+    fn install_wine(&self, prefix: &Path, game_dir: &Path) -> Result<String, InstallerError> {
+        match self.check_existing_install(game_dir)? {
+            InstallPlan::Install(message) => {
+                self.installer.install_to_wine(prefix, game_dir)?;
+                Ok(message)
+            }
+            InstallPlan::Skip(message) => Ok(message),
+        }
+    }
+
+    fn handle_launch_options(&self) -> Result<(), InstallerError> {
+        println!("{}", "🚀 Steam Launch Options".cyan().bold());
+        println!();
+        println!("Recommended launch options for Geometry Dash:");
+        println!("    {}", RECOMMENDED_LAUNCH_OPTIONS.bold());
+        println!();
+
+        let current = self.installer.current_launch_options();
+        for (config, options) in &current {
+            let options = options.as_deref().unwrap_or_default();
+            println!("{:?}", config);
+            println!("    now:   {}", if options.is_empty() { "(none)" } else { options });
+            println!("    after: {}", LaunchOptionsEditor::merge(options).bold());
+        }
+        if !current.is_empty() {
+            println!();
+        }
+
+        if !self.confirm("Write them to Steam's config automatically?") {
+            println!("Paste the line above into Geometry Dash → Properties → Launch Options in Steam.");
+            return Ok(());
+        }
+
+        let changed = self.installer.set_launch_options()?;
+        if changed.is_empty() {
+            info!("Launch options were already set.");
+        }
+        Ok(())
+    }
+
+    fn resolve_game_dir(&self, game_dir: Option<PathBuf>) -> Result<PathBuf, InstallerError> {
+        match game_dir {
+            Some(dir) => Ok(dir),
+            None => self.installer.detect_game_dir(),
+        }
+    }
+
+    fn handle_list_mods(&self, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        let (mods, errors) = ModReader::list_installed(&game_dir)?;
+
+        UserInterface::print_mods(&game_dir, &mods);
+        for error in errors {
+            UserInterface::print_warning(&error.to_string());
+        }
+        Ok(())
+    }
+
+    fn handle_doctor(&self, prefix: Option<PathBuf>, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let checks = self.installer.diagnose(prefix.as_deref(), game_dir.as_deref());
+        UserInterface::print_checks("🩺 Geode installer diagnostics", &checks);
+        Ok(())
+    }
+
+    fn handle_verify(&self, prefix: Option<PathBuf>, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        let prefix = match prefix {
+            Some(prefix) => prefix,
+            None => self.installer.detect_prefix()?,
+        };
+
+        let report = self.installer.verify_install(&prefix, &game_dir);
+        UserInterface::print_checks("🔎 Geode install verification", &report.checks);
+        if !report.passed() {
+            return Err(InstallerError::Installation("Geode isn't installed correctly, install it again".into()));
+        }
+        Ok(())
+    }
+
+    fn handle_status(&self, game_dir: Option<PathBuf>, porcelain: bool) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        let status = self.installer.status(&game_dir)?;
+
+        if porcelain {
+            UserInterface::print_status_porcelain(&status);
+        } else {
+            UserInterface::print_status(&status);
+        }
+        Ok(())
+    }
+
+    /// The menu's version check: the Steam copy if there is one, otherwise a path to type in
+    fn handle_check_version(&self) -> Result<(), InstallerError> {
+        let game_dir = match self.installer.detect_game_dir() {
+            Ok(game_dir) => game_dir,
+            Err(_) => self.installer.expand_path(&UserInterface::read_input("Enter your Geometry Dash path: "))?,
+        };
+        println!("{} {:?}", "Game:     ".white().bold(), game_dir);
+        UserInterface::print_status(&self.installer.status(&game_dir)?);
+        Ok(())
+    }
+
+    fn handle_changelog(&self, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        let status = self.installer.status(&game_dir)?;
+        let releases = self.installer.changelog(&status)?;
+        UserInterface::print_changelog(&status, &releases);
+        Ok(())
+    }
+
+    /// Explicit paths go to the Wine uninstall, otherwise Steam's copy is used
+    fn handle_uninstall(&self, prefix: Option<PathBuf>, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        info!("{}", "🧹 Uninstalling Geode...".red().bold());
+        let target = match &game_dir {
+            Some(game_dir) => format!("{:?}", game_dir),
+            None => "Steam's Geometry Dash".to_string(),
+        };
+        if !self.confirm(&format!("Remove Geode and all installed mods from {}?", target)) {
+            return Err(InstallerError::Installation("Uninstall cancelled".into()));
+        }
+
+        let report = match (prefix, game_dir) {
+            (Some(prefix), Some(game_dir)) => self.installer.uninstall_from_wine(&prefix, &game_dir)?,
+            (None, None) => self.installer.uninstall_from_steam()?,
+            _ => return Err(InstallerError::Installation("Uninstalling from Wine needs both --prefix and --game-dir".into())),
+        };
+        UserInterface::print_removal_report(&report);
+        Ok(())
+    }
+
+    fn handle_rollback(&self, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        if !self.confirm(&format!("Restore {:?} to how it was before the last install?", game_dir)) {
+            info!("Cancelled.");
+            return Ok(());
+        }
+
+        let backup = self.installer.rollback(&game_dir)?;
+        info!("Restored {} file(s) from {:?}", backup.files.len(), backup.dir);
+        for registry in &backup.registry {
+            info!("Restored {:?}", registry);
+        }
+        UserInterface::print_done("Rolled back the last install!");
+        Ok(())
+    }
+
+    fn handle_restore_registry(&self, prefix: Option<PathBuf>) -> Result<(), InstallerError> {
+        let (user_reg, backup) = self.installer.restore_registry(prefix.as_deref())?;
+        UserInterface::print_done(&format!("Restored {:?} from {:?}", user_reg, backup));
+        Ok(())
+    }
+
+    fn handle_reset(&self, prefix: Option<PathBuf>, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        let mod_count = ModReader::list_installed(&game_dir).map(|(mods, _)| mods.len()).unwrap_or(0);
+
+        UserInterface::print_warning(&format!(
+            "This permanently deletes Geode from {:?}, including {} installed mod(s) and all mod settings and data.",
+            game_dir, mod_count
+        ));
+        if !self.confirm_typed("This cannot be undone.", "reset") {
+            info!("Cancelled.");
+            return Ok(());
+        }
+
+        let report = self.installer.reset(prefix.as_deref(), &game_dir)?;
+        UserInterface::print_removal_report(&report);
+        Ok(())
+    }
+
+    fn handle_update_all(&self, game_dir: Option<PathBuf>) -> Result<(), InstallerError> {
+        let game_dir = self.resolve_game_dir(game_dir)?;
+        if !self.confirm(&format!("Update Geode and all mods in {:?}?", game_dir)) {
+            return Ok(());
+        }
+
+        let summary = self.installer.update_all(&game_dir)?;
+        println!();
+        UserInterface::print_update_summary(&summary);
+
+        if summary.iter().any(|row| matches!(row.outcome, UpdateOutcome::Failed(_))) {
+            return Err(InstallerError::Installation("Some updates failed".into()));
+        }
+        Ok(())
+    }
+
+    fn handle_batch(&self, targets: &[InstallTarget], keep_going: bool) -> Result<(), InstallerError> {
+        info!("{}", format!("📦 Installing to {} targets...", targets.len()).blue().bold());
+        let results = self.installer.install_batch(targets, keep_going)?;
+        UserInterface::print_batch_results(&results);
+
+        let failed = results.iter().filter(|r| matches!(r.status, TargetStatus::Failed(_))).count();
+        if failed > 0 {
+            return Err(InstallerError::Installation(format!("{} of {} targets failed", failed, results.len())));
+        }
+        Ok(())
+    }
+
+    fn handle_install_all(&self, keep_going: bool) -> Result<(), InstallerError> {
+        let targets = self.installer.find_steam_targets()?;
+        self.handle_batch(&targets, keep_going)
+    }
+
+    fn handle_desktop_entry(&self) -> Result<(), InstallerError> {
+        let (path, status) = self.installer.create_desktop_entry()?;
+        let message = match status {
+            DesktopEntryStatus::Created => "Created desktop entry",
+            DesktopEntryStatus::Updated => "Updated desktop entry",
+            DesktopEntryStatus::Unchanged => "Desktop entry is already up to date",
+        };
+        info!("{} {:?}", format!("✅ {}", message).green().bold(), path);
+        Ok(())
+    }
+
+    fn handle_clear_cache(&self) -> Result<(), InstallerError> {
+        let (dir, removed) = self.installer.clear_cache()?;
+        info!("{} {:?}", format!("✅ Removed {} cached file(s) from", removed).green().bold(), dir);
+        Ok(())
+    }
+
+    fn handle_check_update(&self) -> Result<(), InstallerError> {
+        let update = self.installer.check_installer_update()?;
+        if update.update_available() {
+            info!("{}", format!("⬆️  Geode Installer {} is out, you have {}", update.latest, update.current).yellow().bold());
+            info!("Download it from {}", update.url);
+        } else {
+            info!("{}", format!("✅ Geode Installer {} is the latest version.", update.current).green().bold());
+        }
+        Ok(())
+    }
+
+    fn run_command(&mut self, command: Command) -> Result<(), InstallerError> {
+        match command {
+            Command::InstallSteam => {
+                UserInterface::print_done(&self.handle_steam_installation()?);
+                Ok(())
+            }
+            Command::InstallWine { prefix, game_dir } => {
+                UserInterface::print_done(&self.handle_wine_command(&prefix, &game_dir)?);
+                Ok(())
+            }
+            Command::ListMods { game_dir } => self.handle_list_mods(game_dir),
+            Command::Doctor { prefix, game_dir } => self.handle_doctor(prefix, game_dir),
+            Command::Verify { prefix, game_dir } => self.handle_verify(prefix, game_dir),
+            Command::Status { game_dir, porcelain } => self.handle_status(game_dir, porcelain),
+            Command::Changelog { game_dir } => self.handle_changelog(game_dir),
+            Command::Uninstall { prefix, game_dir } => self.handle_uninstall(prefix, game_dir),
+            Command::Rollback { game_dir } => self.handle_rollback(game_dir),
+            Command::RestoreRegistry { prefix } => self.handle_restore_registry(prefix),
+            Command::Reset { prefix, game_dir } => self.handle_reset(prefix, game_dir),
+            Command::UpdateAll { game_dir } => self.handle_update_all(game_dir),
+            Command::Batch { targets, keep_going } => self.handle_batch(&targets, keep_going),
+            Command::InstallAll { keep_going } => self.handle_install_all(keep_going),
+            Command::ClearCache => self.handle_clear_cache(),
+            Command::CheckUpdate => self.handle_check_update(),
+            Command::CreateDesktopEntry => self.handle_desktop_entry(),
+        }
+    }
+
+    /// Run an install command for `--json`, collecting where it went and how it ended
+    fn run_json(&mut self, command: Command) -> JsonReport {
+        let (game_path, prefix_path) = match &command {
+            Command::InstallWine { prefix, game_dir } => (Some(game_dir.clone()), Some(prefix.clone())),
+            _ => (self.installer.detect_game_dir().ok(), self.installer.detect_prefix().ok()),
+        };
+        let result = self.run_command(command);
+        let version = game_path
+            .as_deref()
+            .and_then(|game_dir| self.installer.detect_installed_version(game_dir).ok().flatten())
+            .map(|installed| installed.version);
+
+        JsonReport { success: result.is_ok(), version, game_path, prefix_path, error: result.err() }
+    }
+
+    fn handle_rescan(&mut self) -> Result<(), InstallerError> {
+        info!("{}", "🔍 Rescanning Steam installation...".white().bold());
+        self.installer.rescan()?;
+
+        match self.installer.steam_root() {
+            Some(steam_root) => info!("Steam root found at: {:?}", steam_root),
+            None => UserInterface::print_warning("Steam installation not found"),
+        }
+        match self.installer.detect_game_dir() {
+            Ok(game_dir) => info!("App {} found at: {:?}", self.installer.app_id(), game_dir),
+            Err(_) => UserInterface::print_warning(&format!("App {} not found in any Steam library", self.installer.app_id())),
+        }
+        Ok(())
+    }
+
+    /// Returns the message to show when the action succeeded
+    fn execute(&mut self, choice: &MenuChoice) -> Result<String, InstallerError> {
         match choice {
-            MenuChoice::InstallToSteam => Ok(self.handle_steam_installation()?),
-            MenuChoice::InstallToWine => Ok(self.handle_wine_installation()?),
-            MenuChoice::Quit => Ok(()),
+            MenuChoice::InstallToSteam => {
+                self.choose_game()?;
+                self.choose_prefix()?;
+                self.prompt_version()?;
+                self.handle_steam_installation()
+            }
+            MenuChoice::InstallToWine => self.handle_wine_installation(),
+            MenuChoice::Uninstall => self.handle_uninstall(None, None).map(|_| "Geode has been uninstalled!".into()),
+            MenuChoice::SetLaunchOptions => self.handle_launch_options().map(|_| "Launch options are set!".into()),
+            MenuChoice::Rescan => self.handle_rescan().map(|_| "Steam installation rescanned!".into()),
+            MenuChoice::CheckVersion => self.handle_check_version().map(|_| "Version check done, nothing was changed.".into()),
+            MenuChoice::Quit => Ok(String::new()),
         }
     }
 }
 
-fn run_interactive_loop(handler: &InstallationHandler) {
+/// The handler and its Steam detection results live for the whole session; only
+/// the rescan entry refreshes them
+fn run_interactive_loop(handler: &mut InstallationHandler) {
     loop {
         UserInterface::clear_screen();
         UserInterface::print_header();
@@ -123,8 +800,8 @@ fn run_interactive_loop(handler: &InstallationHandler) {
                 println!("{}", "👋 Exiting...".yellow().bold());
                 break;
             }
-            Ok(choice) => match handler.execute(choice) {
-                Ok(_) => UserInterface::print_success(),
+            Ok(choice) => match handler.execute(&choice) {
+                Ok(message) => UserInterface::print_success(&message),
                 Err(e) => UserInterface::print_error(&e),
             },
             Err(e) => UserInterface::print_error(&e),
@@ -133,11 +810,34 @@ fn run_interactive_loop(handler: &InstallationHandler) {
 }
 
 fn main() {
-    let handler = InstallationHandler::new().map_err(|e| InstallerError::Init(e.to_string()))
+    let cli = Cli::parse();
+    if !cli.color_enabled() || cli.json {
+        colored::control::set_override(false);
+    }
+    Logger::init(cli.log_level());
+
+    let mut handler = InstallationHandler::new(cli.install_options(), cli.yes || cli.json).map_err(|e| InstallerError::Init(e.to_string()))
         .unwrap_or_else(|err| {
-            eprintln!("{}", err.format());
+            if cli.json {
+                JsonReport::failed(err).print();
+            } else {
+                eprintln!("{}", err.format());
+            }
             process::exit(1);
         });
 
-    run_interactive_loop(&handler);
+    if let Some(command) = cli.command() {
+        if cli.json {
+            let report = handler.run_json(command);
+            report.print();
+            process::exit(if report.success { 0 } else { 1 });
+        }
+        if let Err(e) = handler.run_command(command) {
+            eprintln!("{}", e.format());
+            process::exit(1);
+        }
+        return;
+    }
+
+    run_interactive_loop(&mut handler);
 }
@@ -0,0 +1,127 @@
+use crate::errors::InstallerError;
+use crate::utils::download_cache::DownloadCache;
+use reqwest::header::{HeaderMap, CACHE_CONTROL, ETAG};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a response is reused, unless the API asks for less
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+const API_CACHE_DIR: &str = "api";
+
+/// A Geode API response as kept on disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    pub body: String,
+    /// Unix time it was fetched
+    pub fetched_at: u64,
+    /// Seconds it stays fresh
+    pub max_age: u64,
+    /// Sent back as `If-None-Match` once it's stale, the API then answers 304 if it's still current
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+impl CachedResponse {
+    /// None when the response says not to store it
+    pub fn new(url: &str, body: String, headers: &HeaderMap) -> Option<Self> {
+        Some(Self {
+            url: url.to_string(),
+            body,
+            fetched_at: now(),
+            max_age: Self::ttl(headers)?.as_secs(),
+            etag: Self::etag(headers),
+        })
+    }
+
+    /// The same response confirmed current by a 304, fresh again from now
+    pub fn revalidated(self, headers: &HeaderMap) -> Option<Self> {
+        Some(Self {
+            fetched_at: now(),
+            max_age: Self::ttl(headers)?.as_secs(),
+            etag: Self::etag(headers).or(self.etag),
+            ..self
+        })
+    }
+
+    fn etag(headers: &HeaderMap) -> Option<String> {
+        headers.get(ETAG).and_then(|value| value.to_str().ok()).map(String::from)
+    }
+
+    /// `max-age` from Cache-Control, at most `DEFAULT_TTL` so new releases still show up within
+    /// the hour. `no-cache` means checking every time, `no-store` not keeping it at all
+    pub fn ttl(headers: &HeaderMap) -> Option<Duration> {
+        let Some(cache_control) = headers.get(CACHE_CONTROL).and_then(|value| value.to_str().ok()) else {
+            return Some(DEFAULT_TTL);
+        };
+
+        let mut ttl = DEFAULT_TTL;
+        for directive in cache_control.split(',').map(|directive| directive.trim().to_ascii_lowercase()) {
+            match directive.split_once('=') {
+                _ if directive == "no-store" => return None,
+                _ if directive == "no-cache" => ttl = Duration::ZERO,
+                Some(("max-age", seconds)) => {
+                    if let Ok(seconds) = seconds.trim_matches('"').parse() {
+                        ttl = ttl.min(Duration::from_secs(seconds));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(ttl)
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        now().saturating_sub(self.fetched_at) < self.max_age
+    }
+}
+
+/// Geode API responses kept in `api/` of the download cache directory, so installing to
+/// several prefixes in a row (or running again right away) asks the API only once
+pub struct ApiCache {
+    dir: PathBuf,
+}
+
+impl ApiCache {
+    pub fn new(home: &Path) -> Self {
+        Self::at(DownloadCache::new(home).dir().join(API_CACHE_DIR))
+    }
+
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The stored response for `url`, fresh or not
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let content = fs::read_to_string(self.path(url)).ok()?;
+        serde_json::from_str::<CachedResponse>(&content)
+            .ok()
+            .filter(|cached| cached.url == url)
+    }
+
+    pub fn put(&self, response: &CachedResponse) -> Result<(), InstallerError> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(&response.url), serde_json::to_string(response)?)?;
+        Ok(())
+    }
+
+    /// One file per URL, named after it
+    fn path(&self, url: &str) -> PathBuf {
+        let name: String = url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,320 @@
+use crate::errors::InstallerError;
+use crate::utils::observer::{InstallObserver, InstallStep};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+use zip::ZipArchive;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_EMPTY_MAGIC: &[u8] = b"PK\x05\x06";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const SEVEN_ZIP_MAGIC: &[u8] = b"7z\xbc\xaf\x27\x1c";
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZip,
+}
+
+impl ArchiveFormat {
+    /// Detect the format from the file's magic bytes, ignoring its extension
+    pub fn detect(path: &Path) -> Result<Self, InstallerError> {
+        let mut header = Vec::with_capacity(TAR_MAGIC_OFFSET + TAR_MAGIC.len());
+        File::open(path)?
+            .take((TAR_MAGIC_OFFSET + TAR_MAGIC.len()) as u64)
+            .read_to_end(&mut header)?;
+
+        Self::from_magic(&header)
+            .ok_or_else(|| InstallerError::Extract(format!("{:?} is not a zip, tar or 7z archive", path)))
+    }
+
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(ZIP_MAGIC) || header.starts_with(ZIP_EMPTY_MAGIC) {
+            Some(Self::Zip)
+        } else if header.starts_with(GZIP_MAGIC) {
+            Some(Self::TarGz)
+        } else if header.starts_with(SEVEN_ZIP_MAGIC) {
+            Some(Self::SevenZip)
+        } else if header.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()) == Some(TAR_MAGIC) {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// A release archive in any supported format
+pub struct Archive {
+    path: PathBuf,
+    format: ArchiveFormat,
+}
+
+impl Archive {
+    pub fn open(path: &Path) -> Result<Self, InstallerError> {
+        let format = ArchiveFormat::detect(path)?;
+        if format == ArchiveFormat::SevenZip {
+            return Err(InstallerError::Extract(format!(
+                "{:?} is a 7z archive, which isn't supported yet; extract it manually",
+                path
+            )));
+        }
+        Ok(Self { path: path.to_path_buf(), format })
+    }
+
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// Paths of the regular files in the archive, skipping any that would land outside
+    /// the destination when extracted
+    pub fn files(&self) -> Result<Vec<PathBuf>, InstallerError> {
+        match self.format {
+            ArchiveFormat::Zip => {
+                let mut archive = ZipArchive::new(File::open(&self.path)?)?;
+                let mut files = Vec::new();
+                for i in 0..archive.len() {
+                    let file = archive.by_index(i)?;
+                    if let Some(relative) = file.enclosed_name().filter(|_| file.is_file()) {
+                        files.push(relative);
+                    }
+                }
+                Ok(files)
+            }
+            ArchiveFormat::Tar => Self::tar_files(File::open(&self.path)?),
+            ArchiveFormat::TarGz => Self::tar_files(GzDecoder::new(File::open(&self.path)?)),
+            ArchiveFormat::SevenZip => unreachable!("rejected in Archive::open"),
+        }
+    }
+
+    /// Total size of the files once extracted
+    pub fn unpacked_size(&self) -> Result<u64, InstallerError> {
+        match self.format {
+            ArchiveFormat::Zip => {
+                let mut archive = ZipArchive::new(File::open(&self.path)?)?;
+                let mut total = 0;
+                for i in 0..archive.len() {
+                    total += archive.by_index_raw(i)?.size();
+                }
+                Ok(total)
+            }
+            ArchiveFormat::Tar => Self::tar_size(File::open(&self.path)?),
+            ArchiveFormat::TarGz => Self::tar_size(GzDecoder::new(File::open(&self.path)?)),
+            ArchiveFormat::SevenZip => unreachable!("rejected in Archive::open"),
+        }
+    }
+
+    /// Extract every entry `keep` accepts (it gets the entry's path inside the archive).
+    /// Entries that would land outside `destination` are skipped. Returns the files
+    /// written, relative to `destination`.
+    pub fn extract(
+        &self,
+        destination: &Path,
+        observer: &dyn InstallObserver,
+        mut keep: impl FnMut(&str) -> bool,
+    ) -> Result<Vec<PathBuf>, InstallerError> {
+        fs::create_dir_all(destination)?;
+        observer.on_step(InstallStep::Extract);
+        let extracted = self.extract_entries(destination, observer, &mut keep);
+        observer.on_step_done(InstallStep::Extract);
+        extracted.map_err(|e| match e {
+            InstallerError::Io(message) => InstallerError::Extract(message),
+            e => e,
+        })
+    }
+
+    fn extract_entries(
+        &self,
+        destination: &Path,
+        observer: &dyn InstallObserver,
+        keep: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Vec<PathBuf>, InstallerError> {
+        match self.format {
+            ArchiveFormat::Zip => self.extract_zip(destination, observer, keep),
+            ArchiveFormat::Tar => {
+                let total = Self::tar_entry_count(File::open(&self.path)?)?;
+                Self::extract_tar(File::open(&self.path)?, destination, observer, total, keep)
+            }
+            ArchiveFormat::TarGz => {
+                let total = Self::tar_entry_count(GzDecoder::new(File::open(&self.path)?))?;
+                Self::extract_tar(GzDecoder::new(File::open(&self.path)?), destination, observer, total, keep)
+            }
+            ArchiveFormat::SevenZip => unreachable!("rejected in Archive::open"),
+        }
+    }
+
+    fn extract_zip(
+        &self,
+        destination: &Path,
+        observer: &dyn InstallObserver,
+        keep: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Vec<PathBuf>, InstallerError> {
+        let mut archive = ZipArchive::new(File::open(&self.path)?)?;
+        let mut written = Vec::new();
+        let total = archive.len() as u64;
+        let destination = destination.canonicalize()?;
+
+        for i in 0..archive.len() {
+            observer.on_progress(i as u64 + 1, total);
+            let mut file = archive.by_index(i)?;
+            if !keep(file.name()) {
+                continue;
+            }
+            let Some(relative) = Self::enclosed_path(file.name()).filter(|_| file.enclosed_name().is_some()) else {
+                observer.on_warning(&format!("skipping {:?}, its path is absolute or contains '..'", file.name()));
+                continue;
+            };
+            let out_path = destination.join(&relative);
+            if !Self::stays_within(&destination, &out_path)? {
+                observer.on_warning(&format!("skipping {:?}, it would be extracted outside {:?}", file.name(), destination));
+                continue;
+            }
+
+            if file.is_symlink() {
+                let mut target = String::new();
+                file.read_to_string(&mut target)?;
+                if !Self::link_stays_within(&destination, &relative, Path::new(&target)) {
+                    observer.on_warning(&format!("skipping symlink {:?}, it points outside the archive ({:?})", file.name(), target));
+                    continue;
+                }
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                if out_path.symlink_metadata().is_ok() {
+                    fs::remove_file(&out_path)?;
+                }
+                std::os::unix::fs::symlink(&target, &out_path)?;
+                written.push(relative);
+                // Permissions would apply to the link target, so stop here
+                continue;
+            } else if file.name().ends_with('/') {
+                fs::create_dir_all(&out_path)?;
+            } else {
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut out_file = File::create(&out_path)?;
+                io::copy(&mut file, &mut out_file)?;
+                written.push(relative);
+            }
+
+            // Preserve Unix permissions if available
+            if let Some(mode) = file.unix_mode() {
+                fs::set_permissions(&out_path, fs::Permissions::from_mode(mode))?;
+            }
+        }
+        Ok(written)
+    }
+
+    /// `name` as a relative path, or `None` if it is absolute or climbs up with `..`
+    fn enclosed_path(name: &str) -> Option<PathBuf> {
+        let path = Path::new(name);
+        let relative = path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        relative.then(|| path.components().collect())
+    }
+
+    /// Resolve symlinks in the part of `path` that already exists and check it is still
+    /// under `destination` (which must be canonical)
+    fn stays_within(destination: &Path, path: &Path) -> io::Result<bool> {
+        let Some(existing) = path.ancestors().find(|ancestor| ancestor.symlink_metadata().is_ok()) else {
+            return Ok(false);
+        };
+        match existing.canonicalize() {
+            Ok(resolved) => Ok(resolved.starts_with(destination)),
+            // A dangling symlink can't be checked, so don't write through it
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Whether a link at `relative` pointing to `target` resolves inside `destination`.
+    /// Every step is checked, so `..` can't climb back out through an existing symlink.
+    fn link_stays_within(destination: &Path, relative: &Path, target: &Path) -> bool {
+        let mut resolved = relative.parent().map(Path::to_path_buf).unwrap_or_default();
+        for component in target.components() {
+            match component {
+                Component::Normal(part) => resolved.push(part),
+                Component::CurDir => {}
+                Component::ParentDir if resolved.pop() => {}
+                _ => return false,
+            }
+            if !Self::stays_within(destination, &destination.join(&resolved)).unwrap_or(false) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn extract_tar<R: Read>(
+        reader: R,
+        destination: &Path,
+        observer: &dyn InstallObserver,
+        total: u64,
+        keep: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Vec<PathBuf>, InstallerError> {
+        let mut archive = tar::Archive::new(reader);
+        let mut written = Vec::new();
+        let canonical = destination.canonicalize()?;
+
+        for (i, entry) in archive.entries()?.enumerate() {
+            observer.on_progress(i as u64 + 1, total);
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            if !keep(&name) {
+                continue;
+            }
+            let entry_type = entry.header().entry_type();
+            // unpack_in refuses paths that escape the destination, but not links that point out of it
+            if entry_type.is_symlink() {
+                let target = entry.link_name()?.map(|target| target.into_owned()).unwrap_or_default();
+                let inside = Self::enclosed_path(&name)
+                    .is_some_and(|relative| Self::link_stays_within(&canonical, &relative, &target));
+                if !inside {
+                    observer.on_warning(&format!("skipping symlink {:?}, it points outside the archive ({:?})", name, target));
+                    continue;
+                }
+            }
+            if entry.unpack_in(destination)? && (entry_type.is_file() || entry_type.is_symlink()) {
+                written.push(PathBuf::from(name));
+            }
+        }
+        Ok(written)
+    }
+
+    fn tar_size<R: Read>(reader: R) -> Result<u64, InstallerError> {
+        let mut total = 0;
+        for entry in tar::Archive::new(reader).entries()? {
+            total += entry?.size();
+        }
+        Ok(total)
+    }
+
+    /// Tar has no index, so this reads through the whole archive once
+    fn tar_entry_count<R: Read>(reader: R) -> Result<u64, InstallerError> {
+        let mut count = 0;
+        for entry in tar::Archive::new(reader).entries()? {
+            entry?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn tar_files<R: Read>(reader: R) -> Result<Vec<PathBuf>, InstallerError> {
+        let mut archive = tar::Archive::new(reader);
+        let mut files = Vec::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let path = entry.path()?.into_owned();
+            let enclosed = path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if entry.header().entry_type().is_file() && enclosed {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::errors::InstallerError;
+use crate::utils::fs_utils::atomic_write;
+use crate::utils::install_manifest::InstallManifest;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Backups live in `<game dir>/.geode_backup/<timestamp>/`
+pub const BACKUP_DIR: &str = ".geode_backup";
+const METADATA_FILE: &str = ".backup.json";
+/// How many install backups are kept per game directory, like `REGISTRY_BACKUPS_KEPT`
+pub const BACKUPS_KEPT: usize = 5;
+
+/// Copies of the game files an install overwrote or deleted, at their original relative paths,
+/// plus the prefix's registry files from before the install patched them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    #[serde(skip)]
+    pub dir: PathBuf,
+    pub files: Vec<PathBuf>,
+    /// Where the saved registry files came from
+    #[serde(default)]
+    pub registry: Vec<PathBuf>,
+}
+
+impl Backup {
+    /// Copy those of `files` (relative to `game_dir`) that exist into a new timestamped backup,
+    /// then delete all but the newest `BACKUPS_KEPT`
+    pub fn create(game_dir: &Path, files: &[PathBuf]) -> Result<Self, InstallerError> {
+        let mut backup = Self {
+            dir: Self::new_dir(game_dir)?,
+            files: Vec::new(),
+            registry: Vec::new(),
+        };
+
+        for file in files.iter().filter(|file| game_dir.join(file).is_file()) {
+            let target = backup.dir.join(file);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(game_dir.join(file), &target)?;
+            backup.files.push(file.clone());
+        }
+
+        backup.save()?;
+
+        let backups = Self::list(game_dir)?;
+        for old in &backups[..backups.len().saturating_sub(BACKUPS_KEPT)] {
+            fs::remove_dir_all(old)?;
+        }
+        Ok(backup)
+    }
+
+    /// The most recent backup of `game_dir`, if any
+    pub fn latest(game_dir: &Path) -> Result<Option<Self>, InstallerError> {
+        match Self::list(game_dir)?.pop() {
+            Some(dir) => Self::load(&dir).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Backup directories of `game_dir`, oldest first
+    pub fn list(game_dir: &Path) -> Result<Vec<PathBuf>, InstallerError> {
+        let entries = match fs::read_dir(game_dir.join(BACKUP_DIR)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut backups: Vec<(u128, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| Some((entry.file_name().to_str()?.parse::<u128>().ok()?, entry.path())))
+            .filter(|(_, dir)| dir.join(METADATA_FILE).is_file())
+            .collect();
+        backups.sort();
+        Ok(backups.into_iter().map(|(_, dir)| dir).collect())
+    }
+
+    /// Keep a copy of a registry file before it gets patched. Only the first call for each
+    /// file counts, that's the state from before the install.
+    pub fn save_registry(&mut self, path: &Path) -> Result<(), InstallerError> {
+        if self.registry.iter().any(|saved| saved == path) {
+            return Ok(());
+        }
+        fs::copy(path, self.registry_copy(path))?;
+        self.registry.push(path.to_path_buf());
+        self.save()
+    }
+
+    /// Copy everything back over `game_dir` and the registry files, then delete the backup so
+    /// the next rollback goes one install further back. Every saved file is checked first, so a damaged
+    /// backup fails without restoring anything. Files the newer install added, which the backup
+    /// has no copy of, are deleted.
+    pub fn restore(&self, game_dir: &Path) -> Result<(), InstallerError> {
+        if let Some(missing) = self.files.iter().find(|file| !self.dir.join(file).is_file()) {
+            return Err(InstallerError::Installation(format!(
+                "Backup {:?} is incomplete ({:?} is missing), nothing was restored",
+                self.dir, missing
+            )));
+        }
+        if let Some(missing) = self.registry.iter().find(|path| !self.registry_copy(path).is_file()) {
+            return Err(InstallerError::Installation(format!(
+                "Backup {:?} is missing its copy of {:?}, nothing was restored",
+                self.dir, missing
+            )));
+        }
+
+        if let Some(mut manifest) = InstallManifest::load(game_dir)? {
+            manifest.files.retain(|file| !self.files.contains(file));
+            manifest.remove_files(game_dir)?;
+            // Put back below if there was one before, otherwise it would list files that are gone
+            fs::remove_file(InstallManifest::path(game_dir))?;
+        }
+        for file in &self.files {
+            let target = game_dir.join(file);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(self.dir.join(file), &target)?;
+        }
+        for path in &self.registry {
+            atomic_write(path, &fs::read(self.registry_copy(path))?)?;
+        }
+
+        fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+
+    /// `.user.reg`, `.system.reg`: hidden so they can't clash with game files
+    fn registry_copy(&self, path: &Path) -> PathBuf {
+        let mut name = OsString::from(".");
+        name.push(path.file_name().unwrap_or_default());
+        self.dir.join(name)
+    }
+
+    fn load(dir: &Path) -> Result<Self, InstallerError> {
+        let mut backup: Self = serde_json::from_str(&fs::read_to_string(dir.join(METADATA_FILE))?)?;
+        backup.dir = dir.to_path_buf();
+        Ok(backup)
+    }
+
+    fn save(&self) -> Result<(), InstallerError> {
+        fs::write(self.dir.join(METADATA_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Milliseconds since the epoch, so backup directories sort by age
+    fn new_dir(game_dir: &Path) -> Result<PathBuf, InstallerError> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| InstallerError::Unknown(e.to_string()))?
+            .as_millis();
+        let dir = game_dir.join(BACKUP_DIR).join(millis.to_string());
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::utils::fs_utils::find_file_case_insensitive;
+use crate::utils::wine_games::{Launcher, WineGame, GD_EXECUTABLE};
+use crate::utils::yaml::YamlParser;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bottles directories, relative to home: the Flatpak (the usual install) and native packages
+const BOTTLES_DIRS: &[&str] = &[
+    ".var/app/com.usebottles.bottles/data/bottles/bottles",
+    ".local/share/bottles/bottles",
+];
+/// Deep enough for `drive_c/Program Files (x86)/Steam/steamapps/common/Geometry Dash`
+const SEARCH_DEPTH: usize = 6;
+
+/// Bottles environments: each bottle is a Wine prefix with its config in `bottle.yml`
+pub struct Bottles;
+
+impl Bottles {
+    /// Bottles with a Geometry Dash executable in them
+    pub fn find_games(home: &Path) -> Vec<WineGame> {
+        let mut games: Vec<WineGame> = BOTTLES_DIRS
+            .iter()
+            .filter_map(|dir| fs::read_dir(home.join(dir)).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|bottle| bottle.join("drive_c").is_dir())
+            .filter_map(|bottle| Self::game_in(&bottle))
+            .collect();
+        games.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+        games
+    }
+
+    /// The game in one bottle: a program added to it in Bottles, else a search of `drive_c`
+    pub fn game_in(bottle: &Path) -> Option<WineGame> {
+        let config = fs::read_to_string(bottle.join("bottle.yml"))
+            .map(|content| YamlParser::parse(&content))
+            .unwrap_or_default();
+
+        let added = config
+            .iter()
+            .filter(|(key, _)| key.starts_with("External_Programs.") && key.ends_with(".path"))
+            .map(|(_, path)| PathBuf::from(path))
+            .find(|path| path.file_name().is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(GD_EXECUTABLE)));
+        let exe = added.or_else(|| Self::search(&bottle.join("drive_c"), SEARCH_DEPTH))?;
+
+        Some(WineGame {
+            launcher: Launcher::Bottles,
+            name: config
+                .get("Name")
+                .cloned()
+                .unwrap_or_else(|| bottle.file_name().unwrap_or_default().to_string_lossy().into_owned()),
+            game_dir: exe.parent()?.to_path_buf(),
+            prefix: bottle.to_path_buf(),
+        })
+    }
+
+    /// Breadth-first so a copy near the top wins, without descending into Wine's own files
+    fn search(dir: &Path, depth: usize) -> Option<PathBuf> {
+        let mut level = vec![dir.to_path_buf()];
+        for _ in 0..=depth {
+            if let Some(exe) = level.iter().find_map(|dir| find_file_case_insensitive(dir, GD_EXECUTABLE)) {
+                return Some(exe);
+            }
+            level = level
+                .iter()
+                .filter_map(|dir| fs::read_dir(dir).ok())
+                .flatten()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                .filter(|entry| !entry.file_name().to_string_lossy().eq_ignore_ascii_case("windows"))
+                .map(|entry| entry.path())
+                .collect();
+        }
+        None
+    }
+}
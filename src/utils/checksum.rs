@@ -0,0 +1,62 @@
+use crate::errors::InstallerError;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// SHA-256 computed while a download streams to disk
+#[derive(Default)]
+pub struct Checksum {
+    hasher: Sha256,
+}
+
+impl Checksum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    /// Lowercase hex digest
+    pub fn finish(self) -> String {
+        self.hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn of_file(path: &Path) -> Result<String, InstallerError> {
+        let mut checksum = Self::new();
+        io::copy(&mut File::open(path)?, &mut checksum)?;
+        Ok(checksum.finish())
+    }
+
+    /// Accept `HEX` or `sha256:HEX` in any case, as GitHub and the CLI may give it
+    pub fn normalize(expected: &str) -> Result<String, InstallerError> {
+        let hex = expected.trim();
+        let hex = hex.strip_prefix("sha256:").unwrap_or(hex).to_ascii_lowercase();
+        if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(InstallerError::Installation(format!("{:?} is not a SHA-256 hash", expected)));
+        }
+        Ok(hex)
+    }
+
+    pub fn verify(expected: &str, got: &str) -> Result<(), InstallerError> {
+        let expected = Self::normalize(expected)?;
+        if expected != got {
+            return Err(InstallerError::ChecksumMismatch { expected, got: got.to_string() });
+        }
+        Ok(())
+    }
+}
+
+/// Lets a file already on disk be hashed with `io::copy`
+impl Write for Checksum {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
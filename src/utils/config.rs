@@ -0,0 +1,153 @@
+use crate::errors::InstallerError;
+use crate::utils::proxy_dll::ProxyDll;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-install config file, read from the game directory
+pub const PER_INSTALL_FILE: &str = ".geode-installer.toml";
+const GLOBAL_DIR: &str = "geode-installer";
+const GLOBAL_FILE: &str = "config.toml";
+const DEFAULT_DLL_OVERRIDE: &str = "native,builtin";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Prerelease,
+}
+
+/// Settings from config files. Every field is optional so files can be layered:
+/// global config (`~/.config/geode-installer/config.toml`) wins over the
+/// per-install `.geode-installer.toml`, which wins over the built-in defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct InstallerConfig {
+    /// Install this release tag instead of the latest one
+    pub version: Option<String>,
+    pub channel: Option<Channel>,
+    /// Archive entries not to extract, as `*`/`?` wildcard patterns
+    pub exclude: Option<Vec<String>>,
+    /// Value written for the DLL override in the Wine registry
+    pub dll_override: Option<String>,
+    /// More DLL overrides to write alongside Geode's, as a `[dll-overrides]` table of `name = "value"`
+    pub dll_overrides: Option<BTreeMap<String, String>>,
+    /// Steam app id of the game to install to; only read from the global config, the
+    /// per-install one is found through it
+    pub app_id: Option<String>,
+}
+
+impl InstallerConfig {
+    /// Read a config file, `None` if it doesn't exist
+    pub fn load(path: &Path) -> Result<Option<Self>, InstallerError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let config: Self = toml::from_str(&content)
+            .map_err(|e| InstallerError::Unknown(format!("Invalid config {:?}: {}", path, e)))?;
+        config
+            .validate()
+            .map_err(|e| InstallerError::Unknown(format!("Invalid config {:?}: {}", path, e)))?;
+        Ok(Some(config))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(value) = &self.dll_override {
+            validate_dll_override("dll-override", value)?;
+        }
+        for (dll, value) in self.dll_overrides.iter().flatten() {
+            validate_dll_override(dll, value)?;
+        }
+        Ok(())
+    }
+
+    pub fn global_path(home: &Path) -> PathBuf {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| home.join(".config"))
+            .join(GLOBAL_DIR)
+            .join(GLOBAL_FILE)
+    }
+
+    pub fn per_install_path(game_dir: &Path) -> PathBuf {
+        game_dir.join(PER_INSTALL_FILE)
+    }
+
+    /// Fill fields unset in `self` from a lower-precedence config
+    pub fn merge(self, lower: Self) -> Self {
+        Self {
+            version: self.version.or(lower.version),
+            channel: self.channel.or(lower.channel),
+            exclude: self.exclude.or(lower.exclude),
+            dll_override: self.dll_override.or(lower.dll_override),
+            dll_overrides: self.dll_overrides.or(lower.dll_overrides),
+            app_id: self.app_id.or(lower.app_id),
+        }
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel.unwrap_or_default()
+    }
+
+    pub fn dll_override(&self) -> &str {
+        self.dll_override.as_deref().unwrap_or(DEFAULT_DLL_OVERRIDE)
+    }
+
+    /// Every `(dll, value)` override to write: Geode's proxy DLL with `dll_override()`, then
+    /// `extra` (from the command line) or else the `[dll-overrides]` table. A later entry for
+    /// the same DLL replaces an earlier one.
+    pub fn dll_overrides(&self, proxy: &str, extra: &[(String, String)]) -> Vec<(String, String)> {
+        let mut overrides = vec![(proxy.to_string(), self.dll_override().to_string())];
+        let extra = if extra.is_empty() {
+            self.dll_overrides.iter().flatten().map(|(dll, value)| (dll.clone(), value.clone())).collect()
+        } else {
+            extra.to_vec()
+        };
+
+        for (dll, value) in extra {
+            match overrides.iter_mut().find(|(existing, _)| existing.eq_ignore_ascii_case(&dll)) {
+                Some(existing) => existing.1 = value,
+                None => overrides.push((dll, value)),
+            }
+        }
+        overrides
+    }
+
+    pub fn is_excluded(&self, entry: &str) -> bool {
+        self.exclude
+            .iter()
+            .flatten()
+            .any(|pattern| wildcard_match(pattern.as_bytes(), entry.as_bytes()))
+    }
+}
+
+/// DLL overrides are written unescaped into `user.reg` and regedit scripts, so the name has
+/// to be a plain file name and the value a comma-separated list of Wine's load orders
+pub fn validate_dll_override(dll: &str, value: &str) -> Result<(), String> {
+    if !ProxyDll::is_valid_name(dll) {
+        return Err(format!("invalid DLL name {:?}, only letters, digits, '_', '.' and '-' are allowed", dll));
+    }
+    if !value.split(',').all(|order| matches!(order, "native" | "builtin" | "disabled" | "")) {
+        return Err(format!("invalid override {:?} for {}, expected native, builtin or disabled separated by commas", value, dll));
+    }
+    Ok(())
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_match(&pattern[1..], text) || (!text.is_empty() && wildcard_match(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => wildcard_match(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
@@ -0,0 +1,88 @@
+use crate::errors::InstallerError;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "geode-installer.desktop";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesktopEntryStatus {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// `.desktop` launcher for the installer, so it can be rerun from the app menu
+pub struct DesktopEntry;
+
+impl DesktopEntry {
+    /// Write the launcher to `$XDG_DATA_HOME/applications` (or `~/.local/share/applications`).
+    /// Running it again only rewrites the file if the executable path changed.
+    pub fn install(home: &Path) -> Result<(PathBuf, DesktopEntryStatus), InstallerError> {
+        let exe = env::current_exe()
+            .and_then(fs::canonicalize)
+            .map_err(|e| InstallerError::Installation(format!("Can't determine the installer's own path: {}", e)))?;
+
+        if exe.starts_with(env::temp_dir()) {
+            return Err(InstallerError::Installation(format!(
+                "The installer is running from a temporary location ({:?}) that will be cleaned up. Move it somewhere permanent first",
+                exe
+            )));
+        }
+
+        let path = Self::applications_dir(home).join(FILE_NAME);
+        let content = Self::render(&exe);
+
+        let status = match fs::read_to_string(&path) {
+            Ok(existing) if existing == content => return Ok((path, DesktopEntryStatus::Unchanged)),
+            Ok(_) => DesktopEntryStatus::Updated,
+            Err(_) => DesktopEntryStatus::Created,
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        Ok((path, status))
+    }
+
+    pub fn render(exe: &Path) -> String {
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Geode Installer\n\
+             Comment=Install and update Geode for Geometry Dash\n\
+             Exec={}\n\
+             Icon=applications-games\n\
+             Terminal=true\n\
+             Categories=Game;Utility;\n",
+            quote_exec_arg(&exe.to_string_lossy())
+        )
+    }
+
+    fn applications_dir(home: &Path) -> PathBuf {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| home.join(".local/share"))
+            .join("applications")
+    }
+}
+
+/// Quote an `Exec=` argument per the Desktop Entry spec
+fn quote_exec_arg(arg: &str) -> String {
+    let needs_quoting = arg.chars().any(|c| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c));
+    if !needs_quoting {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
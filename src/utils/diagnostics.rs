@@ -0,0 +1,82 @@
+use crate::utils::pe::Machine;
+use crate::utils::steam_game_finder::{PrefixArch, SteamGameFinder, MIN_PROTON_MAJOR};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+}
+
+/// One line of `--doctor` output
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn ok(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Ok, detail)
+    }
+
+    pub fn warning(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warning, detail)
+    }
+
+    pub fn failed(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Failed, detail)
+    }
+
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    /// The Proton that set up the prefix, if it's one of Proton's
+    pub fn prefix_proton(version: Option<&str>) -> Self {
+        const NAME: &str = "Prefix Proton";
+
+        match version {
+            Some(version) if SteamGameFinder::is_outdated_proton(version) => Self::failed(
+                NAME,
+                format!("Created with Proton {}, which is known to break Geode. Switch the game to Proton {}.0 or newer", version, MIN_PROTON_MAJOR),
+            ),
+            Some(version) => Self::ok(NAME, version),
+            None => Self::warning(NAME, "No Proton version recorded, the prefix isn't from Proton"),
+        }
+    }
+
+    /// Compare the prefix architecture with the game executable's. Geometry Dash 2.2+
+    /// (and therefore Geode) is 64-bit only, so it needs a win64 prefix.
+    pub fn bitness(prefix_arch: Option<PrefixArch>, game_machine: Option<Machine>) -> Self {
+        const NAME: &str = "Prefix/game bitness";
+
+        match (prefix_arch, game_machine) {
+            (Some(PrefixArch::Win32), Some(machine)) if machine.is_64_bit() => Self::failed(
+                NAME,
+                "The prefix is 32-bit (win32) but Geometry Dash is 64-bit, so it can't run there. \
+                 Recreate the prefix with WINEARCH=win64 (for Proton, delete compatdata/322170 and start the game once)",
+            ),
+            (Some(PrefixArch::Win32), Some(_)) => Self::failed(
+                NAME,
+                "Both the prefix and Geometry Dash are 32-bit, which means a pre-2.2 game version. \
+                 Update Geometry Dash and use a win64 prefix for current Geode",
+            ),
+            (Some(PrefixArch::Win64), Some(machine)) if !machine.is_64_bit() => Self::failed(
+                NAME,
+                format!(
+                    "Geometry Dash is built for {}, current Geode only supports the 64-bit 2.2+ release. Update the game",
+                    machine
+                ),
+            ),
+            (Some(PrefixArch::Win64), Some(_)) => Self::ok(NAME, "64-bit prefix and 64-bit game"),
+            (None, _) => Self::warning(NAME, "Couldn't determine the prefix architecture"),
+            (_, None) => Self::warning(NAME, "Couldn't read the Geometry Dash executable"),
+        }
+    }
+}
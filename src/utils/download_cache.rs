@@ -0,0 +1,56 @@
+use crate::errors::InstallerError;
+use crate::utils::geode_version::ASSET_PLATFORM;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CACHE_DIR: &str = "geode-installer";
+
+/// Release archives kept in `$XDG_CACHE_HOME/geode-installer` (or `~/.cache/geode-installer`),
+/// so reinstalling the same tag doesn't download it again
+pub struct DownloadCache {
+    dir: PathBuf,
+}
+
+impl DownloadCache {
+    pub fn new(home: &Path) -> Self {
+        let dir = env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| home.join(".cache"))
+            .join(CACHE_DIR);
+        Self::at(dir)
+    }
+
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, tag: &str) -> PathBuf {
+        self.dir.join(format!("geode-{}-{}.zip", tag, ASSET_PLATFORM))
+    }
+
+    /// Where a download goes until it's complete, so a partial file is never mistaken for a cached one
+    pub fn partial_path(&self, tag: &str) -> PathBuf {
+        self.dir.join(format!("geode-{}-{}.zip.part", tag, ASSET_PLATFORM))
+    }
+
+    pub fn get(&self, tag: &str) -> Option<PathBuf> {
+        Some(self.path(tag)).filter(|path| path.is_file())
+    }
+
+    /// Delete the cache directory, returns how many files were in it
+    pub fn clear(&self) -> Result<usize, InstallerError> {
+        let count = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries.count(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        fs::remove_dir_all(&self.dir)?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::fs::PermissionsExt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Find a direct child of `dir` by name, ignoring ASCII case (Windows files often vary in casing)
+pub fn find_file_case_insensitive(dir: &Path, name: &str) -> Option<PathBuf> {
+    fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(name))
+        .map(|entry| entry.path())
+}
+
+/// First executable called `name` in a `PATH`-style list of directories
+pub fn find_executable(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|path| fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0))
+}
+
+/// Bytes an unprivileged user can still write on the filesystem holding `path`. A path that
+/// doesn't exist yet is looked up through its nearest existing parent
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    let existing = path.ancestors().find(|dir| dir.exists()).unwrap_or(Path::new("/"));
+    let stat = nix::sys::statvfs::statvfs(existing)?;
+    // The field types are platform-dependent aliases
+    #[allow(clippy::unnecessary_cast)]
+    Ok((stat.blocks_available() as u64).saturating_mul(stat.fragment_size() as u64))
+}
+
+/// Whether `dir` is on a case-insensitive filesystem (NTFS/exFAT/FAT mounts, ciopfs...),
+/// checked by creating a probe file and looking it up with different casing
+pub fn is_case_insensitive(dir: &Path) -> io::Result<bool> {
+    let probe = dir.join(format!(".geode-case-probe-{}", std::process::id()));
+    File::create(&probe)?;
+    let folded = dir.join(format!(".GEODE-CASE-PROBE-{}", std::process::id()));
+    let result = folded.exists();
+    fs::remove_file(&probe)?;
+    Ok(result)
+}
+
+/// Archive paths written so far, to catch entries that would land on the same file
+/// because they differ only in case on a case-insensitive filesystem
+pub struct WrittenPaths {
+    fold_case: bool,
+    seen: HashSet<String>,
+}
+
+impl WrittenPaths {
+    pub fn new(fold_case: bool) -> Self {
+        Self { fold_case, seen: HashSet::new() }
+    }
+
+    /// False if `path` collides with one already written
+    pub fn insert(&mut self, path: &str) -> bool {
+        let key = if self.fold_case { path.to_lowercase() } else { path.to_string() };
+        self.seen.insert(key)
+    }
+}
+
+/// Move everything under `from` into `to`, merging with directories that already exist and
+/// replacing files. Both should be on the same filesystem so each move is a rename.
+pub fn move_tree(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            move_tree(&entry.path(), &target)?;
+        } else {
+            if target.is_dir() && !target.is_symlink() {
+                fs::remove_dir_all(&target)?;
+            }
+            fs::rename(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move a file, copying it when `to` is on another filesystem
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+/// Replace `path` atomically: write a temp file in the same directory, flush it to disk,
+/// then rename it over the original. A crash leaves either the old or the new file, never a
+/// truncated one. The original file's permissions are kept.
+pub fn atomic_write(path: &Path, content: &[u8]) -> io::Result<()> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut temp_name = OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(".geode-tmp");
+    let temp_path = dir.join(temp_name);
+
+    let result = write_and_rename(path, &temp_path, content);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    // Persist the rename itself; not all filesystems support syncing directories
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+fn write_and_rename(path: &Path, temp_path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        fs::set_permissions(temp_path, metadata.permissions())?;
+    }
+
+    fs::rename(temp_path, path).map_err(|e| {
+        io::Error::new(e.kind(), format!("Failed to replace {:?}: {}", path, e))
+    })
+}
@@ -1,52 +1,445 @@
 use crate::errors::InstallerError;
-use crate::utils::steam_game_finder::SteamGameFinder;
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::blocking::Client;
+use crate::utils::api_cache::{ApiCache, CachedResponse};
+use crate::utils::archive::Archive;
+use crate::utils::backup::{Backup, BACKUP_DIR};
+use crate::utils::checksum::Checksum;
+use crate::utils::config::{Channel, InstallerConfig};
+use crate::utils::desktop_entry::{DesktopEntry, DesktopEntryStatus};
+use crate::utils::diagnostics::{Check, CheckStatus};
+use crate::utils::download_cache::DownloadCache;
+use crate::utils::fs_utils::{available_space, find_file_case_insensitive, is_case_insensitive, move_file, move_tree, WrittenPaths};
+use crate::utils::geode_mods::{ModInfo, ModReader};
+use crate::utils::geode_version::{InstalledVersion, LoaderRelease, ASSET_PLATFORM};
+use crate::utils::home::expand_path;
+use crate::utils::install_manifest::{InstallManifest, RegistryChange};
+use crate::utils::launch_options::LaunchOptionsEditor;
+use crate::utils::mod_index::{ModIndex, ModRelease};
+use crate::utils::observer::{InstallObserver, InstallStep, TerminalObserver};
+use crate::utils::pe::PeReader;
+use crate::utils::process::ProcessScanner;
+use crate::utils::proxy_dll::ProxyDll;
+use crate::utils::release_notes::ReleaseNotes;
+use crate::utils::semver::SemVer;
+use crate::utils::steam_deck::NOTES as DECK_NOTES;
+use crate::utils::steam_game_finder::{FinderOptions, GameInfo, PrefixArch, ProtonPrefix, SteamGameFinder, MIN_PROTON_MAJOR};
+use crate::utils::tls::CertificatePins;
+use crate::utils::wine::{Wine, MINIMAL_USER_REG};
+use crate::utils::wine_games::{find_wine_games, WineGame, GD_EXECUTABLE};
+use crate::utils::wine_registry::{DllOverrides, DllOverridesSnapshot, RegistryBackups, RegistryEncoding, RegistryFile, RegistryMethod, RegistryScope, DLL_OVERRIDES_SECTION};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, IF_NONE_MATCH};
+use log::debug;
+use reqwest::Proxy;
 use serde_json::Value;
-use std::fs::{self, File};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Write};
-use std::os::unix::fs::PermissionsExt;
-use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
-use zip::ZipArchive;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
 
 const GD_APP_ID: &str = "322170";
 const GEODE_API_URL: &str = "https://api.geode-sdk.org/v1/loader/versions/latest";
 const GEODE_GITHUB_URL: &str = "https://github.com/geode-sdk/geode/releases/download";
+const USER_AGENT: &str = concat!("geode-cli-installer/", env!("CARGO_PKG_VERSION"));
+/// Token sent with requests to GitHub, for its higher rate limit
+pub const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+/// Release download base URL to use instead of GitHub's, when `--mirror` isn't given
+pub const MIRROR_ENV: &str = "GEODE_MIRROR";
+const GEODE_RELEASES_API_URL: &str = "https://api.github.com/repos/geode-sdk/geode/releases?per_page=100";
+/// This installer's own latest release
+const INSTALLER_RELEASE_API_URL: &str = "https://api.github.com/repos/relativemodder/geode-cli-installer-rs/releases/latest";
+
+/// Files from the Geode release that get loaded into the game process
+#[cfg(not(target_os = "macos"))]
+const GEODE_BINARIES: &[&str] = &["Geode.dll", "xinput1_4.dll"];
+/// The Mac build is a dylib injected into the native game, there's no proxy DLL
+#[cfg(target_os = "macos")]
+const GEODE_BINARIES: &[&str] = &["Geode.dylib"];
+/// Other top-level files a Geode release ships
+const GEODE_EXTRA_FILES: &[&str] = &["Geode.lib", "Geode.pdb", "GeodeUpdater.exe", "geode_temp.zip"];
+/// Loader data directory: mods, their settings and saved data, logs, crashlogs
+const GEODE_DATA_DIR: &str = "geode";
+/// How long to wait before checking that freshly extracted DLLs are still there
+const QUARANTINE_RECHECK_DELAY: Duration = Duration::from_secs(1);
+/// Doubled after every failed attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+pub const DEFAULT_RETRIES: u32 = 3;
+/// Connect timeout, and how long a request may wait for the server to send anything
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct GeodeInstaller {
     finder: SteamGameFinder,
     client: Client,
+    async_client: reqwest::Client,
+    options: InstallOptions,
+    app_id: String,
+    /// Base URL to download release archives from instead of the API's or GitHub's
+    mirror: Option<String>,
+    /// From `GITHUB_TOKEN`
+    github_token: Option<String>,
+    /// Copy of the game picked with `select_game`, instead of the first one found
+    selected_game: Option<PathBuf>,
+    /// Proton prefix picked with `select_prefix`, instead of the one next to the game
+    selected_prefix: Option<PathBuf>,
+    /// Gets all output, a `TerminalObserver` unless replaced with `set_observer`
+    observer: Box<dyn InstallObserver>,
+    /// Latest releases fetched this session, by API URL
+    latest_releases: Mutex<HashMap<String, LoaderRelease>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Parse the PE headers of the extracted DLLs and warn if they aren't 64-bit
+    pub verify_architecture: bool,
+    /// Print extra detail about how paths were detected
+    pub verbose: bool,
+    /// Where to download the release archive; defaults to the game directory
+    /// (batch installs default to the system temp directory)
+    pub temp_dir: Option<PathBuf>,
+    /// Only trust the root CAs the Geode API and GitHub are known to use
+    pub pin_certs: bool,
+    /// Install this release tag (e.g. `v4.2.0`) instead of asking the API for the latest one,
+    /// overrides a version pinned in config
+    pub version: Option<String>,
+    /// Expected SHA-256 of the release archive, overrides the one the API reports
+    pub sha256: Option<String>,
+    /// Install from this release archive instead of downloading one. Its version comes from
+    /// `version`, or from a `geode-<tag>-win.zip` file name
+    pub archive: Option<PathBuf>,
+    /// How many times to try each network request on connection errors, timeouts and
+    /// 5xx responses (always at least once)
+    pub retries: u32,
+    /// Always download the release archive, ignoring and not filling the download cache
+    pub no_cache: bool,
+    /// Ask the API for the latest release even if a cached answer is still fresh
+    pub refresh: bool,
+    /// Don't copy the game files an install overwrites into `.geode_backup` first
+    pub no_backup: bool,
+    /// Install into a game directory even if it has no `GeometryDash.exe`, or while the game is running
+    pub force: bool,
+    /// Print what an install would download, extract and patch without changing anything
+    pub dry_run: bool,
+    /// Skip checking the files and registry override once a Wine install is done
+    pub no_verify: bool,
+    /// Connect and read timeout for network requests, `DEFAULT_TIMEOUT` if unset
+    pub timeout: Option<Duration>,
+    /// Send every request through this proxy instead of the ones in `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY`
+    pub proxy: Option<String>,
+    /// Download release archives from `<mirror>/<tag>/geode-<tag>-win.zip` instead of GitHub,
+    /// overrides `GEODE_MIRROR`
+    pub mirror: Option<String>,
+    /// Which registry files get the DLL override
+    pub registry_scope: RegistryScope,
+    /// `(dll, value)` overrides to write besides Geode's; replaces the config's `[dll-overrides]`
+    pub dll_overrides: Vec<(String, String)>,
+    /// Edit the registry files directly or import the overrides with `wine regedit`
+    pub registry_method: RegistryMethod,
+    /// Steam app id to look for instead of Geometry Dash's, overrides `app-id` in the global config
+    pub app_id: Option<String>,
+    pub finder: FinderOptions,
+}
+
+/// A successful GET, or the server saying the copy we sent the ETag of is still current
+enum Fetched {
+    Body(String, HeaderMap),
+    NotModified(HeaderMap),
+}
+
+/// A downloaded release archive; temporary ones are deleted once installed
+struct FetchedArchive {
+    path: PathBuf,
+    temporary: bool,
+}
+
+/// A prefix + game directory pair for batch installs
+#[derive(Debug, Clone)]
+pub struct InstallTarget {
+    pub prefix: PathBuf,
+    pub game_dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum TargetStatus {
+    Installed,
+    Failed(InstallerError),
+    /// Not attempted because an earlier target failed
+    Skipped,
+}
+
+#[derive(Debug)]
+pub struct TargetResult {
+    pub target: InstallTarget,
+    pub status: TargetStatus,
+}
+
+/// Installed loader compared to the latest release
+#[derive(Debug, Clone)]
+pub struct UpdateStatus {
+    pub installed: Option<InstalledVersion>,
+    pub latest: LoaderRelease,
+}
+
+impl UpdateStatus {
+    /// Only true when Geode is installed and older than the latest release
+    pub fn update_available(&self) -> bool {
+        let Some(installed) = &self.installed else {
+            return false;
+        };
+        match (SemVer::parse(&installed.version), SemVer::parse(&self.latest.tag)) {
+            (Some(installed), Some(latest)) => installed < latest,
+            _ => installed.version != self.latest.tag,
+        }
+    }
+
+    /// True when the installed loader is exactly the latest release
+    pub fn up_to_date(&self) -> bool {
+        let Some(installed) = &self.installed else {
+            return false;
+        };
+        match (SemVer::parse(&installed.version), SemVer::parse(&self.latest.tag)) {
+            (Some(installed), Some(latest)) => installed == latest,
+            _ => installed.version == self.latest.tag,
+        }
+    }
+}
+
+/// This installer's version compared to its latest release on GitHub
+#[derive(Debug, Clone)]
+pub struct InstallerUpdate {
+    pub current: String,
+    pub latest: String,
+    /// Release page of the latest version
+    pub url: String,
+}
+
+impl InstallerUpdate {
+    /// Read a GitHub latest-release response
+    pub fn from_github(json: &Value, current: &str) -> Result<Self, InstallerError> {
+        let latest = json["tag_name"]
+            .as_str()
+            .ok_or_else(|| InstallerError::Unknown("No tag_name in the installer's latest release".into()))?;
+        Ok(Self {
+            current: current.to_string(),
+            latest: latest.to_string(),
+            url: json["html_url"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    pub fn update_available(&self) -> bool {
+        match (SemVer::parse(&self.current), SemVer::parse(&self.latest)) {
+            (Some(current), Some(latest)) => current < latest,
+            _ => false,
+        }
+    }
+}
+
+/// What happened to one component during an update-all run
+#[derive(Debug)]
+pub enum UpdateOutcome {
+    Updated,
+    UpToDate,
+    /// Left alone, with the reason
+    Skipped(String),
+    Failed(InstallerError),
+}
+
+#[derive(Debug)]
+pub struct UpdateSummary {
+    pub name: String,
+    pub from: String,
+    pub to: Option<String>,
+    pub outcome: UpdateOutcome,
+}
+
+/// Everything an uninstall or reset removed
+#[derive(Debug, Default)]
+pub struct RemovalReport {
+    pub removed: Vec<PathBuf>,
+    /// The registry files the DLL override was removed from
+    pub registry: Vec<PathBuf>,
+}
+
+/// What `verify_install` found, one check per thing an install has to leave behind
+#[derive(Debug)]
+pub struct VerifyReport {
+    pub checks: Vec<Check>,
+}
+
+impl VerifyReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Failed)
+    }
 }
 
 #[derive(Debug)]
 pub struct InstallationPaths {
     pub game_path: PathBuf,
     pub proton_prefix: PathBuf,
+    /// How each path was found, for verbose output
+    pub game_via: String,
+    pub prefix_via: String,
 }
 
 impl GeodeInstaller {
     pub fn new() -> Result<Self, InstallerError> {
-        let client = Client::builder()
-            .build()?;
+        Self::with_options(InstallOptions::default())
+    }
+
+    pub fn with_options(options: InstallOptions) -> Result<Self, InstallerError> {
+        let timeout = options.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let tls = if options.pin_certs { Some(CertificatePins::client_config()?) } else { None };
+        let proxy = options.proxy.as_deref().map(Self::parse_proxy).transpose()?;
+
+        // The GitHub API rejects requests without a User-Agent, and both APIs rate-limit anonymous ones harder
+        let mut builder = Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(timeout)
+            .timeout(timeout);
+        // Same settings for the client `install_to_wine_async` uses
+        let mut async_builder = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(timeout)
+            .read_timeout(timeout);
+        if let Some(tls) = tls {
+            builder = builder.use_preconfigured_tls(tls.clone());
+            async_builder = async_builder.use_preconfigured_tls(tls);
+        }
+        // Without an explicit one, reqwest picks proxies up from the environment itself
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.clone());
+            async_builder = async_builder.proxy(proxy);
+        }
+        let client = builder.build()?;
+        let async_client = async_builder.build()?;
+        if let Some(tag) = &options.version {
+            Self::validate_tag(tag)?;
+        }
+        if let Some(sha256) = &options.sha256 {
+            Checksum::normalize(sha256)?;
+        }
+        if let Some(archive) = &options.archive
+            && !archive.is_file()
+        {
+            return Err(InstallerError::InvalidPath { path: archive.display().to_string(), reason: "no such file".into() });
+        }
+
+        let finder = SteamGameFinder::with_options(options.finder.clone())?;
+        let app_id = match &options.app_id {
+            Some(app_id) => app_id.clone(),
+            None => Self::global_config(&finder)?.and_then(|config| config.app_id).unwrap_or_else(|| GD_APP_ID.to_string()),
+        };
+        Self::validate_app_id(&app_id)?;
+        let mirror = options.mirror.clone()
+            .or_else(|| std::env::var(MIRROR_ENV).ok().filter(|url| !url.is_empty()))
+            .map(|mirror| Self::validate_mirror(&mirror))
+            .transpose()?;
 
         Ok(Self {
-            finder: SteamGameFinder::new(),
+            finder,
             client,
+            async_client,
+            options,
+            app_id,
+            mirror,
+            github_token: std::env::var(GITHUB_TOKEN_ENV).ok().filter(|token| !token.is_empty()),
+            selected_game: None,
+            selected_prefix: None,
+            observer: Box::new(TerminalObserver::default()),
+            latest_releases: Mutex::new(HashMap::new()),
         })
     }
 
+    /// An http(s) proxy URL; a bare `host:port` means `http://host:port`
+    pub fn parse_proxy(url: &str) -> Result<Proxy, InstallerError> {
+        let invalid = |reason: &str| InstallerError::InvalidProxy { url: url.to_string(), reason: reason.to_string() };
+        if let Some((scheme, _)) = url.split_once("://")
+            && !scheme.eq_ignore_ascii_case("http")
+            && !scheme.eq_ignore_ascii_case("https")
+        {
+            return Err(invalid("only http:// and https:// proxies are supported"));
+        }
+        Proxy::all(url).map_err(|_| invalid("expected a URL like http://proxy.example:3128"))
+    }
+
+    /// A mirror has to be an http(s) URL; returned without its trailing slash
+    pub fn validate_mirror(url: &str) -> Result<String, InstallerError> {
+        let lower = url.to_ascii_lowercase();
+        let host = lower.strip_prefix("https://").or_else(|| lower.strip_prefix("http://"));
+        if host.is_none_or(|host| host.trim_start_matches('/').is_empty()) {
+            return Err(InstallerError::Init(format!("Mirror {:?} has to be an http:// or https:// URL", url)));
+        }
+        Ok(url.trim_end_matches('/').to_string())
+    }
+
+    /// Steam app ids are plain numbers
+    pub fn validate_app_id(app_id: &str) -> Result<(), InstallerError> {
+        if app_id.is_empty() || !app_id.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(InstallerError::Init(format!("{:?} is not a Steam app id", app_id)));
+        }
+        Ok(())
+    }
+
+    /// The Steam app id the game is looked up by
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
     /// Install Geode to Steam's Geometry Dash installation
     pub fn install_to_steam(&self) -> Result<(), InstallerError> {
-        let steam_root = self.finder.steam_root()
-            .ok_or_else(|| InstallerError::Installation("Can't find Steam installation".into()))?;
+        let steam_root = match self.finder.steam_root() {
+            Some(steam_root) => steam_root,
+            None => {
+                let home = self.finder.home().map_err(|e| InstallerError::Installation(e.to_string()))?;
+                return Err(InstallerError::SteamNotFound(home.path.clone()));
+            }
+        };
+        if self.options.verbose
+            && let Ok(home) = self.finder.home()
+        {
+            self.observer.on_message(&format!("Using home directory {:?} (from {})", home.path, home.source));
+        }
+
+        if self.finder.is_steam_deck() {
+            for note in DECK_NOTES {
+                self.observer.on_message(note);
+            }
+        }
+        self.observer.on_message(&format!("Steam root found at: {:?}", steam_root));
+        if self.options.verbose {
+            self.observer.on_message(&format!("  via {}", self.finder.steam_root_via().unwrap_or("unknown")));
+        }
 
-        println!("Steam root found at: {:?}", steam_root);
+        if cfg!(target_os = "macos") {
+            return self.install_native();
+        }
 
         let paths = self.locate_geometry_dash()?;
 
-        println!("Geometry Dash found at: {:?}", paths.game_path);
-        println!("Proton prefix found at: {:?}", paths.proton_prefix);
+        self.observer.on_message(&format!("Geometry Dash found at: {:?}", paths.game_path));
+        if self.options.verbose {
+            self.observer.on_message(&format!("  via {}", paths.game_via));
+        }
+        if self.selected_prefix.is_some() {
+            self.observer.on_message(&format!("Using selected Proton prefix: {:?}", paths.proton_prefix));
+        } else {
+            self.observer.on_message(&format!("Proton prefix found at: {:?}", paths.proton_prefix));
+        }
+        if self.options.verbose {
+            self.observer.on_message(&format!("  via {}", paths.prefix_via));
+        }
+        if let Some(version) = SteamGameFinder::detect_proton_version(&paths.proton_prefix) {
+            self.observer.on_message(&format!("Prefix created with Proton {}", version));
+            if SteamGameFinder::is_outdated_proton(&version) {
+                self.observer.on_warning(&format!(
+                    "Proton {} is known to break Geode, switch the game to Proton {}.0 or newer in its Steam properties (Compatibility) and launch it once before installing",
+                    version, MIN_PROTON_MAJOR
+                ));
+            }
+        }
 
         self.install_to_wine(&paths.proton_prefix, &paths.game_path)?;
 
@@ -57,244 +450,1864 @@ impl GeodeInstaller {
     pub fn install_to_wine(&self, prefix: &Path, game_dir: &Path) -> Result<(), InstallerError> {
         self.validate_paths(prefix, game_dir)?;
 
-        println!("Installing Geode to: {:?}", game_dir);
-        self.install_to_directory(game_dir)?;
+        let config = self.effective_config(game_dir)?;
+        let release = self.resolve_release(&config)?;
+        if self.options.dry_run {
+            return self.dry_run(prefix, game_dir, &release, &config);
+        }
+
+        self.observer.on_message(&format!("Installing Geode {} to: {:?}", release.tag, game_dir));
+        self.install_to_directory(game_dir, &release, &config)?;
+        self.finish_wine_install(prefix, game_dir, &config)
+    }
+
+    /// The game runs natively on macOS: Geode goes into the game directory and there's no
+    /// Proton prefix or Wine registry to patch
+    fn install_native(&self) -> Result<(), InstallerError> {
+        let game_dir = self.detect_game_dir()?;
+        self.observer.on_message(&format!("Geometry Dash found at: {:?}", game_dir));
 
-        println!("Patching Wine registry...");
-        self.patch_wine_registry(prefix)?;
+        let config = self.effective_config(&game_dir)?;
+        let release = self.resolve_release(&config)?;
+        if self.options.dry_run {
+            self.observer.on_message(&format!("Would install Geode {} to {:?}", release.tag, game_dir));
+            return Ok(());
+        }
 
-        println!("Geode installation completed!");
+        self.observer.on_message(&format!("Installing Geode {} to: {:?}", release.tag, game_dir));
+        self.install_to_directory(&game_dir, &release, &config)?;
+        self.observer.on_message("Geode installation completed!");
         Ok(())
     }
 
+    /// `install_to_wine` for async callers, to be run on a tokio runtime. The release lookup
+    /// and download go through an async client, everything touching the disk runs on the
+    /// blocking pool. The download skips the cache and starts over instead of resuming
+    pub async fn install_to_wine_async(self: Arc<Self>, prefix: PathBuf, game_dir: PathBuf) -> Result<(), InstallerError> {
+        let config = {
+            let (this, prefix, game_dir) = (Arc::clone(&self), prefix.clone(), game_dir.clone());
+            Self::blocking(move || {
+                this.validate_paths(&prefix, &game_dir)?;
+                this.effective_config(&game_dir)
+            })
+            .await?
+        };
+        let release = match (&self.options.archive, self.options.version.as_ref().or(config.version.as_ref())) {
+            (Some(archive), _) => Self::local_release(archive, self.options.version.as_deref())?,
+            (None, Some(tag)) => LoaderRelease {
+                tag: tag.clone(),
+                ..LoaderRelease::default()
+            },
+            (None, None) => self.fetch_latest_release_async(config.channel()).await?,
+        };
+        if self.options.dry_run {
+            let this = Arc::clone(&self);
+            return Self::blocking(move || this.dry_run(&prefix, &game_dir, &release, &config)).await;
+        }
 
-    fn locate_geometry_dash(&self) -> Result<InstallationPaths, InstallerError> {
-        let game_info = self.finder.get_game_info(GD_APP_ID)
-            .ok_or_else(|| InstallerError::Installation("Can't find Geometry Dash installation".into()))?;
-
-        let proton_prefix = game_info.proton_prefix
-            .ok_or_else(|| InstallerError::Installation("Can't find Proton prefix for Geometry Dash".into()))?;
+        self.observer.on_message(&format!("Installing Geode {} to: {:?}", release.tag, game_dir));
+        let archive = {
+            let (this, release, game_dir) = (Arc::clone(&self), release.clone(), game_dir.clone());
+            Self::blocking(move || {
+                this.ensure_game_closed(&game_dir)?;
+                match &this.options.archive {
+                    Some(archive) => {
+                        this.check_local_archive(archive, &release)?;
+                        Ok(FetchedArchive { path: archive.clone(), temporary: false })
+                    }
+                    None => Ok(FetchedArchive { path: this.download_path(&release, &game_dir)?, temporary: true }),
+                }
+            })
+            .await?
+        };
+        if archive.temporary
+            && let Err(e) = self.download_release_async(&release, &archive.path).await
+        {
+            let _ = tokio::fs::remove_file(&archive.path).await;
+            return Err(e);
+        }
 
-        Ok(InstallationPaths {
-            game_path: game_info.game_path,
-            proton_prefix,
+        Self::blocking(move || {
+            let extracted = self.extract_archive(&archive.path, &game_dir, &config);
+            let removed = if archive.temporary { fs::remove_file(&archive.path) } else { Ok(()) };
+            extracted?;
+            removed?;
+            self.check_extracted_files(&game_dir)?;
+            InstalledVersion::write_marker(&game_dir, &release)?;
+            self.finish_wine_install(&prefix, &game_dir, &config)
         })
+        .await
     }
 
-    fn validate_paths(&self, prefix: &Path, game_dir: &Path) -> Result<(), InstallerError> {
-        if !prefix.exists() {
-            return Err(InstallerError::Unknown(format!(
-                "Prefix directory doesn't exist: {:?}",
-                prefix
-            )));
-        }
-        if !game_dir.exists() {
-            return Err(InstallerError::Unknown(format!(
-                "Game directory doesn't exist: {:?}",
-                game_dir
-            )));
-        }
-        Ok(())
+    /// Run `work` on tokio's blocking pool
+    async fn blocking<T: Send + 'static>(
+        work: impl FnOnce() -> Result<T, InstallerError> + Send + 'static,
+    ) -> Result<T, InstallerError> {
+        tokio::task::spawn_blocking(work)
+            .await
+            .map_err(|e| InstallerError::Unknown(format!("Install task failed: {}", e)))?
     }
 
-    fn install_to_directory(&self, destination: &Path) -> Result<(), InstallerError> {
-        let download_url = self.get_download_url()?;
-        println!("Downloading Geode...");
-        self.download_and_extract(&download_url, destination)?;
+    /// Point the prefix at the freshly extracted files and check the result
+    fn finish_wine_install(&self, prefix: &Path, game_dir: &Path, config: &InstallerConfig) -> Result<(), InstallerError> {
+        self.observer.on_step(InstallStep::PatchRegistry);
+        let patched = self.patch_wine_registry(prefix, game_dir, config);
+        self.observer.on_step_done(InstallStep::PatchRegistry);
+        patched?;
+
+        if !self.options.no_verify {
+            let report = self.verify_install(prefix, game_dir);
+            if !report.passed() {
+                let failed: Vec<String> = report.checks
+                    .iter()
+                    .filter(|check| check.status == CheckStatus::Failed)
+                    .map(|check| format!("{}: {}", check.name, check.detail))
+                    .collect();
+                return Err(InstallerError::Installation(format!("The install didn't verify ({})", failed.join("; "))));
+            }
+        }
+
+        self.observer.on_message("Geode installation completed!");
         Ok(())
     }
 
-    fn get_download_url(&self) -> Result<String, InstallerError> {
-        let tag = self.fetch_latest_tag()?;
-        Ok(format!("{}/{}/geode-{}-win.zip", GEODE_GITHUB_URL, tag, tag))
+    /// Check that Geode's files are in `game_dir` and that the prefix's registry loads the
+    /// proxy DLL from there
+    pub fn verify_install(&self, prefix: &Path, game_dir: &Path) -> VerifyReport {
+        let mut checks = Vec::new();
+        let proxy = ProxyDll::detect(game_dir);
+
+        for name in [GEODE_BINARIES[0].to_string(), format!("{}.dll", proxy.name)] {
+            checks.push(match find_file_case_insensitive(game_dir, &name) {
+                Some(path) => Check::ok(&name, format!("{:?}", path)),
+                None => Check::failed(&name, format!("Missing from {:?}", game_dir)),
+            });
+        }
+        let data_dir = game_dir.join(GEODE_DATA_DIR);
+        checks.push(if data_dir.is_dir() {
+            Check::ok("geode folder", format!("{:?}", data_dir))
+        } else {
+            Check::failed("geode folder", format!("{:?} is missing", data_dir))
+        });
+
+        for name in self.options.registry_scope.files() {
+            let path = prefix.join(name);
+            let check_name = format!("DllOverrides in {}", name);
+            let content = match RegistryFile::read(&path) {
+                Ok(registry) => registry.content,
+                Err(e) => {
+                    checks.push(Check::failed(&check_name, format!("Can't read {:?}: {}", path, e)));
+                    continue;
+                }
+            };
+            checks.push(match DllOverrides::get(&content, &proxy.name) {
+                Some(value) if value.starts_with("native") => Check::ok(&check_name, format!("\"{}\"=\"{}\"", proxy.name, value)),
+                Some(value) => Check::failed(
+                    &check_name,
+                    format!("\"{}\" is set to \"{}\", Wine won't load Geode's copy", proxy.name, value),
+                ),
+                None => Check::failed(&check_name, format!("No override for \"{}\"", proxy.name)),
+            });
+        }
+
+        VerifyReport { checks }
     }
 
-    fn fetch_latest_tag(&self) -> Result<String, InstallerError> {
-        let response = self.http_get(GEODE_API_URL)?;
-        let json: Value = serde_json::from_str(&response)?;
+    /// Install to several prefixes, downloading each release only once. Stops at the
+    /// first failure unless `keep_going` is set, in which case every target is attempted.
+    pub fn install_batch(&self, targets: &[InstallTarget], keep_going: bool) -> Result<Vec<TargetResult>, InstallerError> {
+        // Targets can pin different versions in their per-install config
+        let mut archives: HashMap<String, FetchedArchive> = HashMap::new();
+        let mut results = Vec::with_capacity(targets.len());
+        let mut failed = false;
 
-        if let Some(error) = json["error"].as_str() {
-            if !error.is_empty() {
-                return Err(InstallerError::Unknown(format!("Geode API error: {}", error)));
+        for target in targets {
+            let status = if failed && !keep_going {
+                TargetStatus::Skipped
+            } else {
+                match self.install_batch_target(target, &mut archives) {
+                    Ok(()) => TargetStatus::Installed,
+                    Err(e) => {
+                        self.observer.on_message(&format!("Failed to install to {:?}: {}", target.game_dir, e));
+                        failed = true;
+                        TargetStatus::Failed(e)
+                    }
+                }
+            };
+            results.push(TargetResult { target: target.clone(), status });
+        }
+
+        // The installs are done either way, a leftover temp file isn't worth failing the batch over
+        for archive in archives.values().filter(|archive| archive.temporary) {
+            if let Err(e) = fs::remove_file(&archive.path) {
+                self.observer.on_warning(&format!("couldn't delete the downloaded archive {:?}: {}", archive.path, e));
             }
         }
+        Ok(results)
+    }
 
-        json["payload"]["tag"]
-            .as_str()
-            .map(String::from)
-            .ok_or_else(|| InstallerError::Unknown("Failed to extract version tag from API response".into()))
+    fn install_batch_target(&self, target: &InstallTarget, archives: &mut HashMap<String, FetchedArchive>) -> Result<(), InstallerError> {
+        self.validate_paths(&target.prefix, &target.game_dir)?;
+
+        let config = self.effective_config(&target.game_dir)?;
+        let release = self.resolve_release(&config)?;
+        if self.options.dry_run {
+            return self.dry_run(&target.prefix, &target.game_dir, &release, &config);
+        }
+
+        let zip_path = match archives.get(&release.tag) {
+            Some(archive) => archive.path.clone(),
+            None => {
+                let temp_dir = self.options.temp_dir.clone().unwrap_or_else(std::env::temp_dir);
+                fs::create_dir_all(&temp_dir)?;
+                let path = temp_dir.join(format!("geode_temp_{}_{}.zip", std::process::id(), release.tag));
+                let archive = self.fetch_release_archive(&release, &path)?;
+                let zip_path = archive.path.clone();
+                archives.insert(release.tag.clone(), archive);
+                zip_path
+            }
+        };
+
+        self.install_from_archive(&zip_path, &release, &config, &target.prefix, &target.game_dir)
     }
 
-    fn download_and_extract(&self, url: &str, destination: &Path) -> Result<(), InstallerError> {
-        fs::create_dir_all(destination)?;
+    /// A target for every Steam copy of the game. Copies without their own Proton prefix
+    /// (never launched through Proton) are left out with a warning.
+    pub fn find_steam_targets(&self) -> Result<Vec<InstallTarget>, InstallerError> {
+        let installs = self.finder.find_all_game_installs(&self.app_id);
+        if installs.is_empty() {
+            return Err(InstallerError::GameNotFound);
+        }
+
+        let mut targets = Vec::with_capacity(installs.len());
+        for info in installs {
+            match info.proton_prefix {
+                Some(prefix) => targets.push(InstallTarget { prefix, game_dir: info.game_path }),
+                None => self.observer.on_warning(&format!(
+                    "skipping {:?}, it has no Proton prefix (launch it once through Steam first)",
+                    info.game_path
+                )),
+            }
+        }
 
-        let zip_path = destination.join("geode_temp.zip");
+        if targets.is_empty() {
+            return Err(InstallerError::PrefixNotFound);
+        }
+        Ok(targets)
+    }
 
-        self.download_file(url, &zip_path)?;
-        self.extract_zip(&zip_path, destination)?;
+    /// Config for an install: global config over the game directory's `.geode-installer.toml`
+    pub fn effective_config(&self, game_dir: &Path) -> Result<InstallerConfig, InstallerError> {
+        let per_install_path = InstallerConfig::per_install_path(game_dir);
+        let per_install = InstallerConfig::load(&per_install_path)?;
+        if per_install.is_some() && self.options.verbose {
+            self.observer.on_message(&format!("Using per-install config {:?}", per_install_path));
+        }
 
-        fs::remove_file(&zip_path)?;
+        let global = Self::global_config(&self.finder)?;
+        Ok(global.unwrap_or_default().merge(per_install.unwrap_or_default()))
+    }
 
-        Ok(())
+    fn global_config(finder: &SteamGameFinder) -> Result<Option<InstallerConfig>, InstallerError> {
+        match finder.home() {
+            Ok(home) => InstallerConfig::load(&InstallerConfig::global_path(&home.path)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// The localconfig.vdf files `set_launch_options` edits: the active Steam account's, or
+    /// every account's when it can't tell which is active
+    pub fn launch_options_configs(&self) -> Vec<PathBuf> {
+        match self.finder.active_localconfig() {
+            Some(config) => vec![config],
+            None => self.finder.localconfig_files(),
+        }
     }
 
+    /// The game's current launch options in each of `launch_options_configs`
+    pub fn current_launch_options(&self) -> Vec<(PathBuf, Option<String>)> {
+        self.launch_options_configs()
+            .into_iter()
+            .map(|config| {
+                let current = LaunchOptionsEditor::current(&config, &self.app_id).ok().flatten();
+                (config, current)
+            })
+            .collect()
+    }
 
-    fn http_get(&self, url: &str) -> Result<String, InstallerError> {
-        let response = self.client.get(url).send()?;
+    /// Merge the recommended Geometry Dash launch options into the active Steam account's
+    /// localconfig.vdf (every account's if it can't tell). Returns the files that were changed.
+    pub fn set_launch_options(&self) -> Result<Vec<PathBuf>, InstallerError> {
+        if ProcessScanner::is_running("steam") {
+            return Err(InstallerError::Installation(
+                "Steam is running and would overwrite the change on exit. Close Steam and try again".into(),
+            ));
+        }
 
-        if !response.status().is_success() {
-            return Err(InstallerError::Unknown(format!("HTTP error {}", response.status())));
+        let configs = self.launch_options_configs();
+        if configs.is_empty() {
+            return Err(InstallerError::Installation("Can't find Steam's localconfig.vdf".into()));
         }
 
-        Ok(response.text()?)
+        let mut changed = Vec::new();
+        for config in configs {
+            if let Some(backup) = LaunchOptionsEditor::apply(&config, &self.app_id)? {
+                self.observer.on_message(&format!("Updated {:?} (backup at {:?})", config, backup));
+                changed.push(config);
+            }
+        }
+        Ok(changed)
     }
 
+    /// Run environment checks for `--doctor`. Explicit paths take precedence over Steam detection.
+    pub fn diagnose(&self, prefix: Option<&Path>, game_dir: Option<&Path>) -> Vec<Check> {
+        let mut checks = Vec::new();
 
-    fn download_file(&self, url: &str, output: &Path) -> Result<(), InstallerError> {
-        let mut response = self.client.get(url).send()?;
-        if !response.status().is_success() {
-            return Err(InstallerError::Unknown(format!("HTTP error {}", response.status())));
+        match self.finder.steam_root() {
+            Some(root) => checks.push(Check::ok(
+                "Steam root",
+                format!("{:?} (via {})", root, self.finder.steam_root_via().unwrap_or("unknown")),
+            )),
+            None => checks.push(Check::warning("Steam root", "Not found")),
+        }
+        if self.finder.is_steam_deck() {
+            checks.push(Check::ok("Steam Deck", "SteamOS detected, SD card libraries are scanned too"));
         }
 
-        let total_size = response.content_length().unwrap_or(0);
-        let pb = ProgressBar::new(total_size);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                .map_err(|e| InstallerError::Unknown(e.to_string()))?
-                .progress_chars("#>-"),
-        );
-
-        let mut file = File::create(output)?;
-        let mut downloaded = 0u64;
-        let mut buffer = vec![0; 8192];
+        let scan = self.finder.scan(&self.app_id);
+        for warning in scan.warnings {
+            checks.push(Check::warning("Steam scan", warning));
+        }
+        for library in &scan.libraries {
+            let detail = if library.has_game { "has Geometry Dash" } else { "no Geometry Dash" };
+            checks.push(Check::ok("Steam library", format!("{:?} (from {}, {})", library.path, library.source, detail)));
+        }
 
-        loop {
-            let bytes_read = response.read(&mut buffer)?;
-            if bytes_read == 0 {
-                break;
+        let game_info = self.game_info();
+        checks.push(match &game_info {
+            Some(info) if info.manifest_missing => Check::warning(
+                "Steam manifest",
+                format!("appmanifest_{}.acf is missing, Steam doesn't know about {:?}", self.app_id, info.game_path),
+            ),
+            Some(info) => Check::ok(
+                "Steam manifest",
+                format!("appmanifest_{}.acf found in {:?}", self.app_id, info.library_path),
+            ),
+            None => Check::failed("Steam manifest", format!("No library has appmanifest_{}.acf", self.app_id)),
+        });
+        let game_dir = game_dir
+            .map(|dir| (dir.to_path_buf(), "--game-dir".to_string()))
+            .or_else(|| game_info.as_ref().map(|info| (info.game_path.clone(), info.game_via.clone())));
+        let prefix = prefix
+            .map(|prefix| (prefix.to_path_buf(), "--prefix".to_string()))
+            .or_else(|| {
+                let info = game_info?;
+                Some((info.proton_prefix?, info.prefix_via.unwrap_or_default()))
+            });
+
+        match &game_dir {
+            Some((dir, via)) => checks.push(Check::ok("Geometry Dash", format!("{:?} (via {})", dir, via))),
+            None => checks.push(Check::failed("Geometry Dash", "Not found")),
+        }
+        match &prefix {
+            Some((prefix, via)) => checks.push(Check::ok("Wine prefix", format!("{:?} (via {})", prefix, via))),
+            None => checks.push(Check::failed("Wine prefix", "Not found")),
+        }
+        let game_dir = game_dir.map(|(dir, _)| dir);
+        let prefix = prefix.map(|(prefix, _)| prefix);
+
+        if let Some(prefix) = &prefix {
+            checks.push(if prefix.join("user.reg").is_file() {
+                Check::ok("user.reg", format!("{:?}", prefix.join("user.reg")))
+            } else {
+                Check::failed("user.reg", "Missing, the prefix was never started. Launch the game once and try again")
+            });
+        }
+        checks.push(match Wine::find() {
+            Some(wine) => Check::ok("Wine", format!("{:?}", wine.binary())),
+            None => Check::warning("Wine", "Not on PATH, only needed for --registry-method regedit and new prefixes"),
+        });
+        let proton = self.finder.proton_installs();
+        checks.push(if proton.is_empty() {
+            Check::warning("Proton", "No Proton installs found in the Steam libraries")
+        } else {
+            let names: Vec<String> = proton
+                .iter()
+                .map(|path| path.file_name().unwrap_or_default().to_string_lossy().into_owned())
+                .collect();
+            Check::ok("Proton", names.join(", "))
+        });
+
+        if let Some(game_dir) = &game_dir {
+            checks.push(match self.detect_installed_version(game_dir) {
+                Ok(Some(installed)) => Check::ok("Installed Geode", installed.to_string()),
+                Ok(None) => Check::warning("Installed Geode", "Not installed"),
+                Err(e) => Check::warning("Installed Geode", format!("Couldn't read version: {}", e)),
+            });
+        }
+
+        if let (Some(prefix), Some(game_dir)) = (&prefix, &game_dir) {
+            let prefix_arch = SteamGameFinder::detect_prefix_arch(prefix);
+            let game_machine = find_file_case_insensitive(game_dir, GD_EXECUTABLE)
+                .and_then(|exe| PeReader::read_machine(&exe).ok());
+            checks.push(Check::bitness(prefix_arch, game_machine));
+            checks.push(Check::prefix_proton(SteamGameFinder::detect_proton_version(prefix).as_deref()));
+        }
+
+        checks.push(self.check_api());
+        checks
+    }
+
+    /// A single request to the Geode API, without retries so an offline machine answers quickly
+    fn check_api(&self) -> Check {
+        const NAME: &str = "Geode API";
+
+        let started = Instant::now();
+        match self.get(GEODE_API_URL).send() {
+            Ok(response) if response.status().is_success() => {
+                Check::ok(NAME, format!("{} answered in {} ms", GEODE_API_URL, started.elapsed().as_millis()))
             }
-            file.write_all(&buffer[..bytes_read])?;
-            downloaded += bytes_read as u64;
-            pb.set_position(downloaded);
+            Ok(response) => Check::failed(NAME, format!("{} returned {}", GEODE_API_URL, response.status())),
+            Err(e) => Check::failed(NAME, format!("Can't reach {}: {}", GEODE_API_URL, InstallerError::from(e))),
         }
+    }
 
-        pb.finish_with_message("Download complete");
-        Ok(())
+    /// Add a launcher for this installer to the desktop's application menu
+    pub fn create_desktop_entry(&self) -> Result<(PathBuf, DesktopEntryStatus), InstallerError> {
+        let home = self.finder.home().map_err(|e| InstallerError::Installation(e.to_string()))?;
+        DesktopEntry::install(&home.path)
+    }
+
+    /// Compare the installed loader with the latest release on the configured channel
+    pub fn status(&self, game_dir: &Path) -> Result<UpdateStatus, InstallerError> {
+        let config = self.effective_config(game_dir)?;
+        Ok(UpdateStatus {
+            installed: self.detect_installed_version(game_dir)?,
+            latest: self.fetch_latest_release(config.channel())?,
+        })
+    }
+
+    /// Compare the installed loader with the release an install would put in place: the
+    /// pinned version if there is one, otherwise the latest. A Geode.dll whose version can't be
+    /// read counts as no install, a broken one is what reinstalling fixes
+    pub fn install_status(&self, game_dir: &Path) -> Result<UpdateStatus, InstallerError> {
+        let config = self.effective_config(game_dir)?;
+        let installed = self.detect_installed_version(game_dir).unwrap_or_else(|e| {
+            self.observer.on_warning(&format!("couldn't read the installed Geode version ({}), installing over it", e));
+            None
+        });
+        Ok(UpdateStatus {
+            installed,
+            latest: self.resolve_release(&config)?,
+        })
     }
 
-    fn extract_zip(&self, zip_path: &Path, destination: &Path) -> Result<(), InstallerError> {
-        let file = File::open(zip_path)?;
-        let mut archive = ZipArchive::new(file)?;
+    /// Update the loader if a newer release is out, then every installed mod to the latest
+    /// index version compatible with that loader. Mods with no compatible version are skipped.
+    pub fn update_all(&self, game_dir: &Path) -> Result<Vec<UpdateSummary>, InstallerError> {
+        let status = self.status(game_dir)?;
+        let installed = status.installed.as_ref()
+            .ok_or_else(|| InstallerError::Installation("Geode is not installed, install it first".into()))?;
 
-        for i in 0..archive.len() {
-            self.extract_zip_entry(&mut archive, i, destination)?;
+        let mut summary = vec![self.update_loader(game_dir, &status, installed)];
+        let loader_version = match summary[0].outcome {
+            UpdateOutcome::Updated => status.latest.tag.clone(),
+            _ => installed.version.clone(),
+        };
+
+        let (mods, errors) = ModReader::list_installed(game_dir)?;
+        for info in mods {
+            let from = info.version.clone();
+            let (to, outcome) = match self.update_mod(&info, status.latest.gd.as_deref(), &loader_version) {
+                Ok((to, outcome)) => (to, outcome),
+                Err(e) => (None, UpdateOutcome::Failed(e)),
+            };
+            summary.push(UpdateSummary { name: info.name, from, to, outcome });
         }
-        Ok(())
+        summary.extend(errors.into_iter().map(|e| UpdateSummary {
+            name: "(unreadable mod)".into(),
+            from: "-".into(),
+            to: None,
+            outcome: UpdateOutcome::Failed(e),
+        }));
+
+        Ok(summary)
     }
 
-    fn extract_zip_entry(
-        &self,
-        archive: &mut ZipArchive<File>,
-        index: usize,
-        destination: &Path,
-    ) -> Result<(), InstallerError> {
-        let mut file = archive.by_index(index)?;
-        let out_path = match file.enclosed_name() {
-            Some(path) => destination.join(path),
-            None => return Ok(()), // Skip unsafe paths
+    fn update_loader(&self, game_dir: &Path, status: &UpdateStatus, installed: &InstalledVersion) -> UpdateSummary {
+        let mut summary = UpdateSummary {
+            name: "Geode".into(),
+            from: installed.version.clone(),
+            to: None,
+            outcome: UpdateOutcome::UpToDate,
         };
+        if !status.update_available() {
+            return summary;
+        }
 
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&out_path)?;
-        } else {
-            self.extract_file(&mut file, &out_path)?;
+        summary.to = Some(status.latest.tag.clone());
+        summary.outcome = match self.effective_config(game_dir) {
+            Ok(config) if config.version.is_some() => UpdateOutcome::Skipped("version pinned in config".into()),
+            Ok(config) => match self.install_to_directory(game_dir, &status.latest, &config) {
+                Ok(()) => UpdateOutcome::Updated,
+                Err(e) => UpdateOutcome::Failed(e),
+            },
+            Err(e) => UpdateOutcome::Failed(e),
+        };
+        summary
+    }
+
+    fn update_mod(&self, info: &ModInfo, gd: Option<&str>, loader_version: &str) -> Result<(Option<String>, UpdateOutcome), InstallerError> {
+        let Some(release) = self.fetch_mod_release(&info.id, gd, loader_version)? else {
+            let reason = format!("no version for Geode {}", loader_version);
+            return Ok((None, UpdateOutcome::Skipped(reason)));
+        };
+
+        let newer = match (SemVer::parse(&release.version), SemVer::parse(&info.version)) {
+            (Some(latest), Some(installed)) => latest > installed,
+            _ => release.version != info.version,
+        };
+        if !newer {
+            return Ok((None, UpdateOutcome::UpToDate));
         }
 
-        // Preserve Unix permissions if available
-        if let Some(mode) = file.unix_mode() {
-            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode))?;
+        // Download next to the old package and swap it in, so a failed download leaves the mod intact
+        let partial = info.path.with_extension("geode.part");
+        let downloaded = self.download_file(&release.download_link, &partial)
+            .and_then(|sha256| ModIndex::check_package(&release, &partial, &sha256));
+        if let Err(e) = downloaded {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
         }
+        fs::rename(&partial, &info.path)?;
 
-        Ok(())
+        Ok((Some(release.version), UpdateOutcome::Updated))
     }
 
-    fn extract_file(&self, zip_file: &mut dyn Read, out_path: &Path) -> Result<(), InstallerError> {
-        if let Some(parent) = out_path.parent() {
-            fs::create_dir_all(parent)?;
+    /// `None` when the index has no version of the mod compatible with the given versions
+    fn fetch_mod_release(&self, id: &str, gd: Option<&str>, loader_version: &str) -> Result<Option<ModRelease>, InstallerError> {
+        let response = self.send(self.get(&ModIndex::latest_version_url(id, gd, loader_version)))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
         }
-        let mut out_file = File::create(out_path)?;
-        io::copy(zip_file, &mut out_file)?;
-        Ok(())
+        if !response.status().is_success() {
+            return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
+        }
+
+        let json: Value = serde_json::from_str(&response.text()?)?;
+        ModIndex::parse_release(&json).map(Some)
     }
 
-    fn patch_wine_registry(&self, prefix: &Path) -> Result<(), InstallerError> {
-        let user_reg = prefix.join("user.reg");
-        if !user_reg.exists() {
-            return Err(InstallerError::Unknown(format!("Wine registry file not found: {:?}", user_reg)));
+    /// Release notes for every release after the installed one, up to the latest, newest first
+    pub fn changelog(&self, status: &UpdateStatus) -> Result<Vec<ReleaseNotes>, InstallerError> {
+        let response = self.send(self.get(GEODE_RELEASES_API_URL).header(reqwest::header::ACCEPT, "application/vnd.github+json"))?;
+
+        if !response.status().is_success() {
+            return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
         }
 
-        let mut content = fs::read_to_string(&user_reg)?;
-        self.ensure_dll_override(&mut content);
-        fs::write(&user_reg, content)?;
-        Ok(())
+        let json: Value = serde_json::from_str(&response.text()?)?;
+        let installed = status.installed.as_ref().map(|installed| installed.version.as_str());
+        Ok(ReleaseNotes::between(ReleaseNotes::parse_list(&json), installed, &status.latest.tag))
     }
 
-    fn ensure_dll_override(&self, content: &mut String) {
-        const SECTION: &str = "[Software\\\\Wine\\\\DllOverrides]";
-        const ENTRY: &str = "\"xinput1_4\"=\"native,builtin\"";
+    /// Whether a newer version of this installer has been released
+    pub fn check_installer_update(&self) -> Result<InstallerUpdate, InstallerError> {
+        let response = self.send(self.get(INSTALLER_RELEASE_API_URL).header(reqwest::header::ACCEPT, "application/vnd.github+json"))?;
 
-        if content.contains("\"xinput1_4\"=") {
-            return; // Already configured
+        if !response.status().is_success() {
+            return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
         }
 
-        if !content.contains(SECTION) {
-            self.add_dll_overrides_section(content);
-        } else {
-            self.add_dll_entry_to_section(content, SECTION, ENTRY);
+        let json: Value = serde_json::from_str(&response.text()?)?;
+        InstallerUpdate::from_github(&json, env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Version of the Geode loader installed in `game_dir`, if any
+    pub fn detect_installed_version(&self, game_dir: &Path) -> Result<Option<InstalledVersion>, InstallerError> {
+        InstalledVersion::detect(game_dir)
+    }
+
+    /// Remove Geode from `game_dir`: the loader files, the proxy DLL and the whole `geode/`
+    /// directory (mods and their data included), then the DLL override from the prefix's
+    /// user.reg (putting back the DllOverrides section saved at install, when there is one).
+    /// Files that are already gone are skipped, so running it twice is harmless.
+    pub fn uninstall_from_wine(&self, prefix: &Path, game_dir: &Path) -> Result<RemovalReport, InstallerError> {
+        self.remove_geode(Some(prefix), game_dir)
+    }
+
+    /// Uninstall from Steam's copy of the game
+    pub fn uninstall_from_steam(&self) -> Result<RemovalReport, InstallerError> {
+        let paths = self.locate_geometry_dash()?;
+        self.uninstall_from_wine(&paths.proton_prefix, &paths.game_path)
+    }
+
+    /// Restore the game directory to vanilla: uninstall, and also delete the per-install config.
+    /// The prefix is taken from Steam when not given; without one the registry is left alone.
+    pub fn reset(&self, prefix: Option<&Path>, game_dir: &Path) -> Result<RemovalReport, InstallerError> {
+        let prefix = prefix
+            .map(Path::to_path_buf)
+            .or_else(|| self.game_info()?.proton_prefix);
+        let mut report = self.remove_geode(prefix.as_deref(), game_dir)?;
+
+        let per_install = InstallerConfig::per_install_path(game_dir);
+        if per_install.exists() {
+            fs::remove_file(&per_install)?;
+            report.removed.push(per_install);
+        }
+
+        // Rollback points and registry copies would only bring Geode back
+        let backups = game_dir.join(BACKUP_DIR);
+        if backups.exists() {
+            fs::remove_dir_all(&backups)?;
+            report.removed.push(backups);
         }
+        if let Some(prefix) = &prefix {
+            for registry in ["user.reg", "system.reg"] {
+                for backup in RegistryBackups::list(&prefix.join(registry))? {
+                    fs::remove_file(&backup)?;
+                    report.removed.push(backup);
+                }
+            }
+        }
+
+        Ok(report)
     }
 
-    fn add_dll_overrides_section(&self, content: &mut String) {
-        let timestamp = current_timestamp();
-        let hex_time = current_hex_timestamp();
-        content.push_str(&format!(
-            "\n\n[Software\\\\Wine\\\\DllOverrides] {}\n#time={}\n\"xinput1_4\"=\"native,builtin\"\n",
-            timestamp, hex_time
-        ));
+    /// Put back the files and `user.reg` saved by the most recent install into `game_dir`
+    pub fn rollback(&self, game_dir: &Path) -> Result<Backup, InstallerError> {
+        let backup = Backup::latest(game_dir)?.ok_or_else(|| {
+            InstallerError::Installation(format!("No backup to roll back to in {:?}", game_dir.join(BACKUP_DIR)))
+        })?;
+        backup.restore(game_dir)?;
+        Ok(backup)
     }
 
-    fn add_dll_entry_to_section(&self, content: &mut String, section: &str, entry: &str) {
-        if let Some(section_pos) = content.find(section) {
-            let search_start = section_pos + section.len();
-            let insert_pos = content[search_start..]
-                .find("\n[")
-                .map(|pos| search_start + pos)
-                .unwrap_or(content.len());
+    /// Put back the newest `user.reg.geode.bak.*` copy in `prefix` (Steam's Proton prefix if
+    /// not given). Returns the restored file and the backup it came from
+    pub fn restore_registry(&self, prefix: Option<&Path>) -> Result<(PathBuf, PathBuf), InstallerError> {
+        let prefix = match prefix {
+            Some(prefix) => prefix.to_path_buf(),
+            None => self.locate_geometry_dash()?.proton_prefix,
+        };
+        let user_reg = prefix.join("user.reg");
+        let backup = RegistryBackups::restore_latest(&user_reg)?;
+        Ok((user_reg, backup))
+    }
 
-            let entry_with_newline = if insert_pos == content.len() {
-                format!("\n{}\n", entry)
-            } else {
-                format!("{}\n", entry)
-            };
-            content.insert_str(insert_pos, &entry_with_newline);
+    fn remove_geode(&self, prefix: Option<&Path>, game_dir: &Path) -> Result<RemovalReport, InstallerError> {
+        let mut report = RemovalReport::default();
+        // Before the files they are read from are gone
+        let proxy = ProxyDll::detect(game_dir);
+        let snapshot = DllOverridesSnapshot::load(game_dir)?;
+        let manifest = InstallManifest::load(game_dir)?;
+
+        // With a manifest only what this tool extracted is removed; older installs
+        // didn't write one, so fall back to the known release files
+        match &manifest {
+            Some(manifest) => report.removed.extend(manifest.remove_files(game_dir)?),
+            None => {
+                let proxy_file = format!("{}.dll", proxy.name);
+                for name in GEODE_BINARIES.iter().chain(GEODE_EXTRA_FILES).chain([&proxy_file.as_str()]) {
+                    if let Some(path) = find_file_case_insensitive(game_dir, name) {
+                        fs::remove_file(&path)?;
+                        report.removed.push(path);
+                    }
+                }
+            }
+        }
+
+        let data_dir = game_dir.join(GEODE_DATA_DIR);
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir)?;
+            report.removed.push(data_dir);
+        }
+
+        let Some(prefix) = prefix else {
+            return Ok(report);
+        };
+        // user.reg always, system.reg only when the install recorded a change there
+        let mut files = vec![prefix.join("user.reg")];
+        if let Some(manifest) = &manifest {
+            for change in &manifest.registry {
+                if !files.contains(&change.file) {
+                    files.push(change.file.clone());
+                }
+            }
+        }
+
+        for path in files.into_iter().filter(|path| path.exists()) {
+            let mut registry = RegistryFile::read(&path)?;
+            let original = registry.content.clone();
+            // The snapshot is of user.reg only
+            let is_user_reg = path.file_name().is_some_and(|name| name == "user.reg");
+            match (&snapshot, &manifest) {
+                (Some(snapshot), _) if is_user_reg => snapshot.restore(&mut registry.content),
+                (_, Some(manifest)) => {
+                    for change in manifest.registry.iter().filter(|change| change.file == path) {
+                        DllOverrides::remove(&mut registry.content, &change.value);
+                    }
+                }
+                (_, None) => {
+                    DllOverrides::remove(&mut registry.content, &proxy.name);
+                }
+            }
+            if registry.content != original {
+                registry.write(&path)?;
+                report.registry.push(path);
+            }
         }
+
+        Ok(report)
     }
-}
 
-impl Default for GeodeInstaller {
-    fn default() -> Self {
-        Self::new().expect("Failed to initialize GeodeInstaller")
+    /// Install `version` (a release tag) from now on, `None` goes back to the latest release
+    pub fn set_version(&mut self, version: Option<String>) -> Result<(), InstallerError> {
+        if let Some(tag) = &version {
+            Self::validate_tag(tag)?;
+        }
+        self.options.version = version;
+        Ok(())
     }
-}
 
-fn current_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-}
+    /// Release tags look like `v4.2.0` or `v4.0.0-beta.1`
+    pub fn validate_tag(tag: &str) -> Result<(), InstallerError> {
+        if !tag.starts_with('v') || SemVer::parse(tag).is_none() {
+            return Err(InstallerError::InvalidTag(tag.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Delete every cached release archive, returns the cache directory and how many files it held
+    pub fn clear_cache(&self) -> Result<(PathBuf, usize), InstallerError> {
+        let home = self.finder.home().map_err(|e| InstallerError::Installation(e.to_string()))?;
+        let cache = DownloadCache::new(&home.path);
+        let removed = cache.clear()?;
+        Ok((cache.dir().to_path_buf(), removed))
+    }
 
-fn current_hex_timestamp() -> String {
-    format!("{:x}", current_timestamp())
+    /// Forget cached Steam detection results and detect again
+    pub fn rescan(&mut self) -> Result<(), InstallerError> {
+        self.selected_game = None;
+        self.selected_prefix = None;
+        self.finder.rescan()
+    }
+
+    /// Every Steam copy of the game, or the manifest-less one if Steam knows of none
+    pub fn game_installs(&self) -> Vec<GameInfo> {
+        let installs = self.finder.find_all_game_installs(&self.app_id);
+        if installs.is_empty() {
+            return self.finder.find_in_common(&self.app_id, GD_EXECUTABLE).into_iter().collect();
+        }
+        installs
+    }
+
+    /// Install to this copy (one of `game_installs`) instead of the first one found
+    pub fn select_game(&mut self, game_path: PathBuf) {
+        self.selected_game = Some(game_path);
+    }
+
+    /// Every Proton prefix for the game, most recently used first
+    pub fn prefix_candidates(&self) -> Vec<ProtonPrefix> {
+        self.finder.find_proton_prefixes(&self.app_id)
+    }
+
+    /// Patch this prefix (one of `prefix_candidates`) on Steam installs
+    pub fn select_prefix(&mut self, prefix: PathBuf) {
+        self.selected_prefix = Some(prefix);
+    }
+
+    /// Send progress and messages to `observer` instead of printing them
+    pub fn set_observer(&mut self, observer: Box<dyn InstallObserver>) {
+        self.observer = observer;
+    }
+
+    /// Expand `~` and `$VAR` in a path the user typed, `~` being the detection home directory
+    pub fn expand_path(&self, input: &str) -> Result<PathBuf, InstallerError> {
+        expand_path(input, self.finder.home().ok().map(|home| home.path.as_path()))
+    }
+
+    pub fn steam_root(&self) -> Option<&Path> {
+        self.finder.steam_root().map(PathBuf::as_path)
+    }
+
+    /// Find the Steam copy of Geometry Dash, without requiring a Proton prefix
+    pub fn detect_game_dir(&self) -> Result<PathBuf, InstallerError> {
+        self.located_game_info().map(|info| info.game_path)
+    }
+
+    /// The Proton prefix of the game `detect_game_dir` finds, or the one picked with `select_prefix`
+    pub fn detect_prefix(&self) -> Result<PathBuf, InstallerError> {
+        self.locate_geometry_dash().map(|paths| paths.proton_prefix)
+    }
+
+    /// Copies of the game that Lutris, Bottles and Heroic run in their own Wine prefixes
+    pub fn wine_games(&self) -> Vec<WineGame> {
+        match self.finder.home() {
+            Ok(home) => find_wine_games(&home.path),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// The game directory and Proton prefix a Steam install would use, and how each was found
+    pub fn locate_geometry_dash(&self) -> Result<InstallationPaths, InstallerError> {
+        let game_info = self.located_game_info()?;
+
+        let (proton_prefix, prefix_via) = match &self.selected_prefix {
+            Some(prefix) => (prefix.clone(), "selected from the prefixes found".to_string()),
+            None => {
+                let prefix = game_info.proton_prefix
+                    .ok_or(InstallerError::PrefixNotFound)?;
+                (prefix, game_info.prefix_via.unwrap_or_default())
+            }
+        };
+
+        Ok(InstallationPaths {
+            game_path: game_info.game_path,
+            proton_prefix,
+            game_via: game_info.game_via,
+            prefix_via,
+        })
+    }
+
+    /// Steam's record of the game, falling back to a copy in a library's `common/` without a manifest
+    fn game_info(&self) -> Option<GameInfo> {
+        if let Some(selected) = &self.selected_game {
+            return self.game_installs().into_iter().find(|info| &info.game_path == selected);
+        }
+        self.finder.get_game_info(&self.app_id)
+            .or_else(|| self.finder.find_in_common(&self.app_id, GD_EXECUTABLE))
+    }
+
+    fn located_game_info(&self) -> Result<GameInfo, InstallerError> {
+        let info = self.game_info().ok_or(InstallerError::GameNotFound)?;
+        if info.manifest_missing {
+            self.observer.on_warning(&format!(
+                "using {:?}, but Steam doesn't know about this install (appmanifest_{}.acf is missing)",
+                info.game_path, self.app_id
+            ));
+        }
+        Ok(info)
+    }
+
+    fn validate_paths(&self, prefix: &Path, game_dir: &Path) -> Result<(), InstallerError> {
+        Self::validate_prefix(prefix)?;
+        self.warn_win32_prefix(prefix);
+        match Self::validate_game_dir(game_dir) {
+            Err(InstallerError::InvalidPath { .. }) if self.options.force && game_dir.is_dir() => {
+                self.observer.on_warning(&format!("no {} in {:?}, installing anyway (--force)", GD_EXECUTABLE, game_dir));
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Geode and its proxy DLL are 64-bit, so in a win32 prefix the install goes through but
+    /// the game never loads them
+    fn warn_win32_prefix(&self, prefix: &Path) {
+        if SteamGameFinder::detect_prefix_arch(prefix) != Some(PrefixArch::Win32) {
+            return;
+        }
+
+        let compat_data = prefix.parent().filter(|_| prefix.ends_with("pfx"));
+        let fix = match compat_data {
+            Some(compat_data) => format!(
+                "delete {:?} and start the game once from Steam, Proton then creates a new 64-bit prefix",
+                compat_data
+            ),
+            None => "recreate it as a 64-bit prefix with WINEARCH=win64 wineboot in an empty directory".to_string(),
+        };
+        self.observer.on_warning(&format!(
+            "{:?} IS A 32-BIT (win32) PREFIX. Geode and its proxy DLL are 64-bit and won't load in it. To fix this, {}",
+            prefix, fix
+        ));
+    }
+
+    /// A started Wine prefix has `drive_c` and `system.reg`; `user.reg` may still be missing
+    /// (`initialize_prefix` takes care of that). An empty directory is a prefix still to be created.
+    pub fn validate_prefix(prefix: &Path) -> Result<(), InstallerError> {
+        let invalid = |reason: &str| InstallerError::InvalidPath {
+            path: prefix.display().to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut entries = fs::read_dir(prefix).map_err(|_| invalid("the Wine prefix doesn't exist"))?;
+        if entries.next().is_none() {
+            return Ok(());
+        }
+        let missing: Vec<&str> = ["drive_c", "system.reg"].into_iter()
+            .filter(|name| !prefix.join(name).exists())
+            .collect();
+        if !missing.is_empty() {
+            let verb = if missing.len() == 1 { "is" } else { "are" };
+            return Err(invalid(&format!("it isn't a Wine prefix, {} {} missing", missing.join(" and "), verb)));
+        }
+        Ok(())
+    }
+
+    /// The game directory has to contain `GeometryDash.exe`
+    pub fn validate_game_dir(game_dir: &Path) -> Result<(), InstallerError> {
+        let invalid = |reason: String| InstallerError::InvalidPath {
+            path: game_dir.display().to_string(),
+            reason,
+        };
+
+        if !game_dir.is_dir() {
+            return Err(invalid("the game directory doesn't exist".into()));
+        }
+        if find_file_case_insensitive(game_dir, GD_EXECUTABLE).is_none() {
+            return Err(invalid(format!(
+                "there is no {} in it. Point at the folder that contains the game, or use --force if your setup is unusual",
+                GD_EXECUTABLE
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that `archive` is a Geode release before anything is extracted. A mirror or a
+    /// redirect can serve some other archive with a 200
+    pub fn validate_release_archive(archive: &Archive) -> Result<(), InstallerError> {
+        let is_geode_file = |file: &PathBuf| {
+            // `tar -C dir .` names every entry `./...`
+            let components: Vec<Component> = file.components().filter(|c| !matches!(c, Component::CurDir)).collect();
+            match components.first() {
+                Some(Component::Normal(first)) if components.len() == 1 => first.eq_ignore_ascii_case(GEODE_BINARIES[0]),
+                Some(Component::Normal(first)) => first.eq_ignore_ascii_case(GEODE_DATA_DIR),
+                _ => false,
+            }
+        };
+        if archive.files()?.iter().any(is_geode_file) {
+            Ok(())
+        } else {
+            Err(InstallerError::Download("Downloaded archive doesn't look like Geode".into()))
+        }
+    }
+
+    fn install_to_directory(
+        &self,
+        destination: &Path,
+        release: &LoaderRelease,
+        config: &InstallerConfig,
+    ) -> Result<(), InstallerError> {
+        self.ensure_game_closed(destination)?;
+        self.download_and_extract(release, destination, config)?;
+        self.check_extracted_files(destination)?;
+        InstalledVersion::write_marker(destination, release)?;
+        Ok(())
+    }
+
+    /// Files the running game has open would end up half replaced
+    fn ensure_game_closed(&self, game_dir: &Path) -> Result<(), InstallerError> {
+        if self.options.force {
+            return Ok(());
+        }
+        match ProcessScanner::find_game(game_dir, GD_EXECUTABLE) {
+            Some(pid) => Err(InstallerError::Installation(format!(
+                "Geometry Dash is running (pid {}). Close the game and try again, or use --force",
+                pid
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn install_from_archive(
+        &self,
+        zip_path: &Path,
+        release: &LoaderRelease,
+        config: &InstallerConfig,
+        prefix: &Path,
+        game_dir: &Path,
+    ) -> Result<(), InstallerError> {
+        self.ensure_game_closed(game_dir)?;
+        self.observer.on_message(&format!("Installing Geode {} to: {:?}", release.tag, game_dir));
+        self.extract_archive(zip_path, game_dir, config)?;
+        self.check_extracted_files(game_dir)?;
+        InstalledVersion::write_marker(game_dir, release)?;
+
+        self.observer.on_step(InstallStep::PatchRegistry);
+        let patched = self.patch_wine_registry(prefix, game_dir, config);
+        self.observer.on_step_done(InstallStep::PatchRegistry);
+        patched
+    }
+
+    /// The release pinned with --version or in config, or the latest one on the configured channel
+    fn resolve_release(&self, config: &InstallerConfig) -> Result<LoaderRelease, InstallerError> {
+        if let Some(archive) = &self.options.archive {
+            return Self::local_release(archive, self.options.version.as_deref());
+        }
+        match self.options.version.as_ref().or(config.version.as_ref()) {
+            Some(tag) => Ok(LoaderRelease {
+                tag: tag.clone(),
+                ..LoaderRelease::default()
+            }),
+            None => self.fetch_latest_release(config.channel()),
+        }
+    }
+
+    /// The release a local archive holds: `version` if given, else the tag in the usual
+    /// `geode-<tag>-win.zip` name
+    pub fn local_release(archive: &Path, version: Option<&str>) -> Result<LoaderRelease, InstallerError> {
+        let tag = match version {
+            Some(version) => version.to_string(),
+            None => archive
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("geode-")?.strip_suffix(&format!("-{}.zip", ASSET_PLATFORM)))
+                .filter(|tag| Self::validate_tag(tag).is_ok())
+                .ok_or_else(|| InstallerError::InvalidPath {
+                    path: archive.display().to_string(),
+                    reason: "can't tell which Geode version it is, pass --version or name it geode-<tag>-win.zip".into(),
+                })?
+                .to_string(),
+        };
+        Ok(LoaderRelease {
+            tag,
+            ..LoaderRelease::default()
+        })
+    }
+
+    fn check_extracted_files(&self, destination: &Path) -> Result<(), InstallerError> {
+        self.verify_installation(destination)?;
+
+        // The Mac build isn't made of PE files
+        if self.options.verify_architecture && cfg!(not(target_os = "macos")) {
+            self.observer.on_message("Checking architecture of extracted binaries...");
+            self.verify_binaries_architecture(destination);
+        }
+        Ok(())
+    }
+
+    /// Check the Geode DLLs exist after extraction, then look again a moment later. DLLs that
+    /// vanish in between were almost certainly removed by antivirus/real-time protection.
+    fn verify_installation(&self, game_dir: &Path) -> Result<(), InstallerError> {
+        let mut extracted = Vec::new();
+        for name in GEODE_BINARIES {
+            match find_file_case_insensitive(game_dir, name) {
+                Some(path) => extracted.push(path),
+                None => self.observer.on_warning(&format!("{} was not found after extraction", name)),
+            }
+        }
+
+        thread::sleep(QUARANTINE_RECHECK_DELAY);
+
+        let vanished: Vec<String> = extracted
+            .iter()
+            .filter(|path| !path.exists())
+            .map(|path| path.file_name().unwrap_or_default().to_string_lossy().into_owned())
+            .collect();
+
+        if !vanished.is_empty() {
+            return Err(InstallerError::Installation(format!(
+                "{} disappeared right after being extracted. Security software may have quarantined it; \
+                 add an exception for {:?} and install again",
+                vanished.join(", "),
+                game_dir
+            )));
+        }
+        Ok(())
+    }
+
+    /// Warn about extracted DLLs that aren't 64-bit Windows binaries, which usually
+    /// means a corrupted or wrong-platform download
+    fn verify_binaries_architecture(&self, destination: &Path) {
+        for name in GEODE_BINARIES {
+            let Some(path) = find_file_case_insensitive(destination, name) else {
+                continue; // Already reported by verify_installation
+            };
+
+            match PeReader::read_machine(&path) {
+                Ok(machine) if machine.is_64_bit() => {}
+                Ok(machine) => self.observer.on_warning(&format!(
+                    "{} is built for {}, expected a 64-bit Windows binary",
+                    name, machine
+                )),
+                Err(e) => self.observer.on_warning(&format!("couldn't read {}: {}", name, e)),
+            }
+        }
+    }
+
+    /// A mirror wins, then the URL the API gave for the asset; GitHub's usual asset name is
+    /// only a guess for releases that came without one (pinned versions)
+    fn download_url(&self, release: &LoaderRelease) -> String {
+        match (&self.mirror, &release.download_url) {
+            (Some(mirror), _) => format!("{}/{}/geode-{}-{}.zip", mirror, release.tag, release.tag, ASSET_PLATFORM),
+            (None, Some(url)) => url.clone(),
+            (None, None) => format!("{}/{}/geode-{}-{}.zip", GEODE_GITHUB_URL, release.tag, release.tag, ASSET_PLATFORM),
+        }
+    }
+
+    fn fetch_latest_release(&self, channel: Channel) -> Result<LoaderRelease, InstallerError> {
+        let url = Self::latest_release_url(channel);
+        if let Some(release) = self.cached_release(&url) {
+            return Ok(release);
+        }
+
+        // Even a stale copy may still be current, sending its ETag lets the API answer 304
+        let stale = self.api_cache().and_then(|cache| cache.get(&url));
+        self.observer.on_step(InstallStep::FetchRelease);
+        let response = self.http_get(&url, stale.as_ref().and_then(|cached| cached.etag.as_deref()));
+        self.observer.on_step_done(InstallStep::FetchRelease);
+        self.cache_release(&url, response?, stale)
+    }
+
+    async fn fetch_latest_release_async(&self, channel: Channel) -> Result<LoaderRelease, InstallerError> {
+        let url = Self::latest_release_url(channel);
+        if let Some(release) = self.cached_release(&url) {
+            return Ok(release);
+        }
+
+        let stale = self.api_cache().and_then(|cache| cache.get(&url));
+        self.observer.on_step(InstallStep::FetchRelease);
+        let response = async {
+            let mut request = self.get_async(&url);
+            if let Some(etag) = stale.as_ref().and_then(|cached| cached.etag.as_deref()) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            let response = self.send_async(request).await?;
+            if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                return Ok(Fetched::NotModified(response.headers().clone()));
+            }
+            if !response.status().is_success() {
+                return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
+            }
+            let headers = response.headers().clone();
+            Ok(Fetched::Body(response.text().await?, headers))
+        }
+        .await;
+        self.observer.on_step_done(InstallStep::FetchRelease);
+        self.cache_release(&url, response?, stale)
+    }
+
+    /// The latest release from earlier this session, else from a fresh API cache entry
+    /// unless `--refresh` was given
+    fn cached_release(&self, url: &str) -> Option<LoaderRelease> {
+        if let Some(release) = self.latest_releases.lock().unwrap().get(url) {
+            return Some(release.clone());
+        }
+        if self.options.refresh {
+            return None;
+        }
+
+        let cached = self.api_cache()?.get(url).filter(CachedResponse::is_fresh)?;
+        let release = Self::parse_latest_release(&cached.body).ok()?;
+        debug!("Using the cached answer from {} for the latest release", url);
+        self.latest_releases.lock().unwrap().insert(url.to_string(), release.clone());
+        Some(release)
+    }
+
+    /// Parse an API response, or the `stale` copy it said is still current, and keep it for
+    /// later installs in memory and on disk
+    fn cache_release(&self, url: &str, fetched: Fetched, stale: Option<CachedResponse>) -> Result<LoaderRelease, InstallerError> {
+        let (body, cached) = match fetched {
+            Fetched::Body(body, headers) => (body.clone(), CachedResponse::new(url, body, &headers)),
+            Fetched::NotModified(headers) => {
+                let stale = stale.ok_or_else(|| InstallerError::Network(format!("{} answered 304 Not Modified to a request without an ETag", url)))?;
+                debug!("{} hasn't changed since it was cached", url);
+                (stale.body.clone(), stale.revalidated(&headers))
+            }
+        };
+
+        let release = Self::parse_latest_release(&body)?;
+        self.latest_releases.lock().unwrap().insert(url.to_string(), release.clone());
+        if let (Some(cache), Some(cached)) = (self.api_cache(), cached)
+            && let Err(e) = cache.put(&cached)
+        {
+            debug!("Couldn't cache the API response: {}", e);
+        }
+        Ok(release)
+    }
+
+    fn api_cache(&self) -> Option<ApiCache> {
+        self.finder.home().ok().map(|home| ApiCache::new(&home.path))
+    }
+
+    fn latest_release_url(channel: Channel) -> String {
+        match channel {
+            Channel::Stable => GEODE_API_URL.to_string(),
+            Channel::Prerelease => format!("{}?prerelease=true", GEODE_API_URL),
+        }
+    }
+
+    fn parse_latest_release(body: &str) -> Result<LoaderRelease, InstallerError> {
+        let json: Value = serde_json::from_str(body)?;
+
+        if let Some(error) = json["error"].as_str()
+            && !error.is_empty()
+        {
+            return Err(InstallerError::Network(format!("Geode API error: {}", error)));
+        }
+
+        LoaderRelease::from_api(&json["payload"])
+    }
+
+    fn download_and_extract(&self, release: &LoaderRelease, destination: &Path, config: &InstallerConfig) -> Result<(), InstallerError> {
+        let zip_path = self.download_path(release, destination)?;
+        let archive = self.fetch_release_archive(release, &zip_path)?;
+        let extracted = self.extract_archive(&archive.path, destination, config);
+
+        // Also on failure, so a broken archive isn't left lying in the game folder
+        let removed = if archive.temporary { fs::remove_file(&archive.path) } else { Ok(()) };
+        extracted?;
+        Ok(removed?)
+    }
+
+
+    /// A GET request, authenticated when it goes to GitHub and a token is set. reqwest drops
+    /// the header if GitHub redirects a download to another host
+    fn get(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url);
+        match self.token_for(url) {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// `get` for the async client
+    fn get_async(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.async_client.get(url);
+        match self.token_for(url) {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// The GitHub token, if there is one and `url` goes to GitHub
+    fn token_for(&self, url: &str) -> Option<&str> {
+        let to_github = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host == "github.com" || host.ends_with(".github.com")))
+            .unwrap_or(false);
+        self.github_token.as_deref().filter(|_| to_github)
+    }
+
+    /// GitHub answers 403 (or 429) with no requests left when the rate limit is hit
+    fn rate_limit_error(&self, status: reqwest::StatusCode, headers: &HeaderMap, url: &reqwest::Url) -> Option<InstallerError> {
+        if status != reqwest::StatusCode::FORBIDDEN && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        if header("x-ratelimit-remaining") != Some("0") {
+            return None;
+        }
+
+        let host = url.host_str().unwrap_or("the server");
+        let mut message = match header("x-ratelimit-reset").and_then(|reset| reset.parse::<u64>().ok()) {
+            Some(reset) => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|now| now.as_secs())
+                    .unwrap_or(0);
+                let minutes = reset.saturating_sub(now).div_ceil(60);
+                format!("{} allows no more requests for now, the limit resets in {} minute(s)", host, minutes)
+            }
+            None => format!("{} allows no more requests for now", host),
+        };
+        if self.github_token.is_none() {
+            message.push_str(&format!(". Setting {} raises the limit", GITHUB_TOKEN_ENV));
+        }
+        Some(InstallerError::RateLimited(message))
+    }
+
+    /// Send a request, retrying with exponential backoff on errors that are likely to be
+    /// transient. 4xx responses are returned as they are
+    fn send(&self, request: RequestBuilder) -> Result<Response, InstallerError> {
+        let attempts = self.options.retries.max(1);
+        let mut delay = RETRY_BASE_DELAY;
+        let mut attempt = 1;
+
+        loop {
+            let this_try = request
+                .try_clone()
+                .ok_or_else(|| InstallerError::Unknown("Request can't be retried".into()))?;
+            let (reason, timed_out) = match this_try.send() {
+                Ok(response) if response.status().is_server_error() => (response.status().to_string(), false),
+                Ok(response) => match self.rate_limit_error(response.status(), response.headers(), response.url()) {
+                    Some(e) => return Err(e),
+                    None => return Ok(response),
+                },
+                Err(e) if e.is_connect() || e.is_timeout() => (e.to_string(), e.is_timeout()),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= attempts {
+                let message = format!("{} (gave up after {} attempts)", reason, attempts);
+                return Err(if timed_out { InstallerError::Timeout(message) } else { InstallerError::Network(message) });
+            }
+            attempt += 1;
+            self.observer.on_message(&format!("{}, retrying ({}/{})...", reason, attempt, attempts));
+            thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+
+    /// `send` for the async client
+    async fn send_async(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, InstallerError> {
+        let attempts = self.options.retries.max(1);
+        let mut delay = RETRY_BASE_DELAY;
+        let mut attempt = 1;
+
+        loop {
+            let this_try = request
+                .try_clone()
+                .ok_or_else(|| InstallerError::Unknown("Request can't be retried".into()))?;
+            let (reason, timed_out) = match this_try.send().await {
+                Ok(response) if response.status().is_server_error() => (response.status().to_string(), false),
+                Ok(response) => match self.rate_limit_error(response.status(), response.headers(), response.url()) {
+                    Some(e) => return Err(e),
+                    None => return Ok(response),
+                },
+                Err(e) if e.is_connect() || e.is_timeout() => (e.to_string(), e.is_timeout()),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= attempts {
+                let message = format!("{} (gave up after {} attempts)", reason, attempts);
+                return Err(if timed_out { InstallerError::Timeout(message) } else { InstallerError::Network(message) });
+            }
+            attempt += 1;
+            self.observer.on_message(&format!("{}, retrying ({}/{})...", reason, attempt, attempts));
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    /// A GET that's conditional on `etag` when there is one
+    fn http_get(&self, url: &str, etag: Option<&str>) -> Result<Fetched, InstallerError> {
+        let mut request = self.get(url);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = self.send(request)?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified(response.headers().clone()));
+        }
+        if !response.status().is_success() {
+            return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
+        }
+
+        let headers = response.headers().clone();
+        Ok(Fetched::Body(response.text()?, headers))
+    }
+
+
+    /// Where to download the archive of `release` when it isn't cached. It's extracted straight
+    /// from there, so a temp dir on another filesystem never needs a cross-device rename, only
+    /// a plain delete afterwards. The name is stable across runs so an interrupted download can
+    /// be resumed, and has the tag in it so it's only ever resumed with the same release
+    fn download_path(&self, release: &LoaderRelease, destination: &Path) -> Result<PathBuf, InstallerError> {
+        fs::create_dir_all(destination)?;
+        match &self.options.temp_dir {
+            Some(temp_dir) => {
+                fs::create_dir_all(temp_dir)?;
+                Ok(temp_dir.join(format!("geode_temp_{}.zip", release.tag)))
+            }
+            None => Ok(destination.join(format!("geode_temp_{}.zip", release.tag))),
+        }
+    }
+
+    /// The release archive from the download cache, downloading it into the cache first if it
+    /// isn't there. With --no-cache (or no home directory to keep a cache in) it's downloaded
+    /// to `download_path` instead, to be deleted after extracting; with --temp-dir it's
+    /// downloaded there either way
+    fn fetch_release_archive(&self, release: &LoaderRelease, download_path: &Path) -> Result<FetchedArchive, InstallerError> {
+        if let Some(archive) = &self.options.archive {
+            self.check_local_archive(archive, release)?;
+            return Ok(FetchedArchive { path: archive.clone(), temporary: false });
+        }
+
+        let cache = match self.finder.home() {
+            Ok(home) if !self.options.no_cache => DownloadCache::new(&home.path),
+            _ => {
+                self.observer.on_message(&format!("Downloading Geode {}...", release.tag));
+                self.download_release(release, download_path)?;
+                return Ok(FetchedArchive { path: download_path.to_path_buf(), temporary: true });
+            }
+        };
+
+        if let Some(cached) = cache.get(&release.tag) {
+            // Without a digest to check, it at least has to open as a Geode archive
+            let intact = match self.expected_sha256(release) {
+                Some(expected) => Checksum::verify(expected, &Checksum::of_file(&cached)?).is_ok(),
+                None => Archive::open(&cached).and_then(|archive| Self::validate_release_archive(&archive)).is_ok(),
+            };
+            if intact {
+                self.observer.on_message(&format!("Using cached {:?}", cached));
+                return Ok(FetchedArchive { path: cached, temporary: false });
+            }
+            self.observer.on_message(&format!("Cached {:?} is damaged, downloading it again", cached));
+            fs::remove_file(&cached)?;
+        }
+
+        fs::create_dir_all(cache.dir())?;
+        self.observer.on_message(&format!("Downloading Geode {}...", release.tag));
+        // --temp-dir still decides where the download is written, it's moved into the cache once complete
+        let partial = match &self.options.temp_dir {
+            Some(_) => download_path.to_path_buf(),
+            None => cache.partial_path(&release.tag),
+        };
+        self.download_release(release, &partial)?;
+        let cached = cache.path(&release.tag);
+        move_file(&partial, &cached)?;
+        Ok(FetchedArchive { path: cached, temporary: false })
+    }
+
+    /// Check `--archive` against `--sha256` when one is given; whether it's Geode at all is
+    /// checked before extracting, like downloads are
+    fn check_local_archive(&self, archive: &Path, release: &LoaderRelease) -> Result<(), InstallerError> {
+        self.observer.on_message(&format!("Using local archive {:?}", archive));
+        if let Some(expected) = self.expected_sha256(release) {
+            Checksum::verify(expected, &Checksum::of_file(archive)?)?;
+        }
+        Ok(())
+    }
+
+    /// `--sha256` if given, else the hash the API reported
+    fn expected_sha256<'a>(&'a self, release: &'a LoaderRelease) -> Option<&'a str> {
+        self.options.sha256.as_deref().or(release.sha256.as_deref())
+    }
+
+    /// Download the Windows archive of a release and check its SHA-256 when one is known.
+    /// A missing tag gets its own error instead of a bare 404
+    fn download_release(&self, release: &LoaderRelease, output: &Path) -> Result<(), InstallerError> {
+        let url = self.download_url(release);
+        let got = self.download_file(&url, output).map_err(|e| match e {
+            InstallerError::NotFound(_) => InstallerError::Download(format!(
+                "Geode {} has no Windows release ({} returned 404). Check the tag at https://github.com/geode-sdk/geode/releases",
+                release.tag, url
+            )),
+            InstallerError::Io(message) => InstallerError::Download(format!("Can't save {:?}: {}", output, message)),
+            e => e,
+        })?;
+        self.check_download(release, output, &got)
+    }
+
+    /// `download_release` through the async client
+    async fn download_release_async(&self, release: &LoaderRelease, output: &Path) -> Result<(), InstallerError> {
+        let url = self.download_url(release);
+        let got = self.download_file_async(&url, output).await.map_err(|e| match e {
+            InstallerError::NotFound(_) => InstallerError::Download(format!(
+                "Geode {} has no Windows release ({} returned 404). Check the tag at https://github.com/geode-sdk/geode/releases",
+                release.tag, url
+            )),
+            InstallerError::Io(message) => InstallerError::Download(format!("Can't save {:?}: {}", output, message)),
+            e => e,
+        })?;
+        self.check_download(release, output, &got)
+    }
+
+    /// Compare the SHA-256 of a downloaded archive with the expected one, deleting it on a mismatch
+    fn check_download(&self, release: &LoaderRelease, output: &Path, got: &str) -> Result<(), InstallerError> {
+        match self.expected_sha256(release) {
+            Some(expected) => {
+                if let Err(e) = Checksum::verify(expected, got) {
+                    let _ = fs::remove_file(output);
+                    return Err(e);
+                }
+                if self.options.verbose {
+                    self.observer.on_message(&format!("SHA-256 verified: {}", got));
+                }
+            }
+            None if self.options.verbose => self.observer.on_message(&format!("No checksum to compare against, SHA-256 is {}", got)),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Fail early instead of halfway through writing `needed` bytes to `path`. Filesystems
+    /// that can't report their free space are given the benefit of the doubt
+    fn ensure_free_space(&self, path: &Path, needed: u64) -> Result<(), InstallerError> {
+        match available_space(path) {
+            Ok(have) if have < needed => Err(InstallerError::NotEnoughSpace { path: path.to_path_buf(), need: needed, have }),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                if self.options.verbose {
+                    self.observer.on_message(&format!("Can't check the free space for {:?}: {}", path, e));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the SHA-256 of what was written. A partial file left at `output` by an
+    /// interrupted download is continued with a Range request when the server supports it
+    fn download_file(&self, url: &str, output: &Path) -> Result<String, InstallerError> {
+        let resume_from = fs::metadata(output).map(|metadata| metadata.len()).unwrap_or(0);
+        let mut request = self.get(url);
+        if resume_from > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+        }
+
+        let mut response = self.send(request)?;
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // The leftover is as long as or longer than the file, it can't be a prefix of it
+            fs::remove_file(output)?;
+            return self.download_file(url, output);
+        }
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(InstallerError::NotFound(url.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
+        }
+        if resume_from > 0
+            && response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+            && Self::content_range_start(response.headers()) != Some(resume_from)
+        {
+            // Appending a range that starts anywhere else would splice the file
+            self.observer.on_message("Server sent a different range than asked for, downloading from the start");
+            drop(response);
+            fs::remove_file(output)?;
+            return self.download_file(url, output);
+        }
+
+        let mut checksum = Checksum::new();
+        // A 200 means the server ignored the range and is sending the whole file again
+        let (mut file, mut downloaded) = if resume_from > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            self.observer.on_message(&format!("Resuming download at {} bytes", resume_from));
+            io::copy(&mut File::open(output)?, &mut checksum)?;
+            (OpenOptions::new().append(true).open(output)?, resume_from)
+        } else {
+            (File::create(output)?, 0)
+        };
+
+        if let Some(length) = response.content_length() {
+            self.ensure_free_space(output, length)?;
+        }
+        let total_size = downloaded + response.content_length().unwrap_or(0);
+        self.observer.on_step(InstallStep::Download);
+        self.observer.on_progress(downloaded, total_size);
+
+        let mut receive = || -> Result<(), InstallerError> {
+            let mut buffer = vec![0; 8192];
+            loop {
+                let bytes_read = response.read(&mut buffer)?;
+                if bytes_read == 0 {
+                    return Ok(());
+                }
+                file.write_all(&buffer[..bytes_read])?;
+                checksum.update(&buffer[..bytes_read]);
+                downloaded += bytes_read as u64;
+                self.observer.on_progress(downloaded, total_size);
+            }
+        };
+        let received = receive();
+        self.observer.on_step_done(InstallStep::Download);
+        received?;
+        Ok(checksum.finish())
+    }
+
+    /// Where the body of a 206 starts, from `Content-Range: bytes <start>-<end>/<total>`
+    pub fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+        let range = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+        let (start, _) = range.trim().strip_prefix("bytes ")?.split_once('-')?;
+        start.trim().parse().ok()
+    }
+
+    /// `download_file` through the async client, always from the start
+    async fn download_file_async(&self, url: &str, output: &Path) -> Result<String, InstallerError> {
+        let mut response = self.send_async(self.get_async(url)).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(InstallerError::NotFound(url.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(InstallerError::Http { status: response.status().as_u16(), url: response.url().to_string() });
+        }
+
+        let mut file = tokio::fs::File::create(output).await?;
+        if let Some(length) = response.content_length() {
+            self.ensure_free_space(output, length)?;
+        }
+        let total_size = response.content_length().unwrap_or(0);
+        let mut checksum = Checksum::new();
+        self.observer.on_step(InstallStep::Download);
+        self.observer.on_progress(0, total_size);
+
+        let received = async {
+            let mut downloaded = 0;
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                checksum.update(&chunk);
+                downloaded += chunk.len() as u64;
+                self.observer.on_progress(downloaded, total_size);
+            }
+            Ok::<(), InstallerError>(file.flush().await?)
+        }
+        .await;
+        self.observer.on_step_done(InstallStep::Download);
+        received?;
+        Ok(checksum.finish())
+    }
+
+    fn extract_archive(&self, archive_path: &Path, destination: &Path, config: &InstallerConfig) -> Result<(), InstallerError> {
+        let archive = Archive::open(archive_path)?;
+        if self.options.verbose {
+            self.observer.on_message(&format!("Extracting {:?} archive", archive.format()));
+        }
+        Self::validate_release_archive(&archive)?;
+        self.ensure_free_space(destination, archive.unpacked_size()?)?;
+
+        // Extract next to the game first, so a failure part way through leaves the game untouched
+        let staging = Self::staging_dir(destination);
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        let result = self.extract_staged(&archive, &staging, destination, config);
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+
+    /// Extract into `staging`, then back up and replace the files in `destination`
+    fn extract_staged(&self, archive: &Archive, staging: &Path, destination: &Path, config: &InstallerConfig) -> Result<(), InstallerError> {
+        let fold_case = is_case_insensitive(destination).unwrap_or(false);
+        if fold_case && self.options.verbose {
+            self.observer.on_message(&format!("{:?} is on a case-insensitive filesystem", destination));
+        }
+        let mut written = WrittenPaths::new(fold_case);
+
+        let files = archive.extract(staging, self.observer.as_ref(), |name| {
+            if config.is_excluded(name) {
+                self.observer.on_message(&format!("Skipping excluded file: {}", name));
+                return false;
+            }
+            if !written.insert(name) {
+                self.observer.on_warning(&format!("skipping {}, it differs only in case from an earlier file", name));
+                return false;
+            }
+            true
+        })?;
+
+        let previous = InstallManifest::load(destination)?;
+        if !self.options.no_backup {
+            let mut replaced: Vec<PathBuf> = archive
+                .files()?
+                .into_iter()
+                .filter(|file| !config.is_excluded(&file.to_string_lossy()))
+                .collect();
+            if let Some(previous) = &previous {
+                replaced.extend(previous.files.iter().cloned());
+            }
+            // So a rollback also brings back the record of which version was installed
+            for bookkeeping in [InstallManifest::path(destination), InstalledVersion::marker_path(destination)] {
+                if let Ok(relative) = bookkeeping.strip_prefix(destination) {
+                    replaced.push(relative.to_path_buf());
+                }
+            }
+            replaced.sort();
+            replaced.dedup();
+            let backup = Backup::create(destination, &replaced)?;
+            if !backup.files.is_empty() {
+                self.observer.on_message(&format!("Backed up {} files that will be replaced to {:?}", backup.files.len(), backup.dir));
+            }
+        }
+
+        // Files from the previous install that this release no longer ships would linger otherwise
+        if let Some(previous) = &previous {
+            let removed = previous.remove_files(destination)?;
+            if self.options.verbose {
+                self.observer.on_message(&format!("Removed {} files from the previous install", removed.len()));
+            }
+        }
+
+        move_tree(staging, destination)?;
+
+        let manifest = InstallManifest {
+            files,
+            registry: previous.map(|previous| previous.registry).unwrap_or_default(),
+        };
+        manifest.save(destination)
+    }
+
+    /// Hidden sibling of `destination`, on the same filesystem so moving out of it is a rename
+    fn staging_dir(destination: &Path) -> PathBuf {
+        let mut name = OsString::from(".");
+        name.push(destination.file_name().unwrap_or_default());
+        name.push(".geode-extract");
+        destination.with_file_name(name)
+    }
+
+    /// Report what installing `release` would do. Only reads: the archive is counted if it's
+    /// already cached, and the registry changes are worked out on a copy
+    fn dry_run(&self, prefix: &Path, game_dir: &Path, release: &LoaderRelease, config: &InstallerConfig) -> Result<(), InstallerError> {
+        let cached = match &self.options.archive {
+            Some(archive) => Some(archive.clone()),
+            None => self.finder.home().ok()
+                .filter(|_| !self.options.no_cache)
+                .and_then(|home| DownloadCache::new(&home.path).get(&release.tag)),
+        };
+        match &cached {
+            Some(archive) if self.options.archive.is_some() => self.observer.on_message(&format!("Would use local archive {:?}", archive)),
+            Some(cached) => self.observer.on_message(&format!("Would use cached {:?}", cached)),
+            None => self.observer.on_message(&format!("Would download {}", self.download_url(release))),
+        }
+
+        let files = match &cached {
+            Some(cached) => Some(Archive::open(cached)?.files()?),
+            None => None,
+        };
+        match files {
+            Some(files) => {
+                let count = files.iter().filter(|file| !config.is_excluded(&file.to_string_lossy())).count();
+                self.observer.on_message(&format!("Would extract {} files to {:?}", count, game_dir));
+            }
+            None => self.observer.on_message(&format!("Would extract Geode {} to {:?}", release.tag, game_dir)),
+        }
+        if !self.options.no_backup {
+            self.observer.on_message(&format!("Would back up the files it replaces to {:?}", game_dir.join(BACKUP_DIR)));
+        }
+
+        if !prefix.join("user.reg").exists() {
+            self.observer.on_message(&format!("Would initialize {:?} with wineboot, its registry can't be shown yet", prefix));
+            return Ok(());
+        }
+        let proxy = ProxyDll::detect(game_dir);
+        let overrides = config.dll_overrides(&proxy.name, &self.options.dll_overrides);
+        for name in self.options.registry_scope.files() {
+            let path = prefix.join(name);
+            if !path.exists() {
+                self.observer.on_message(&format!("Would skip {:?}, it doesn't exist", path));
+                continue;
+            }
+
+            let before = RegistryFile::read(&path)?.content;
+            let mut after = before.clone();
+            let added = DllOverrides::ensure_all(&mut after, &overrides);
+            if added.is_empty() {
+                self.observer.on_message(&format!("{:?} already has every DLL override", path));
+                continue;
+            }
+            for dll in &added {
+                self.observer.on_message(&format!("Would add registry entry {} \"{}\" to {:?}", DLL_OVERRIDES_SECTION, dll, path));
+            }
+            let old_lines: HashSet<&str> = before.lines().collect();
+            let new_lines: HashSet<&str> = after.lines().collect();
+            for line in before.lines().filter(|line| !new_lines.contains(line)) {
+                self.observer.on_message(&format!("  - {}", line));
+            }
+            for line in after.lines().filter(|line| !old_lines.contains(line)) {
+                self.observer.on_message(&format!("  + {}", line));
+            }
+        }
+        Ok(())
+    }
+
+    /// A prefix that was created but never started has no `user.reg` yet. Boot it with Wine
+    /// when that's available, falling back to an empty registry if wineboot doesn't write one
+    fn initialize_prefix(&self, prefix: &Path, user_reg: &Path) -> Result<(), InstallerError> {
+        let wine = Wine::find().ok_or_else(|| {
+            InstallerError::Installation(format!(
+                "{:?} has no user.reg, the prefix was never started. Install Wine so the installer can \
+                 initialize it, or run any program in the prefix once and try again",
+                prefix
+            ))
+        })?;
+
+        self.observer.on_message(&format!("{:?} has no user.reg, initializing the prefix with wineboot...", prefix));
+        wine.boot(prefix)?;
+        if !user_reg.exists() {
+            self.observer.on_message(&format!("wineboot didn't create {:?}, writing an empty one", user_reg));
+            fs::write(user_reg, MINIMAL_USER_REG)?;
+        }
+        Ok(())
+    }
+
+    fn patch_wine_registry(&self, prefix: &Path, game_dir: &Path, config: &InstallerConfig) -> Result<(), InstallerError> {
+        let user_reg = prefix.join("user.reg");
+        if !user_reg.exists() {
+            self.initialize_prefix(prefix, &user_reg)?;
+        }
+
+        let proxy = ProxyDll::detect(game_dir);
+        if self.options.verbose {
+            self.observer.on_message(&format!("Overriding {}.dll (from {:?})", proxy.name, proxy.source));
+        }
+
+        for name in self.options.registry_scope.files() {
+            let path = prefix.join(name);
+            if !path.exists() {
+                self.observer.on_warning(&format!("{:?} doesn't exist, skipping it", path));
+                continue;
+            }
+            self.patch_registry_file(&path, game_dir, &proxy, config)?;
+        }
+        Ok(())
+    }
+
+    /// Back up one registry file and add the DLL override to it
+    fn patch_registry_file(&self, path: &Path, game_dir: &Path, proxy: &ProxyDll, config: &InstallerConfig) -> Result<(), InstallerError> {
+        let backup = RegistryBackups::create(path)?;
+        if self.options.verbose {
+            self.observer.on_message(&format!("Backed up {:?} to {:?}", path, backup));
+        }
+        // The backup was started when the files were extracted
+        if !self.options.no_backup
+            && let Some(mut backup) = Backup::latest(game_dir)?
+        {
+            backup.save_registry(path)?;
+        }
+
+        let mut registry = RegistryFile::read(path)?;
+        if registry.encoding != RegistryEncoding::Utf8 {
+            self.observer.on_warning(&format!("{:?} is {}, it will be written back in the same encoding", path, registry.encoding));
+        }
+
+        if path.file_name().is_some_and(|name| name == "user.reg")
+            && DllOverridesSnapshot::capture(&registry.content).save_if_missing(game_dir)?
+            && self.options.verbose
+        {
+            self.observer.on_message(&format!("Saved the original DllOverrides to {:?}", DllOverridesSnapshot::path(game_dir)));
+        }
+        // Worked out on the decoded file either way, so regedit only gets what actually changes
+        let overrides = config.dll_overrides(&proxy.name, &self.options.dll_overrides);
+        let added = DllOverrides::ensure_all(&mut registry.content, &overrides);
+        if added.is_empty() {
+            return Ok(());
+        }
+        match self.options.registry_method {
+            RegistryMethod::File => registry
+                .write(path)
+                .map_err(|e| InstallerError::Registry(format!("Can't write {:?}: {}", path, e)))?,
+            RegistryMethod::Regedit => {
+                let changed: Vec<(&str, &str)> = overrides
+                    .iter()
+                    .filter(|(dll, _)| added.contains(&dll.as_str()))
+                    .map(|(dll, value)| (dll.as_str(), value.as_str()))
+                    .collect();
+                self.import_overrides(path, &changed)?;
+            }
+        }
+
+        let mut manifest = InstallManifest::load(game_dir)?.unwrap_or_default();
+        for dll in added {
+            manifest.record_registry_change(RegistryChange {
+                file: path.to_path_buf(),
+                section: DLL_OVERRIDES_SECTION.to_string(),
+                value: dll.to_string(),
+            });
+        }
+        manifest.save(game_dir)
+    }
+
+    /// Apply overrides to the hive in `registry_file` through `wine regedit` instead of editing it
+    fn import_overrides(&self, registry_file: &Path, overrides: &[(&str, &str)]) -> Result<(), InstallerError> {
+        let wine = Wine::find().ok_or_else(|| {
+            InstallerError::Registry("--registry-method regedit needs Wine, but `wine` isn't on PATH".into())
+        })?;
+        let prefix = registry_file.parent().unwrap_or(Path::new("."));
+
+        let script = std::env::temp_dir().join(format!("geode-dll-overrides-{}.reg", std::process::id()));
+        fs::write(&script, DllOverrides::regedit_script(registry_file, overrides))?;
+        if self.options.verbose {
+            self.observer.on_message(&format!("Importing {:?} into {:?} with wine regedit", script, prefix));
+        }
+        let result = wine.regedit(prefix, &script);
+        let _ = fs::remove_file(&script);
+        result
+    }
+}
+
+impl Default for GeodeInstaller {
+    fn default() -> Self {
+        Self::new().expect("Failed to initialize GeodeInstaller")
+    }
 }
@@ -0,0 +1,75 @@
+use crate::errors::InstallerError;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+const MODS_DIR: &str = "geode/mods";
+const MOD_EXTENSION: &str = "geode";
+const MOD_METADATA_FILE: &str = "mod.json";
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct ModInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub path: PathBuf,
+}
+
+/// Reads metadata of installed Geode mods (`.geode` packages are zip archives with a `mod.json`)
+pub struct ModReader;
+
+impl ModReader {
+    pub fn mods_dir(game_dir: &Path) -> PathBuf {
+        game_dir.join(MODS_DIR)
+    }
+
+    /// List every mod package in `geode/mods/`, sorted by id. Packages that
+    /// can't be read are reported in the second element instead of failing the whole listing.
+    pub fn list_installed(game_dir: &Path) -> Result<(Vec<ModInfo>, Vec<InstallerError>), InstallerError> {
+        let mods_dir = Self::mods_dir(game_dir);
+        if !mods_dir.exists() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut mods = Vec::new();
+        let mut errors = Vec::new();
+
+        for entry in fs::read_dir(&mods_dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != MOD_EXTENSION) {
+                continue;
+            }
+
+            match Self::read_mod_info(&path) {
+                Ok(info) => mods.push(info),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        mods.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok((mods, errors))
+    }
+
+    pub fn read_mod_info(path: &Path) -> Result<ModInfo, InstallerError> {
+        let file = File::open(path)?;
+        let mut archive = ZipArchive::new(file)?;
+        let metadata = archive.by_name(MOD_METADATA_FILE).map_err(|_| {
+            InstallerError::Unknown(format!("{:?} has no {}", path, MOD_METADATA_FILE))
+        })?;
+        let json: Value = serde_json::from_reader(metadata)?;
+
+        let id = json["id"]
+            .as_str()
+            .ok_or_else(|| InstallerError::Unknown(format!("{:?} has no mod id", path)))?
+            .to_string();
+
+        Ok(ModInfo {
+            name: json["name"].as_str().unwrap_or(&id).to_string(),
+            version: json["version"].as_str().unwrap_or("unknown").to_string(),
+            id,
+            path: path.to_path_buf(),
+        })
+    }
+}
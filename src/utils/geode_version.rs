@@ -0,0 +1,165 @@
+use crate::errors::InstallerError;
+use crate::utils::fs_utils::find_file_case_insensitive;
+use crate::utils::pe::PeReader;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written by this installer after extracting a release
+const VERSION_MARKER: &str = "geode/.installed_version.json";
+const GEODE_DLL: &str = "Geode.dll";
+
+/// Platform key of the release asset this build installs, as in `geode-<tag>-<platform>.zip`
+#[cfg(not(target_os = "macos"))]
+pub const ASSET_PLATFORM: &str = "win";
+/// Geode runs natively on macOS, so the Mac build is installed there instead of the Windows one
+#[cfg(target_os = "macos")]
+pub const ASSET_PLATFORM: &str = "mac";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// The marker file this installer writes next to the install
+    Marker,
+    /// The VERSIONINFO resource of Geode.dll, for installs made without a marker
+    /// (older versions of this tool, the official installer, manual installs)
+    Dll,
+}
+
+/// Loader release metadata as reported by the Geode API
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoaderRelease {
+    pub tag: String,
+    pub commit: Option<String>,
+    pub created_at: Option<String>,
+    /// Windows Geometry Dash version the release targets, e.g. `2.2074`
+    pub gd: Option<String>,
+    /// SHA-256 of the Windows archive, when the API reports one
+    pub sha256: Option<String>,
+    /// Download URL of the Windows archive from the API's asset list
+    pub download_url: Option<String>,
+}
+
+impl LoaderRelease {
+    /// Read the `payload` of a Geode API loader version response
+    pub fn from_api(payload: &Value) -> Result<Self, InstallerError> {
+        let tag = payload["tag"]
+            .as_str()
+            .ok_or_else(|| InstallerError::Unknown("Failed to extract version tag from API response".into()))?;
+
+        Ok(Self {
+            tag: tag.to_string(),
+            commit: payload["commit_hash"].as_str().map(String::from),
+            created_at: payload["created_at"].as_str().map(String::from),
+            gd: payload["gd"][ASSET_PLATFORM].as_str().map(String::from),
+            sha256: payload["hash"].as_str().map(String::from),
+            download_url: Self::platform_asset_url(payload),
+        })
+    }
+
+    /// The `-win.zip` (`-mac.zip` on macOS) asset, from either an `assets` list (GitHub style)
+    /// or a `downloads` map keyed by platform
+    fn platform_asset_url(payload: &Value) -> Option<String> {
+        let suffix = format!("-{}.zip", ASSET_PLATFORM);
+        let from_assets = payload["assets"].as_array().and_then(|assets| {
+            assets.iter()
+                .filter(|asset| asset["name"].as_str().is_some_and(|name| name.to_ascii_lowercase().ends_with(&suffix)))
+                .find_map(|asset| ["browser_download_url", "download_url", "url"].iter().find_map(|key| asset[key].as_str()))
+        });
+        from_assets
+            .or_else(|| payload["downloads"][ASSET_PLATFORM].as_str())
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+            .map(String::from)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledVersion {
+    /// Release tag, e.g. `v4.2.0`
+    pub version: String,
+    pub commit: Option<String>,
+    pub build_date: Option<String>,
+    pub source: VersionSource,
+}
+
+impl InstalledVersion {
+    /// Returns `None` if there's no recognizable Geode install in `game_dir`
+    pub fn detect(game_dir: &Path) -> Result<Option<Self>, InstallerError> {
+        if let Some(version) = Self::from_marker(&Self::marker_path(game_dir)) {
+            return Ok(Some(version));
+        }
+
+        let Some(dll) = find_file_case_insensitive(game_dir, GEODE_DLL) else {
+            return Ok(None);
+        };
+        let metadata = PeReader::read_metadata(&dll)?;
+
+        Ok(metadata.file_version.map(|version| Self {
+            version: format!("v{}", version),
+            commit: None,
+            build_date: metadata.timestamp.map(|t| format_unix_date(t as u64)),
+            source: VersionSource::Dll,
+        }))
+    }
+
+    pub fn write_marker(game_dir: &Path, release: &LoaderRelease) -> Result<(), InstallerError> {
+        let path = Self::marker_path(game_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let marker = json!({
+            "tag": release.tag,
+            "commit": release.commit,
+            "build_date": release.created_at,
+        });
+        fs::write(path, serde_json::to_string_pretty(&marker)?)?;
+        Ok(())
+    }
+
+    pub fn marker_path(game_dir: &Path) -> PathBuf {
+        game_dir.join(VERSION_MARKER)
+    }
+
+    /// An unreadable or malformed marker is treated as missing so detection can fall back to the DLL
+    fn from_marker(path: &Path) -> Option<Self> {
+        let json: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        let version = json["tag"].as_str().or_else(|| json["version"].as_str())?;
+        let string_field = |key: &str| json[key].as_str().filter(|s| !s.is_empty()).map(String::from);
+
+        Some(Self {
+            version: if version.starts_with('v') { version.to_string() } else { format!("v{}", version) },
+            commit: string_field("commit"),
+            build_date: string_field("build_date"),
+            source: VersionSource::Marker,
+        })
+    }
+}
+
+impl std::fmt::Display for InstalledVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.version)?;
+        if let Some(commit) = &self.commit {
+            write!(f, " ({})", commit.chars().take(7).collect::<String>())?;
+        }
+        if let Some(date) = &self.build_date {
+            write!(f, ", built {}", date)?;
+        }
+        Ok(())
+    }
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD` (UTC)
+fn format_unix_date(timestamp: u64) -> String {
+    // Days-to-civil conversion from Howard Hinnant's date algorithms
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
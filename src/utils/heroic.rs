@@ -0,0 +1,77 @@
+use crate::utils::fs_utils::find_file_case_insensitive;
+use crate::utils::wine_games::{Launcher, WineGame, GD_EXECUTABLE};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Heroic's config directories, relative to home: native and Flatpak
+const CONFIG_DIRS: &[&str] = &[".config/heroic", ".var/app/com.heroicgameslauncher.hgl/config/heroic"];
+/// Epic games Heroic installed through legendary, relative to a config directory
+const INSTALLED_EPIC: &str = "legendaryConfig/legendary/installed.json";
+/// Where Heroic puts a game's prefix unless told otherwise, relative to home
+const DEFAULT_PREFIXES: &str = "Games/Heroic/Prefixes/default";
+
+/// The Heroic Games Launcher, which runs Epic Games Store copies of the game in Wine
+pub struct Heroic;
+
+impl Heroic {
+    pub fn find_games(home: &Path) -> Vec<WineGame> {
+        CONFIG_DIRS
+            .iter()
+            .map(|dir| home.join(dir))
+            .flat_map(|config_dir| Self::games_in(&config_dir, home))
+            .collect()
+    }
+
+    /// Installed Epic games in one config directory whose executable is Geometry Dash, each
+    /// with the prefix from `GamesConfig/<app name>.json`
+    pub fn games_in(config_dir: &Path, home: &Path) -> Vec<WineGame> {
+        let Some(installed) = Self::read_json(&config_dir.join(INSTALLED_EPIC)) else {
+            return Vec::new();
+        };
+        let Some(installed) = installed.as_object() else {
+            return Vec::new();
+        };
+
+        installed
+            .iter()
+            .filter_map(|(app_name, game)| {
+                let game_dir = PathBuf::from(game["install_path"].as_str()?);
+                let executable = game["executable"].as_str().map(Path::new).and_then(Path::file_name);
+                let is_gd = match executable {
+                    Some(executable) => executable.to_string_lossy().eq_ignore_ascii_case(GD_EXECUTABLE),
+                    None => find_file_case_insensitive(&game_dir, GD_EXECUTABLE).is_some(),
+                };
+                if !is_gd {
+                    return None;
+                }
+
+                let title = game["title"].as_str().unwrap_or(app_name);
+                Some(WineGame {
+                    launcher: Launcher::Heroic,
+                    name: title.to_string(),
+                    prefix: Self::prefix(config_dir, home, app_name, title)?,
+                    game_dir,
+                })
+            })
+            .collect()
+    }
+
+    /// `winePrefix` from the game's settings, else Heroic's default location. Proton prefixes
+    /// keep the actual Wine prefix in a `pfx` subdirectory
+    fn prefix(config_dir: &Path, home: &Path, app_name: &str, title: &str) -> Option<PathBuf> {
+        let configured = Self::read_json(&config_dir.join("GamesConfig").join(format!("{}.json", app_name)))
+            .and_then(|config| config[app_name]["winePrefix"].as_str().map(PathBuf::from));
+        let prefix = configured.unwrap_or_else(|| home.join(DEFAULT_PREFIXES).join(title));
+        if !prefix.is_dir() {
+            return None;
+        }
+
+        let pfx = prefix.join("pfx");
+        Some(if pfx.is_dir() { pfx } else { prefix })
+    }
+
+    fn read_json(path: &Path) -> Option<Value> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+}
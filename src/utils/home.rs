@@ -0,0 +1,128 @@
+use crate::errors::InstallerError;
+use homedir::UserIdentifier;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Where the home directory was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HomeSource {
+    Override,
+    Environment,
+    Passwd,
+}
+
+impl std::fmt::Display for HomeSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HomeSource::Override => write!(f, "--home override"),
+            HomeSource::Environment => write!(f, "$HOME"),
+            HomeSource::Passwd => write!(f, "passwd entry"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HomeDir {
+    pub path: PathBuf,
+    pub source: HomeSource,
+}
+
+impl HomeDir {
+    /// Resolve the user's home directory: explicit override, then `$HOME`, then the passwd entry.
+    /// A relative or missing `$HOME` (common in containers and some launchers) is skipped.
+    pub fn resolve(override_path: Option<&Path>) -> Result<Self, InstallerError> {
+        if let Some(path) = override_path {
+            if !path.is_absolute() || !path.is_dir() {
+                return Err(InstallerError::Init(format!(
+                    "Home override {:?} is not an absolute path to an existing directory",
+                    path
+                )));
+            }
+            return Ok(Self::new(path.to_path_buf(), HomeSource::Override));
+        }
+
+        if let Some(path) = Self::from_env() {
+            return Ok(Self::new(path, HomeSource::Environment));
+        }
+
+        if let Some(path) = Self::from_passwd() {
+            return Ok(Self::new(path, HomeSource::Passwd));
+        }
+
+        Err(InstallerError::Init(
+            "Can't determine your home directory: $HOME is unset or invalid and there is no passwd entry for this user. Use --home to set it explicitly".into(),
+        ))
+    }
+
+    fn new(path: PathBuf, source: HomeSource) -> Self {
+        Self { path, source }
+    }
+
+    fn from_env() -> Option<PathBuf> {
+        let path = PathBuf::from(env::var_os("HOME")?);
+        (path.is_absolute() && path.is_dir()).then_some(path)
+    }
+
+    fn from_passwd() -> Option<PathBuf> {
+        let path = UserIdentifier::my_id().ok()?.to_home().ok()??;
+        path.is_dir().then_some(path)
+    }
+}
+
+/// Expand a path typed by the user like a shell would: a leading `~` or `~user`, and `$VAR`
+/// or `${VAR}` anywhere. `home` is what a bare `~` means.
+pub fn expand_path(input: &str, home: Option<&Path>) -> Result<PathBuf, InstallerError> {
+    let expanded = expand_vars(input)?;
+
+    let Some(rest) = expanded.strip_prefix('~') else {
+        return Ok(PathBuf::from(expanded));
+    };
+    let (user, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let user_home = if user.is_empty() {
+        home.map(Path::to_path_buf)
+            .ok_or_else(|| invalid_path(input, "the home directory is unknown".into()))?
+    } else {
+        homedir::home(user)
+            .ok()
+            .flatten()
+            .ok_or_else(|| invalid_path(input, format!("there is no user {:?}", user)))?
+    };
+    Ok(user_home.join(rest))
+}
+
+fn expand_vars(input: &str) -> Result<String, InstallerError> {
+    let mut out = String::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, remaining) = match after.strip_prefix('{') {
+            Some(braced) => match braced.split_once('}') {
+                Some((name, remaining)) => (name, remaining),
+                None => return Err(invalid_path(input, "unclosed ${".into())),
+            },
+            None => {
+                let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+                after.split_at(end)
+            }
+        };
+
+        if name.is_empty() {
+            // A lone `$` isn't a variable
+            out.push('$');
+        } else {
+            let value = env::var(name)
+                .map_err(|_| invalid_path(input, format!("${} isn't set", name)))?;
+            out.push_str(&value);
+        }
+        rest = remaining;
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn invalid_path(input: &str, reason: String) -> InstallerError {
+    InstallerError::InvalidPath { path: input.to_string(), reason }
+}
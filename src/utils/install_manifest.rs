@@ -0,0 +1,74 @@
+use crate::errors::InstallerError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "geode/.installed_files.json";
+
+/// A registry value this tool added
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryChange {
+    pub file: PathBuf,
+    pub section: String,
+    pub value: String,
+}
+
+/// Everything the last install created, so uninstalling removes exactly that
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstallManifest {
+    /// Extracted files, relative to the game directory
+    pub files: Vec<PathBuf>,
+    pub registry: Vec<RegistryChange>,
+}
+
+impl InstallManifest {
+    pub fn path(game_dir: &Path) -> PathBuf {
+        game_dir.join(MANIFEST_FILE)
+    }
+
+    pub fn load(game_dir: &Path) -> Result<Option<Self>, InstallerError> {
+        let path = Self::path(game_dir);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| InstallerError::Unknown(format!("Invalid install manifest {:?}: {}", path, e)))
+    }
+
+    pub fn save(&self, game_dir: &Path) -> Result<(), InstallerError> {
+        let path = Self::path(game_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Delete the recorded files that still exist, returning their full paths.
+    /// Paths that would point outside the game directory are ignored.
+    pub fn remove_files(&self, game_dir: &Path) -> Result<Vec<PathBuf>, InstallerError> {
+        let mut removed = Vec::new();
+        for relative in &self.files {
+            if relative.is_absolute() || relative.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+                continue;
+            }
+            let path = game_dir.join(relative);
+            if path.is_file() || path.is_symlink() {
+                fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn record_registry_change(&mut self, change: RegistryChange) {
+        if !self.registry.contains(&change) {
+            self.registry.push(change);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::errors::InstallerError;
+use crate::utils::fs_utils::atomic_write;
+use crate::utils::vdf::VdfDocument;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Makes Proton load Geode's xinput1_4.dll even if the prefix override is missing
+pub const RECOMMENDED_LAUNCH_OPTIONS: &str = "WINEDLLOVERRIDES=\"xinput1_4=n,b\" %command%";
+/// The part of the recommended options merged into launch options the user already has
+const GEODE_OVERRIDE: &str = "xinput1_4=n,b";
+const OVERRIDES_VAR: &str = "WINEDLLOVERRIDES=";
+
+/// Edits per-game launch options in Steam's `userdata/<id>/config/localconfig.vdf`
+pub struct LaunchOptionsEditor;
+
+impl LaunchOptionsEditor {
+    /// Merge Geode's override into the launch options in a localconfig.vdf, backing it up first.
+    /// Returns the backup path, or `None` if the override was already there.
+    pub fn apply(localconfig: &Path, app_id: &str) -> Result<Option<PathBuf>, InstallerError> {
+        let content = fs::read_to_string(localconfig)?;
+        let Some(updated) = Self::apply_to_content(&content, app_id) else {
+            return Ok(None);
+        };
+
+        let backup = Self::backup_path(localconfig);
+        fs::copy(localconfig, &backup)?;
+        atomic_write(localconfig, updated.as_bytes())?;
+        Ok(Some(backup))
+    }
+
+    /// Returns the new file content, or `None` if nothing needs to change
+    pub fn apply_to_content(content: &str, app_id: &str) -> Option<String> {
+        let path = Self::launch_options_path(app_id);
+        let mut document = VdfDocument::parse(content);
+
+        let current = document.get_string(&path);
+        let merged = Self::merge(current.unwrap_or_default());
+        if current == Some(merged.as_str()) {
+            return None;
+        }
+
+        document.set_string(&path, &merged);
+        Some(document.serialize())
+    }
+
+    /// The game's launch options in a localconfig.vdf, if it has any
+    pub fn current(localconfig: &Path, app_id: &str) -> Result<Option<String>, InstallerError> {
+        let document = VdfDocument::parse(&fs::read_to_string(localconfig)?);
+        Ok(document.get_string(&Self::launch_options_path(app_id)).map(String::from))
+    }
+
+    /// Add Geode's override to existing launch options: into a WINEDLLOVERRIDES they already
+    /// set, else in front of them, keeping a single `%command%`. Options without `%command%`
+    /// are arguments to the game and go after it
+    pub fn merge(existing: &str) -> String {
+        let existing = existing.trim();
+        if existing.contains(GEODE_OVERRIDE) {
+            return existing.to_string();
+        }
+        if existing.is_empty() {
+            return RECOMMENDED_LAUNCH_OPTIONS.to_string();
+        }
+
+        if let Some(start) = existing.find(OVERRIDES_VAR) {
+            let (before, value) = existing.split_at(start + OVERRIDES_VAR.len());
+            return match value.strip_prefix('"') {
+                Some(quoted) => format!("{}\"{};{}", before, GEODE_OVERRIDE, quoted),
+                None => {
+                    let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                    format!("{}\"{};{}\"{}", before, GEODE_OVERRIDE, &value[..end], &value[end..])
+                }
+            };
+        }
+
+        let override_env = format!("{}\"{}\"", OVERRIDES_VAR, GEODE_OVERRIDE);
+        if existing.contains("%command%") {
+            format!("{} {}", override_env, existing)
+        } else {
+            format!("{} %command% {}", override_env, existing)
+        }
+    }
+
+    fn launch_options_path(app_id: &str) -> [&str; 7] {
+        ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", app_id, "LaunchOptions"]
+    }
+
+    fn backup_path(localconfig: &Path) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let mut name = localconfig.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".geode.bak.{}", timestamp));
+        localconfig.with_file_name(name)
+    }
+}
@@ -0,0 +1,42 @@
+use colored::Colorize;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::io::{self, Write};
+
+/// Records from dependencies (reqwest, rustls...) are left out, at debug level they drown ours
+const TARGET: &str = "geode_cli_installer";
+
+static LOGGER: Logger = Logger;
+
+/// Writes this crate's log records to the terminal: info to stdout, the rest to stderr
+pub struct Logger;
+
+impl Logger {
+    /// Install the logger; only the first call does anything
+    pub fn init(level: LevelFilter) {
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(level);
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level() && metadata.target().starts_with(TARGET)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            Level::Error => eprintln!("{}", record.args().to_string().red().bold()),
+            Level::Warn => eprintln!("{}", format!("⚠️  {}", record.args()).yellow()),
+            Level::Info => println!("{}", record.args()),
+            Level::Debug | Level::Trace => eprintln!("{} {}", "debug:".dimmed(), record.args()),
+        }
+    }
+
+    fn flush(&self) {
+        let _ = io::stdout().flush();
+    }
+}
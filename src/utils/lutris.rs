@@ -0,0 +1,61 @@
+use crate::utils::wine_games::{prefix_of, Launcher, WineGame, GD_EXECUTABLE};
+use crate::utils::yaml::YamlParser;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Game config directories, relative to home: current Lutris, older versions and the Flatpak
+const GAME_CONFIG_DIRS: &[&str] = &[
+    ".config/lutris/games",
+    ".local/share/lutris/games",
+    ".var/app/net.lutris.Lutris/config/lutris/games",
+    ".var/app/net.lutris.Lutris/data/lutris/games",
+];
+
+/// Lutris game configs, the YAML files it keeps one of per installed game
+pub struct Lutris;
+
+impl Lutris {
+    /// Games whose `game.exe` is Geometry Dash, from every config directory that exists
+    pub fn find_games(home: &Path) -> Vec<WineGame> {
+        let mut games: Vec<WineGame> = GAME_CONFIG_DIRS
+            .iter()
+            .filter_map(|dir| fs::read_dir(home.join(dir)).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "yml" || ext == "yaml"))
+            .filter_map(|path| {
+                let content = fs::read_to_string(&path).ok()?;
+                let slug = path.file_stem()?.to_string_lossy().into_owned();
+                Self::from_config(&content, &slug)
+            })
+            .collect();
+        games.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+        games.dedup_by(|a, b| a.prefix == b.prefix && a.game_dir == b.game_dir);
+        games
+    }
+
+    /// Read one game config. `game.prefix` is optional, Lutris then runs the game in the
+    /// prefix its executable is in; a relative `game.exe` is relative to the prefix
+    pub fn from_config(content: &str, slug: &str) -> Option<WineGame> {
+        let config = YamlParser::parse(content);
+        let exe = PathBuf::from(config.get("game.exe")?);
+        if !exe.file_name()?.to_string_lossy().eq_ignore_ascii_case(GD_EXECUTABLE) {
+            return None;
+        }
+
+        let prefix = config.get("game.prefix").map(PathBuf::from);
+        let exe = match &prefix {
+            Some(prefix) if exe.is_relative() => prefix.join(exe),
+            _ => exe,
+        };
+        let prefix = prefix.or_else(|| prefix_of(&exe))?;
+
+        Some(WineGame {
+            launcher: Launcher::Lutris,
+            name: config.get("name").cloned().unwrap_or_else(|| slug.to_string()),
+            game_dir: exe.parent()?.to_path_buf(),
+            prefix,
+        })
+    }
+}
@@ -1,2 +1,34 @@
 pub mod steam_game_finder;
-pub mod geode_installer;
\ No newline at end of file
+pub mod geode_installer;
+pub mod geode_mods;
+pub mod geode_version;
+pub mod api_cache;
+pub mod archive;
+pub mod backup;
+pub mod bottles;
+pub mod checksum;
+pub mod config;
+pub mod desktop_entry;
+pub mod diagnostics;
+pub mod download_cache;
+pub mod fs_utils;
+pub mod heroic;
+pub mod home;
+pub mod install_manifest;
+pub mod launch_options;
+pub mod logger;
+pub mod lutris;
+pub mod mod_index;
+pub mod observer;
+pub mod pe;
+pub mod process;
+pub mod proxy_dll;
+pub mod release_notes;
+pub mod semver;
+pub mod steam_deck;
+pub mod tls;
+pub mod vdf;
+pub mod wine;
+pub mod wine_games;
+pub mod wine_registry;
+pub mod yaml;
\ No newline at end of file
@@ -0,0 +1,68 @@
+use crate::errors::InstallerError;
+use crate::utils::checksum::Checksum;
+use serde_json::Value;
+use std::fs::File;
+use std::path::Path;
+use zip::ZipArchive;
+
+const GEODE_MODS_API_URL: &str = "https://api.geode-sdk.org/v1/mods";
+const PLATFORM: &str = "win";
+
+/// A mod version published on the Geode index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModRelease {
+    pub version: String,
+    pub download_link: String,
+    /// SHA-256 of the `.geode` package, when the index reports one
+    pub hash: Option<String>,
+}
+
+/// Queries against the Geode mod index
+pub struct ModIndex;
+
+impl ModIndex {
+    /// Latest version of `id` that runs on the given GD and loader versions
+    pub fn latest_version_url(id: &str, gd: Option<&str>, geode: &str) -> String {
+        let geode = geode.strip_prefix('v').unwrap_or(geode);
+        let mut url = format!("{}/{}/versions/latest?platforms={}&geode={}", GEODE_MODS_API_URL, id, PLATFORM, geode);
+        if let Some(gd) = gd {
+            url.push_str(&format!("&gd={}", gd));
+        }
+        url
+    }
+
+    pub fn parse_release(json: &Value) -> Result<ModRelease, InstallerError> {
+        if let Some(error) = json["error"].as_str()
+            && !error.is_empty()
+        {
+            return Err(InstallerError::Unknown(format!("Geode index error: {}", error)));
+        }
+
+        let payload = &json["payload"];
+        let field = |key: &str| {
+            payload[key]
+                .as_str()
+                .map(String::from)
+                .ok_or_else(|| InstallerError::Unknown(format!("Geode index response has no {}", key)))
+        };
+
+        Ok(ModRelease {
+            version: field("version")?,
+            download_link: field("download_link")?,
+            hash: payload["hash"].as_str().filter(|hash| !hash.is_empty()).map(String::from),
+        })
+    }
+
+    /// Check a downloaded package (whose SHA-256 is `sha256`) against the index's hash, or at
+    /// least that it's a readable zip, so an error page or a cut-off download never replaces a
+    /// working mod
+    pub fn check_package(release: &ModRelease, path: &Path, sha256: &str) -> Result<(), InstallerError> {
+        match &release.hash {
+            Some(hash) => Checksum::verify(hash, sha256),
+            None => {
+                ZipArchive::new(File::open(path)?)?;
+                Ok(())
+            }
+        }
+    }
+}
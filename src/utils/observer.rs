@@ -0,0 +1,128 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{info, warn, Level};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SPINNER_TICK: Duration = Duration::from_millis(100);
+
+/// The parts of an install that take long enough to show progress for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallStep {
+    /// Asking the API for the latest release, no progress is reported
+    FetchRelease,
+    /// Progress is in bytes
+    Download,
+    /// Progress is in archive entries, skipped ones included
+    Extract,
+    PatchRegistry,
+}
+
+/// Receives everything the installer reports while it works, so embedders can show it
+/// their own way instead of on the terminal
+pub trait InstallObserver: Send + Sync {
+    fn on_step(&self, step: InstallStep);
+
+    /// `step` is over, successfully or not
+    fn on_step_done(&self, _step: InstallStep) {}
+
+    /// Progress of the current step; `total` is 0 when it isn't known
+    fn on_progress(&self, done: u64, total: u64);
+
+    fn on_message(&self, message: &str);
+
+    /// Something went wrong that the installer worked around
+    fn on_warning(&self, message: &str) {
+        self.on_message(&format!("Warning: {}", message));
+    }
+}
+
+/// What the CLI shows: messages go through the `log` crate (see `Logger`), with indicatif
+/// progress bars unless info messages are turned off
+#[derive(Default)]
+pub struct TerminalObserver {
+    bar: Mutex<Option<ProgressBar>>,
+}
+
+impl TerminalObserver {
+    fn bar(template: &str) -> ProgressBar {
+        let style = ProgressStyle::default_bar()
+            .template(template)
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars("#>-");
+        ProgressBar::new(0).with_style(style)
+    }
+}
+
+impl InstallObserver for TerminalObserver {
+    fn on_step(&self, step: InstallStep) {
+        if !log::log_enabled!(Level::Info) {
+            return;
+        }
+        let bar = match step {
+            InstallStep::FetchRelease => {
+                let spinner = ProgressBar::new_spinner();
+                spinner.set_message("Fetching latest Geode version...");
+                spinner.enable_steady_tick(SPINNER_TICK);
+                spinner
+            }
+            InstallStep::Download => Self::bar(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+            ),
+            InstallStep::Extract => Self::bar("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files {msg}"),
+            InstallStep::PatchRegistry => {
+                info!("Patching Wine registry...");
+                return;
+            }
+        };
+        *self.bar.lock().unwrap() = Some(bar);
+    }
+
+    fn on_step_done(&self, step: InstallStep) {
+        let Some(bar) = self.bar.lock().unwrap().take() else {
+            return;
+        };
+        match step {
+            InstallStep::FetchRelease => bar.finish_and_clear(),
+            InstallStep::Download => bar.finish_with_message("Download complete"),
+            InstallStep::Extract => bar.finish_with_message("Extraction complete"),
+            InstallStep::PatchRegistry => {}
+        }
+    }
+
+    fn on_progress(&self, done: u64, total: u64) {
+        if let Some(bar) = self.bar.lock().unwrap().as_ref() {
+            let first = bar.position() == 0;
+            bar.set_length(total);
+            bar.set_position(done);
+            // A resumed download starts part way, keep those bytes out of the speed and ETA
+            if first {
+                bar.reset_eta();
+            }
+        }
+    }
+
+    fn on_message(&self, message: &str) {
+        match self.bar.lock().unwrap().as_ref() {
+            Some(bar) => bar.suspend(|| info!("{}", message)),
+            None => info!("{}", message),
+        }
+    }
+
+    fn on_warning(&self, message: &str) {
+        match self.bar.lock().unwrap().as_ref() {
+            Some(bar) => bar.suspend(|| warn!("{}", message)),
+            None => warn!("{}", message),
+        }
+    }
+}
+
+/// Drops everything
+pub struct SilentObserver;
+
+impl InstallObserver for SilentObserver {
+    fn on_step(&self, _step: InstallStep) {}
+
+    fn on_progress(&self, _done: u64, _total: u64) {}
+
+    fn on_message(&self, _message: &str) {}
+}
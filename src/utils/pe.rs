@@ -0,0 +1,212 @@
+use crate::errors::InstallerError;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const DOS_MAGIC: &[u8; 2] = b"MZ";
+const PE_SIGNATURE: &[u8; 4] = b"PE\0\0";
+const PE_OFFSET_POINTER: u64 = 0x3C;
+const RT_VERSION: u32 = 16;
+const FIXED_FILE_INFO_SIGNATURE: [u8; 4] = 0xFEEF04BDu32.to_le_bytes();
+
+/// Target machine of a PE (Portable Executable) image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    I386,
+    Amd64,
+    Arm64,
+    Other(u16),
+}
+
+impl Machine {
+    fn from_raw(value: u16) -> Self {
+        match value {
+            0x014c => Machine::I386,
+            0x8664 => Machine::Amd64,
+            0xaa64 => Machine::Arm64,
+            other => Machine::Other(other),
+        }
+    }
+
+    pub fn is_64_bit(&self) -> bool {
+        matches!(self, Machine::Amd64 | Machine::Arm64)
+    }
+}
+
+impl std::fmt::Display for Machine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Machine::I386 => write!(f, "x86 (32-bit)"),
+            Machine::Amd64 => write!(f, "x86-64 (64-bit)"),
+            Machine::Arm64 => write!(f, "ARM64"),
+            Machine::Other(value) => write!(f, "unknown machine 0x{:04x}", value),
+        }
+    }
+}
+
+/// Minimal PE header reader, only looks at the bits needed to tell the target machine
+pub struct PeReader;
+
+impl PeReader {
+    pub fn read_machine(path: &Path) -> Result<Machine, InstallerError> {
+        let mut file = File::open(path)?;
+        Self::machine_from_reader(&mut file)
+            .map_err(|e| InstallerError::Unknown(format!("{:?}: {}", path, e)))
+    }
+
+    pub fn machine_from_reader<R: Read + Seek>(reader: &mut R) -> Result<Machine, InstallerError> {
+        let mut dos_magic = [0u8; 2];
+        reader.read_exact(&mut dos_magic)?;
+        if &dos_magic != DOS_MAGIC {
+            return Err(InstallerError::Unknown("Not a Windows executable (missing MZ header)".into()));
+        }
+
+        reader.seek(SeekFrom::Start(PE_OFFSET_POINTER))?;
+        let pe_offset = read_u32(reader)?;
+
+        reader.seek(SeekFrom::Start(pe_offset as u64))?;
+        let mut signature = [0u8; 4];
+        reader.read_exact(&mut signature)?;
+        if &signature != PE_SIGNATURE {
+            return Err(InstallerError::Unknown("Not a Windows executable (missing PE signature)".into()));
+        }
+
+        Ok(Machine::from_raw(read_u16(reader)?))
+    }
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> Result<u16, InstallerError> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, InstallerError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// Metadata embedded in a PE image
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeMetadata {
+    /// `FileVersion` from the VERSIONINFO resource, as `major.minor.patch`
+    pub file_version: Option<String>,
+    /// Link time from the COFF header, seconds since the Unix epoch
+    pub timestamp: Option<u32>,
+}
+
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_offset: u32,
+    raw_size: u32,
+}
+
+impl PeReader {
+    pub fn read_metadata(path: &Path) -> Result<PeMetadata, InstallerError> {
+        let data = std::fs::read(path)?;
+        Self::parse_metadata(&data)
+            .ok_or_else(|| InstallerError::Unknown(format!("{:?} is not a valid PE image", path)))
+    }
+
+    /// Walks the headers and resource directory far enough to find the version resource
+    pub fn parse_metadata(data: &[u8]) -> Option<PeMetadata> {
+        if data.get(..2)? != DOS_MAGIC {
+            return None;
+        }
+        let pe_offset = le_u32(data, PE_OFFSET_POINTER as usize)? as usize;
+        if data.get(pe_offset..pe_offset + 4)? != PE_SIGNATURE {
+            return None;
+        }
+
+        let coff = pe_offset + 4;
+        let section_count = le_u16(data, coff + 2)? as usize;
+        let timestamp = le_u32(data, coff + 4)?;
+        let optional_header_size = le_u16(data, coff + 16)? as usize;
+        let optional_header = coff + 20;
+
+        let data_directories = match le_u16(data, optional_header)? {
+            0x10b => optional_header + 96,
+            0x20b => optional_header + 112,
+            _ => return None,
+        };
+        let resource_rva = le_u32(data, data_directories + 2 * 8)?;
+
+        let sections: Vec<Section> = (0..section_count)
+            .filter_map(|i| {
+                let header = optional_header + optional_header_size + i * 40;
+                Some(Section {
+                    virtual_size: le_u32(data, header + 8)?,
+                    virtual_address: le_u32(data, header + 12)?,
+                    raw_size: le_u32(data, header + 16)?,
+                    raw_offset: le_u32(data, header + 20)?,
+                })
+            })
+            .collect();
+
+        let file_version = (resource_rva != 0)
+            .then(|| Self::find_file_version(data, &sections, resource_rva))
+            .flatten();
+
+        Some(PeMetadata {
+            file_version,
+            timestamp: (timestamp != 0).then_some(timestamp),
+        })
+    }
+
+    fn find_file_version(data: &[u8], sections: &[Section], resource_rva: u32) -> Option<String> {
+        let root = rva_to_offset(sections, resource_rva)?;
+
+        // type (RT_VERSION) -> name (first) -> language (first) -> data entry
+        let names = resource_subdirectory(data, root, root, Some(RT_VERSION))?;
+        let languages = resource_subdirectory(data, root, names, None)?;
+        let data_entry = root + resource_entry_offset(data, languages, None)? as usize;
+
+        let version_rva = le_u32(data, data_entry)?;
+        let version_size = le_u32(data, data_entry + 4)? as usize;
+        let start = rva_to_offset(sections, version_rva)?;
+        let version_info = data.get(start..start + version_size)?;
+
+        Self::parse_fixed_file_version(version_info)
+    }
+
+    /// Find `VS_FIXEDFILEINFO` inside a `VS_VERSIONINFO` blob and format its file version
+    pub fn parse_fixed_file_version(version_info: &[u8]) -> Option<String> {
+        let signature = version_info
+            .windows(4)
+            .position(|window| window == FIXED_FILE_INFO_SIGNATURE)?;
+        let version_ms = le_u32(version_info, signature + 8)?;
+        let version_ls = le_u32(version_info, signature + 12)?;
+
+        Some(format!("{}.{}.{}", version_ms >> 16, version_ms & 0xffff, version_ls >> 16))
+    }
+}
+
+fn rva_to_offset(sections: &[Section], rva: u32) -> Option<usize> {
+    sections.iter()
+        .find(|s| rva >= s.virtual_address && rva < s.virtual_address + s.virtual_size.max(s.raw_size))
+        .map(|s| (rva - s.virtual_address + s.raw_offset) as usize)
+}
+
+/// Offset of the subdirectory for the entry with `id` (or the first entry)
+fn resource_subdirectory(data: &[u8], root: usize, directory: usize, id: Option<u32>) -> Option<usize> {
+    let offset = resource_entry_offset(data, directory, id)?;
+    (offset & 0x8000_0000 != 0).then(|| root + (offset & 0x7fff_ffff) as usize)
+}
+
+fn resource_entry_offset(data: &[u8], directory: usize, id: Option<u32>) -> Option<u32> {
+    let count = le_u16(data, directory + 12)? as usize + le_u16(data, directory + 14)? as usize;
+    (0..count)
+        .map(|i| directory + 16 + i * 8)
+        .find(|&entry| id.is_none_or(|id| le_u32(data, entry) == Some(id)))
+        .and_then(|entry| le_u32(data, entry + 4))
+}
+
+fn le_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+/// Looks at `/proc` to find running processes
+pub struct ProcessScanner;
+
+impl ProcessScanner {
+    /// Whether a process with this exact name (as in `/proc/<pid>/comm`) is running
+    pub fn is_running(name: &str) -> bool {
+        Self::pids(Path::new("/proc"))
+            .into_iter()
+            .filter_map(|pid| fs::read_to_string(Path::new("/proc").join(pid.to_string()).join("comm")).ok())
+            .any(|comm| comm.trim_end() == name)
+    }
+
+    /// A process running `executable` (a Windows program started through Wine or Proton) or
+    /// any binary inside `game_dir`, as its pid
+    pub fn find_game(game_dir: &Path, executable: &str) -> Option<u32> {
+        Self::find_game_in(Path::new("/proc"), game_dir, executable)
+    }
+
+    /// `find_game` against another `/proc`
+    pub fn find_game_in(proc_root: &Path, game_dir: &Path, executable: &str) -> Option<u32> {
+        let game_dir = game_dir.canonicalize().unwrap_or_else(|_| game_dir.to_path_buf());
+        Self::pids(proc_root).into_iter().find(|pid| {
+            let dir = proc_root.join(pid.to_string());
+            if fs::read_link(dir.join("exe")).is_ok_and(|exe| exe.starts_with(&game_dir)) {
+                return true;
+            }
+            // Under Wine the exe is the Wine loader; the Windows path shows up in the arguments
+            let Ok(cmdline) = fs::read(dir.join("cmdline")) else {
+                return false;
+            };
+            cmdline.split(|&b| b == 0).any(|arg| {
+                let arg = String::from_utf8_lossy(arg);
+                let name = arg.rsplit(['/', '\\']).next().unwrap_or_default();
+                name.eq_ignore_ascii_case(executable)
+            })
+        })
+    }
+
+    fn pids(proc_root: &Path) -> Vec<u32> {
+        let Ok(entries) = fs::read_dir(proc_root) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect()
+    }
+}
@@ -0,0 +1,71 @@
+use crate::utils::fs_utils::find_file_case_insensitive;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Loader metadata that may name the DLL Geode hooks in through
+const LOADER_METADATA: &str = "geode/resources/geode.loader/mod.json";
+const METADATA_KEY: &str = "proxy-dll";
+const DEFAULT_PROXY_DLL: &str = "xinput1_4";
+
+/// Where the proxy DLL name came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyDllSource {
+    Metadata,
+    FileScan,
+    Default,
+}
+
+/// The DLL Windows loads from the game directory that in turn loads Geode,
+/// and so the one Wine has to be told to prefer the native copy of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyDll {
+    /// Lowercase name without the `.dll` extension, as used in DllOverrides
+    pub name: String,
+    pub source: ProxyDllSource,
+}
+
+impl ProxyDll {
+    /// The loader's declared proxy DLL, else an `xinput*.dll` next to the game, else `xinput1_4`
+    pub fn detect(game_dir: &Path) -> Self {
+        if let Some(name) = Self::from_metadata(game_dir) {
+            return Self::new(&name, ProxyDllSource::Metadata);
+        }
+        if let Some(name) = Self::from_files(game_dir) {
+            return Self::new(&name, ProxyDllSource::FileScan);
+        }
+        Self::new(DEFAULT_PROXY_DLL, ProxyDllSource::Default)
+    }
+
+    fn new(name: &str, source: ProxyDllSource) -> Self {
+        let name = name.to_ascii_lowercase();
+        let name = name.strip_suffix(".dll").unwrap_or(&name).to_string();
+        Self { name, source }
+    }
+
+    fn from_metadata(game_dir: &Path) -> Option<String> {
+        let json: Value = serde_json::from_str(&fs::read_to_string(game_dir.join(LOADER_METADATA)).ok()?).ok()?;
+        json[METADATA_KEY].as_str().filter(|name| Self::is_valid_name(name)).map(String::from)
+    }
+
+    /// DLL names end up unescaped in `user.reg` and regedit scripts, so only plain file name
+    /// characters are allowed
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    }
+
+    fn from_files(game_dir: &Path) -> Option<String> {
+        // The default name first, so a stray extra xinput DLL doesn't win over it
+        if find_file_case_insensitive(game_dir, &format!("{}.dll", DEFAULT_PROXY_DLL)).is_some() {
+            return Some(DEFAULT_PROXY_DLL.to_string());
+        }
+
+        let mut names: Vec<String> = fs::read_dir(game_dir).ok()?
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_ascii_lowercase())
+            .filter(|name| name.starts_with("xinput") && name.ends_with(".dll") && Self::is_valid_name(name))
+            .collect();
+        names.sort();
+        names.into_iter().next()
+    }
+}
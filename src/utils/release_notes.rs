@@ -0,0 +1,48 @@
+use crate::utils::semver::SemVer;
+use serde_json::Value;
+
+/// Notes for one Geode release, from the GitHub releases API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseNotes {
+    pub tag: String,
+    pub name: String,
+    pub published_at: Option<String>,
+    pub body: String,
+}
+
+impl ReleaseNotes {
+    pub fn parse_list(json: &Value) -> Vec<Self> {
+        json.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|release| {
+                let tag = release["tag_name"].as_str()?.to_string();
+                Some(Self {
+                    name: release["name"].as_str().filter(|n| !n.is_empty()).unwrap_or(&tag).to_string(),
+                    published_at: release["published_at"].as_str().map(String::from),
+                    body: release["body"].as_str().unwrap_or_default().to_string(),
+                    tag,
+                })
+            })
+            .collect()
+    }
+
+    /// Releases newer than `installed` up to and including `latest`, newest first.
+    /// Without an installed version only the latest release is returned.
+    pub fn between(releases: Vec<Self>, installed: Option<&str>, latest: &str) -> Vec<Self> {
+        let latest_version = SemVer::parse(latest);
+        let installed_version = installed.and_then(SemVer::parse);
+
+        let mut selected: Vec<(SemVer, Self)> = releases
+            .into_iter()
+            .filter_map(|release| Some((SemVer::parse(&release.tag)?, release)))
+            .filter(|(version, release)| match (&installed_version, &latest_version) {
+                (Some(installed), Some(latest)) => version > installed && version <= latest,
+                _ => release.tag == latest,
+            })
+            .collect();
+
+        selected.sort_by(|a, b| b.0.cmp(&a.0));
+        selected.into_iter().map(|(_, release)| release).collect()
+    }
+}
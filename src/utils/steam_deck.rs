@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const OS_RELEASE: &str = "/etc/os-release";
+/// SteamOS mounts SD cards and USB drives here, under `deck/` on current versions
+pub const MEDIA_ROOT: &str = "/run/media";
+
+/// Shown before installing on a Deck
+pub const NOTES: &[&str] = &[
+    "Steam Deck detected. Run this from Desktop Mode; Game Mode can't show the prompts.",
+    "Games on an SD card keep their Proton prefix on the card too, in its steamapps/compatdata.",
+    "SteamOS's root filesystem is read-only, but nothing outside your home directory and the game's library is changed, so there's no need to run steamos-readonly disable.",
+];
+
+pub struct SteamDeck;
+
+impl SteamDeck {
+    /// Whether this is SteamOS, going by `/etc/os-release`
+    pub fn detect() -> bool {
+        fs::read_to_string(OS_RELEASE).is_ok_and(|content| Self::is_steamos(&content))
+    }
+
+    pub fn is_steamos(os_release: &str) -> bool {
+        os_release.lines().filter_map(|line| line.split_once('=')).any(|(key, value)| {
+            let value = value.trim_matches('"');
+            (key == "ID" && value == "steamos") || (key == "VARIANT_ID" && value == "steamdeck")
+        })
+    }
+
+    /// Libraries at the root of mounted SD cards and drives: `<media>/deck/<label>/steamapps`,
+    /// and `<media>/<device>/steamapps` from older SteamOS versions
+    pub fn library_folders(media_root: &Path) -> Vec<PathBuf> {
+        let mounts = |dir: &Path| -> Vec<PathBuf> {
+            fs::read_dir(dir)
+                .map(|entries| entries.filter_map(Result::ok).map(|entry| entry.path()).collect())
+                .unwrap_or_default()
+        };
+
+        let mut folders: Vec<PathBuf> = mounts(media_root)
+            .iter()
+            .flat_map(|mount| {
+                let mut candidates = vec![mount.clone()];
+                candidates.extend(mounts(mount));
+                candidates
+            })
+            .map(|mount| mount.join("steamapps"))
+            .filter(|steamapps| steamapps.is_dir())
+            .collect();
+        folders.sort();
+        folders
+    }
+}
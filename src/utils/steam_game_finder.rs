@@ -1,7 +1,32 @@
-use homedir::my_home;
+use crate::errors::InstallerError;
+use crate::utils::fs_utils::find_file_case_insensitive;
+use crate::utils::home::HomeDir;
+use crate::utils::steam_deck::{SteamDeck, MEDIA_ROOT};
+use crate::utils::vdf::VdfParser;
+use log::{debug, warn};
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::path::PathBuf;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::SystemTime;
+
+/// Per-app data directory of the Flatpak Steam, relative to the home directory
+const FLATPAK_DATA_DIR: &str = ".var/app/com.valvesoftware.Steam";
+/// Data directory of the Snap Steam, relative to the home directory
+const SNAP_DATA_DIR: &str = "snap/steam";
+/// Where removable and extra drives get mounted, for `deep_scan`
+const MOUNT_ROOTS: &[&str] = &["/run/media", "/media", "/mnt"];
+const MOUNT_SCAN_DEPTH: usize = 3;
+/// Steam root to use instead of searching, when `--steam-root` isn't given
+pub const STEAM_ROOT_ENV: &str = "GEODE_STEAM_ROOT";
+/// Set by Steam for games it launches, and by some users to keep prefixes outside `compatdata`
+const COMPAT_DATA_ENV: &str = "STEAM_COMPAT_DATA_PATH";
+/// Prefixes made by Proton older than this fail to load Geode
+pub const MIN_PROTON_MAJOR: u32 = 7;
 
 #[derive(Debug, Clone)]
 #[allow(unused)]
@@ -10,60 +35,533 @@ pub struct GameInfo {
     pub game_path: PathBuf,
     pub proton_prefix: Option<PathBuf>,
     pub library_path: PathBuf,
+    /// Found by scanning `common/` because the app manifest is missing, so Steam doesn't know about it
+    pub manifest_missing: bool,
+    /// How the game directory was found
+    pub game_via: String,
+    /// How the prefix was found
+    pub prefix_via: Option<String>,
+    /// Proton version the prefix was last set up by, e.g. `8.0-105` or `GE-Proton9-20`
+    pub proton_version: Option<String>,
 }
 
+/// Wine prefix architecture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixArch {
+    Win32,
+    Win64,
+}
+
+impl std::fmt::Display for PrefixArch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrefixArch::Win32 => write!(f, "win32"),
+            PrefixArch::Win64 => write!(f, "win64"),
+        }
+    }
+}
+
+/// How a library folder was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LibrarySource {
+    /// `steamapps` in the Steam root
+    Main,
+    LibraryFoldersVdf,
+    SteamDeckSdCard,
+    DeepScan,
+}
+
+impl std::fmt::Display for LibrarySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LibrarySource::Main => write!(f, "the Steam root"),
+            LibrarySource::LibraryFoldersVdf => write!(f, "libraryfolders.vdf"),
+            LibrarySource::SteamDeckSdCard => write!(f, "the Steam Deck SD card scan"),
+            LibrarySource::DeepScan => write!(f, "--deep-scan"),
+        }
+    }
+}
+
+/// A Steam library folder (its `steamapps` directory) as seen by `scan`
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryScan {
+    pub path: PathBuf,
+    pub source: LibrarySource,
+    pub has_game: bool,
+    pub prefix: Option<PathBuf>,
+}
+
+/// A `compatdata/<app id>/pfx` found in one of the libraries
+#[derive(Debug, Clone)]
+pub struct ProtonPrefix {
+    pub path: PathBuf,
+    /// When Wine last wrote the prefix's `user.reg`, or the prefix directory itself
+    pub modified: Option<SystemTime>,
+}
+
+/// Everything detection found, in one serializable structure
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanResult {
+    pub home: Option<PathBuf>,
+    /// Every candidate location that holds a Steam installation
+    pub steam_roots: Vec<PathBuf>,
+    /// The root detection uses
+    pub steam_root: Option<PathBuf>,
+    pub libraries: Vec<LibraryScan>,
+    pub game_path: Option<PathBuf>,
+    pub prefixes: Vec<PathBuf>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FinderOptions {
+    /// Use this directory as home instead of `$HOME`/passwd
+    pub home: Option<PathBuf>,
+    /// Use this Steam root instead of searching the usual locations
+    pub steam_root: Option<PathBuf>,
+    /// Also look for libraries on every mounted drive, for ones libraryfolders.vdf doesn't list
+    pub deep_scan: bool,
+    /// Also look for Proton prefixes in `<prefix_root>/<app id>/pfx`
+    pub prefix_root: Option<PathBuf>,
+}
+
+/// Detection results are computed once and reused until `rescan` is called
 pub struct SteamGameFinder {
+    options: FinderOptions,
+    home: Result<HomeDir, String>,
     steam_root: Option<PathBuf>,
+    steam_root_via: Option<String>,
     library_folders: Vec<PathBuf>,
+    /// Where each of `library_folders` came from, for `describe_library` and `--doctor`
+    library_sources: HashMap<PathBuf, LibrarySource>,
+    steam_deck: bool,
+    /// A Mutex rather than a RefCell so installers can be shared with async tasks
+    games: Mutex<HashMap<String, Option<GameInfo>>>,
 }
 
 impl SteamGameFinder {
     pub fn new() -> Self {
-        let steam_root = Self::find_steam_root();
-        let library_folders = Self::discover_library_folders(&steam_root);
-        
+        Self::build(FinderOptions::default(), None)
+    }
+
+    /// Fails if an explicit Steam root was given but doesn't look like one
+    pub fn with_options(options: FinderOptions) -> Result<Self, InstallerError> {
+        let steam_root = options.steam_root.as_deref().map(Self::validate_steam_root).transpose()?;
+        Ok(Self::build(options, steam_root))
+    }
+
+    /// Detect everything again, e.g. after Steam installed or moved the game
+    pub fn rescan(&mut self) -> Result<(), InstallerError> {
+        *self = Self::with_options(self.options.clone())?;
+        Ok(())
+    }
+
+    fn build(options: FinderOptions, steam_root: Option<PathBuf>) -> Self {
+        let home = HomeDir::resolve(options.home.as_deref()).map_err(|e| e.to_string());
+        // An unusable GEODE_STEAM_ROOT falls back to auto-detection, but not silently
+        let from_env = match Self::steam_root_from_env().filter(|_| steam_root.is_none()) {
+            Some(Ok(root)) => Some(root),
+            Some(Err(e)) => {
+                warn!("Ignoring {}: {}", STEAM_ROOT_ENV, e);
+                None
+            }
+            None => None,
+        };
+        let (steam_root, steam_root_via) = match steam_root {
+            Some(root) => (Some(root), Some("--steam-root".to_string())),
+            None if let Some(root) = from_env => (Some(root), Some(format!("{} env", STEAM_ROOT_ENV))),
+            None => match home.as_ref().ok().and_then(|home| Self::find_steam_root(&home.path)) {
+                Some(root) => {
+                    let source = home.as_ref().map(|h| h.source.to_string()).unwrap_or_default();
+                    let sandboxed = |dir: &str| home.as_ref().is_ok_and(|h| root.starts_with(h.path.join(dir)));
+                    let via = if sandboxed(FLATPAK_DATA_DIR) {
+                        format!("Flatpak install under {}", source)
+                    } else if sandboxed(SNAP_DATA_DIR) {
+                        format!("Snap install under {}", source)
+                    } else {
+                        format!("standard location under {}", source)
+                    };
+                    (Some(root), Some(via))
+                }
+                None => (None, None),
+            },
+        };
+        let steam_deck = SteamDeck::detect();
+        let main_library = steam_root.as_ref().map(|root| root.join("steamapps"));
+        let mut found: Vec<(PathBuf, LibrarySource)> = Self::discover_library_folders(&steam_root)
+            .into_iter()
+            .map(|path| {
+                let source = if Some(&path) == main_library.as_ref() { LibrarySource::Main } else { LibrarySource::LibraryFoldersVdf };
+                (path, source)
+            })
+            .collect();
+        // SD cards are usually in libraryfolders.vdf already, this catches ones it's out of date about
+        if steam_deck {
+            found.extend(SteamDeck::library_folders(Path::new(MEDIA_ROOT)).into_iter().map(|path| (path, LibrarySource::SteamDeckSdCard)));
+        }
+        if options.deep_scan {
+            let roots: Vec<PathBuf> = MOUNT_ROOTS.iter().map(PathBuf::from).collect();
+            found.extend(Self::scan_mounts(&roots).into_iter().map(|path| (path, LibrarySource::DeepScan)));
+        }
+        // The first way a library was found is the one it's kept under
+        let mut library_sources = HashMap::new();
+        for (path, source) in &found {
+            library_sources.entry(path.clone()).or_insert(*source);
+        }
+        let library_folders = Self::deduplicate_paths(found.into_iter().map(|(path, _)| path).collect());
+
         Self {
+            options,
+            home,
             steam_root,
+            steam_root_via,
             library_folders,
+            library_sources,
+            steam_deck,
+            games: Mutex::new(HashMap::new()),
         }
     }
 
+    /// `GEODE_STEAM_ROOT` if it's set, validated like `--steam-root`
+    fn steam_root_from_env() -> Option<Result<PathBuf, InstallerError>> {
+        env::var_os(STEAM_ROOT_ENV)
+            .filter(|value| !value.is_empty())
+            .map(|value| Self::validate_steam_root(Path::new(&value)))
+    }
+
+    fn validate_steam_root(path: &Path) -> Result<PathBuf, InstallerError> {
+        if !path.join("steamapps").is_dir() {
+            return Err(InstallerError::Init(format!(
+                "Steam root {:?} has no steamapps directory",
+                path
+            )));
+        }
+        Ok(path.to_path_buf())
+    }
+
+    /// The home directory used for detection, or why it couldn't be determined
+    pub fn home(&self) -> Result<&HomeDir, &str> {
+        self.home.as_ref().map_err(String::as_str)
+    }
+
     pub fn steam_root(&self) -> Option<&PathBuf> {
         self.steam_root.as_ref()
     }
 
+    /// How the Steam root was found
+    pub fn steam_root_via(&self) -> Option<&str> {
+        self.steam_root_via.as_deref()
+    }
+
+    /// Running on SteamOS
+    pub fn is_steam_deck(&self) -> bool {
+        self.steam_deck
+    }
+
 
     #[allow(unused)]
     pub fn library_folders(&self) -> &[PathBuf] {
         &self.library_folders
     }
 
+    /// Proton builds Steam can run the game with: official ones installed as tools in a
+    /// library, and custom ones (like GE-Proton) in `compatibilitytools.d`
+    pub fn proton_installs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = self.library_folders.iter().map(|library| library.join("common")).collect();
+        dirs.extend(self.steam_root.iter().map(|root| root.join("compatibilitytools.d")));
+
+        let mut installs: Vec<PathBuf> = dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.join("proton").is_file())
+            .collect();
+        installs.sort();
+        installs
+    }
+
+    /// The localconfig.vdf of the account that logged in last (`MostRecent` in
+    /// config/loginusers.vdf), when there is one
+    pub fn active_localconfig(&self) -> Option<PathBuf> {
+        let steam_root = self.steam_root.as_ref()?;
+        let users = VdfParser::parse_file(&steam_root.join("config/loginusers.vdf"));
+        let path = Self::most_recent_account(&users).map(|account| {
+            steam_root.join("userdata").join(account.to_string()).join("config/localconfig.vdf")
+        })?;
+        path.exists().then_some(path)
+    }
+
+    /// The userdata account id (the low 32 bits of the SteamID64) of the most recent login
+    pub fn most_recent_account(login_users: &HashMap<String, String>) -> Option<u64> {
+        login_users
+            .iter()
+            .filter(|(key, value)| key.to_ascii_lowercase().ends_with(".mostrecent") && value.as_str() == "1")
+            .filter_map(|(key, _)| key.split('.').nth(1)?.parse::<u64>().ok())
+            .map(|steam_id| steam_id & 0xFFFF_FFFF)
+            .min()
+    }
+
+    /// Every `userdata/<account>/config/localconfig.vdf` under the Steam root
+    pub fn localconfig_files(&self) -> Vec<PathBuf> {
+        let Some(steam_root) = &self.steam_root else {
+            return Vec::new();
+        };
+        let Ok(accounts) = fs::read_dir(steam_root.join("userdata")) else {
+            return Vec::new();
+        };
+
+        accounts
+            .filter_map(Result::ok)
+            .map(|account| account.path().join("config/localconfig.vdf"))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
     pub fn get_game_info(&self, app_id: &str) -> Option<GameInfo> {
+        if let Some(cached) = self.games.lock().unwrap().get(app_id) {
+            return cached.clone();
+        }
+
+        let info = self.find_game_info(app_id);
+        self.games.lock().unwrap().insert(app_id.to_string(), info.clone());
+        info
+    }
+
+    fn find_game_info(&self, app_id: &str) -> Option<GameInfo> {
         let (game_path, library_path) = self.find_game_by_appid(app_id)?;
-        let proton_prefix = self.find_proton_prefix(app_id, Some(&library_path));
+        let (proton_prefix, prefix_via) = self.find_proton_prefix(app_id, Some(&library_path)).unzip();
+
+        Some(GameInfo {
+            app_id: app_id.to_string(),
+            game_via: format!("appmanifest_{}.acf in {}", app_id, self.describe_library(&library_path)),
+            game_path,
+            library_path,
+            proton_version: proton_prefix.as_deref().and_then(Self::detect_proton_version),
+            proton_prefix,
+            prefix_via,
+            manifest_missing: false,
+        })
+    }
+
+    /// Every library with a copy of the game, each with its own Proton prefix if it has one
+    pub fn find_all_game_installs(&self, app_id: &str) -> Vec<GameInfo> {
+        self.library_folders.iter()
+            .filter_map(|library| self.check_library_for_game(library, app_id))
+            .map(|(game_path, library_path)| {
+                let proton_prefix = Self::check_compatdata(&library_path, app_id);
+                GameInfo {
+                    app_id: app_id.to_string(),
+                    game_via: format!("appmanifest_{}.acf in {}", app_id, self.describe_library(&library_path)),
+                    prefix_via: proton_prefix.as_ref().map(|_| "compatdata in the game's library".to_string()),
+                    proton_version: proton_prefix.as_deref().and_then(Self::detect_proton_version),
+                    proton_prefix,
+                    game_path,
+                    library_path,
+                    manifest_missing: false,
+                }
+            })
+            .collect()
+    }
+
+    /// Fallback for when the app manifest is gone (manual copy, interrupted Steam operation):
+    /// the first directory under a library's `common/` that contains `executable`
+    pub fn find_in_common(&self, app_id: &str, executable: &str) -> Option<GameInfo> {
+        let (game_path, library_path) = self.library_folders.iter().find_map(|library| {
+            let mut dirs: Vec<PathBuf> = fs::read_dir(library.join("common")).ok()?
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect();
+            dirs.sort();
+            dirs.into_iter()
+                .find(|dir| find_file_case_insensitive(dir, executable).is_some())
+                .map(|dir| (dir, library.clone()))
+        })?;
+        let (proton_prefix, prefix_via) = self.find_proton_prefix(app_id, Some(&library_path)).unzip();
 
         Some(GameInfo {
             app_id: app_id.to_string(),
+            game_via: format!("scan of common/ in {} (no app manifest)", self.describe_library(&library_path)),
             game_path,
             library_path,
+            proton_version: proton_prefix.as_deref().and_then(Self::detect_proton_version),
             proton_prefix,
+            prefix_via,
+            manifest_missing: true,
         })
     }
 
-    fn find_steam_root() -> Option<PathBuf> {
-        let home = my_home().ok()??;
+    /// Every Proton prefix for `app_id` across the libraries and prefix roots, most recently used first
+    pub fn find_proton_prefixes(&self, app_id: &str) -> Vec<ProtonPrefix> {
+        let mut paths: Vec<PathBuf> = self.library_folders.iter()
+            .filter_map(|library| Self::check_compatdata(library, app_id))
+            .collect();
+        for (root, _) in self.prefix_roots() {
+            let path = root.join(app_id).join("pfx");
+            if path.exists() && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        let mut prefixes: Vec<ProtonPrefix> = paths.into_iter()
+            .map(|path| {
+                let modified = fs::metadata(path.join("user.reg"))
+                    .or_else(|_| fs::metadata(&path))
+                    .and_then(|metadata| metadata.modified())
+                    .ok();
+                ProtonPrefix { path, modified }
+            })
+            .collect();
+        prefixes.sort_by_key(|prefix| std::cmp::Reverse(prefix.modified));
+        prefixes
+    }
+
+    /// Collect all detection results for `app_id`, including problems that normal
+    /// lookups silently skip over
+    pub fn scan(&self, app_id: &str) -> ScanResult {
+        let mut warnings = Vec::new();
+
+        let home = match &self.home {
+            Ok(home) => Some(home.path.clone()),
+            Err(e) => {
+                warnings.push(e.clone());
+                None
+            }
+        };
+
+        if self.options.steam_root.is_none()
+            && let Some(Err(_)) = Self::steam_root_from_env()
+        {
+            warnings.push(format!("Ignoring {}, it has no steamapps directory", STEAM_ROOT_ENV));
+        }
+
+        let mut steam_roots = home.as_deref().map(Self::find_steam_roots).unwrap_or_default();
+        if let Some(root) = &self.steam_root
+            && !steam_roots.contains(root)
+        {
+            // An explicit --steam-root or GEODE_STEAM_ROOT that isn't in a standard location
+            steam_roots.insert(0, root.clone());
+        }
+        if steam_roots.len() > 1
+            && let Some(root) = &self.steam_root
+        {
+            warnings.push(format!("Found {} Steam installations, using {:?}", steam_roots.len(), root));
+        }
+
+        if let Some(root) = &self.steam_root {
+            for path in Self::library_paths_from_vdf(root) {
+                if !path.exists() {
+                    warnings.push(format!("Library {:?} from libraryfolders.vdf doesn't exist", path));
+                }
+            }
+        }
 
+        let libraries: Vec<LibraryScan> = self.library_folders.iter()
+            .map(|library| LibraryScan {
+                path: library.clone(),
+                source: self.library_source(library),
+                has_game: self.check_library_for_game(library, app_id).is_some(),
+                prefix: Self::check_compatdata(library, app_id),
+            })
+            .collect();
+        let prefixes = libraries.iter().filter_map(|library| library.prefix.clone()).collect();
+
+        ScanResult {
+            home,
+            steam_roots,
+            steam_root: self.steam_root.clone(),
+            game_path: self.get_game_info(app_id).map(|info| info.game_path),
+            libraries,
+            prefixes,
+            warnings,
+        }
+    }
+
+    /// The Proton version from the `version` file Proton writes next to `pfx` when it sets up
+    /// or upgrades the prefix, falling back to the first line of `config_info`
+    pub fn detect_proton_version(prefix: &Path) -> Option<String> {
+        let compat_data = prefix.parent()?;
+        ["version", "config_info"].iter().find_map(|file| {
+            let content = fs::read_to_string(compat_data.join(file)).ok()?;
+            // Proton's own version file is `<timestamp> proton-<version>`, keep the last word
+            let version = content.lines().next()?.split_whitespace().last()?;
+            Some(version.strip_prefix("proton-").unwrap_or(version).to_string())
+        })
+    }
+
+    /// Whether `version` is a Proton older than `MIN_PROTON_MAJOR`, going by its first number
+    pub fn is_outdated_proton(version: &str) -> bool {
+        let major: String = version
+            .chars()
+            .skip_while(|c| !c.is_ascii_digit())
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        major.parse::<u32>().is_ok_and(|major| major < MIN_PROTON_MAJOR)
+    }
+
+    /// Read the prefix architecture from the `#arch=` header Wine writes into system.reg,
+    /// falling back to the presence of `syswow64` (only created in 64-bit prefixes)
+    pub fn detect_prefix_arch(prefix: &Path) -> Option<PrefixArch> {
+        let mut system_reg = Vec::new();
+        if File::open(prefix.join("system.reg")).and_then(|file| file.take(512).read_to_end(&mut system_reg)).is_ok() {
+            let header = String::from_utf8_lossy(&system_reg);
+            if header.contains("#arch=win64") {
+                return Some(PrefixArch::Win64);
+            }
+            if header.contains("#arch=win32") {
+                return Some(PrefixArch::Win32);
+            }
+        }
+
+        let windows_dir = prefix.join("drive_c/windows");
+        if windows_dir.join("syswow64").is_dir() {
+            Some(PrefixArch::Win64)
+        } else if windows_dir.join("system32").is_dir() {
+            Some(PrefixArch::Win32)
+        } else {
+            None
+        }
+    }
+
+    fn find_steam_root(home: &Path) -> Option<PathBuf> {
+        Self::find_steam_roots(home).into_iter().next()
+    }
+
+    /// Candidate roots in order of preference; `.steam/steam` and `.steam/root` are usually
+    /// symlinks to the same install, so they are deduplicated by their resolved path
+    fn find_steam_roots(home: &Path) -> Vec<PathBuf> {
+        #[cfg(target_os = "macos")]
+        let candidates = [home.join("Library/Application Support/Steam")];
+        #[cfg(not(target_os = "macos"))]
         let candidates = [
             home.join(".steam/steam"),
             home.join(".steam/root"),
             home.join(".local/share/Steam"),
-            home.join(".var/app/com.valvesoftware.Steam"),
-            home.join(".var/app/com.valvesoftware.Steam/data/Steam"),
+            home.join(FLATPAK_DATA_DIR).join(".local/share/Steam"),
+            home.join(FLATPAK_DATA_DIR).join("data/Steam"),
+            home.join(SNAP_DATA_DIR).join("common/.local/share/Steam"),
+            home.join(SNAP_DATA_DIR).join("current/.local/share/Steam"),
             PathBuf::from("/usr/share/steam"),
         ];
 
+        let mut seen = HashSet::new();
         candidates.into_iter()
-            .find(|path| path.exists() && path.join("steamapps").exists())
+            .filter(|path| {
+                let found = path.exists() && path.join("steamapps").exists();
+                debug!("Steam root {:?}: {}", path, if found { "found" } else { "not found" });
+                found
+            })
+            .filter(|path| {
+                let new = seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone()));
+                if !new {
+                    debug!("{:?} is the same Steam install as an earlier one", path);
+                }
+                new
+            })
+            .collect()
     }
 
     fn discover_library_folders(steam_root: &Option<PathBuf>) -> Vec<PathBuf> {
@@ -77,65 +575,168 @@ impl SteamGameFinder {
         Self::deduplicate_paths(folders)
     }
 
-    fn parse_library_folders_vdf(steam_root: &PathBuf) -> Vec<PathBuf> {
+    fn parse_library_folders_vdf(steam_root: &Path) -> Vec<PathBuf> {
+        Self::library_paths_from_vdf(steam_root)
+            .into_iter()
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    /// `steamapps` directories of every library listed, whether or not they exist
+    fn library_paths_from_vdf(steam_root: &Path) -> Vec<PathBuf> {
         let library_file = steam_root.join("steamapps/libraryfolders.vdf");
         if !library_file.exists() {
             return Vec::new();
         }
 
-        let data = VdfParser::parse_file(&library_file);
-        
-        data.iter()
-            .filter(|(key, _)| key.contains(".path"))
-            .filter_map(|(_, value)| {
-                let path = PathBuf::from(value).join("steamapps");
-                path.exists().then_some(path)
-            })
+        debug!("Reading library folders from {:?}", library_file);
+        Self::library_paths(&VdfParser::parse_file(&library_file))
+            .into_iter()
+            .inspect(|path| debug!("Library listed: {:?}", path))
             .collect()
     }
 
+    /// `steamapps` directories in a parsed libraryfolders.vdf. Current Steam nests each library
+    /// as `"0" { "path" "..." }`; older versions had plain `"1" "/path"` values next to keys like
+    /// `ContentStatsID`
+    pub fn library_paths(data: &HashMap<String, String>) -> Vec<PathBuf> {
+        let mut libraries: Vec<(&str, PathBuf)> = data
+            .iter()
+            .filter(|(key, _)| {
+                let parts: Vec<&str> = key.split('.').collect();
+                match parts.as_slice() {
+                    [_, index, path] => path.eq_ignore_ascii_case("path") && index.parse::<u32>().is_ok(),
+                    [_, index] => index.parse::<u32>().is_ok(),
+                    _ => false,
+                }
+            })
+            .map(|(key, value)| (key.as_str(), PathBuf::from(value).join("steamapps")))
+            .collect();
+        // HashMap order isn't stable, the file's is
+        libraries.sort_by_key(|(key, _)| key.split('.').nth(1).and_then(|index| index.parse::<u32>().ok()));
+        libraries.into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// `steamapps` directories up to `MOUNT_SCAN_DEPTH` levels below each of `roots`, deep
+    /// enough for `/run/media/<user>/<drive>/SteamLibrary/steamapps`. Symlinks aren't followed
+    pub fn scan_mounts(roots: &[PathBuf]) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        let mut level: Vec<PathBuf> = roots.to_vec();
+        for _ in 0..=MOUNT_SCAN_DEPTH {
+            let mut next = Vec::new();
+            for dir in &level {
+                let Ok(entries) = fs::read_dir(dir) else {
+                    continue;
+                };
+                for entry in entries.filter_map(Result::ok) {
+                    if !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                        continue;
+                    }
+                    if entry.file_name() == "steamapps" {
+                        debug!("Found library {:?}", entry.path());
+                        found.push(entry.path());
+                    } else {
+                        next.push(entry.path());
+                    }
+                }
+            }
+            level = next;
+        }
+        found.sort();
+        found
+    }
+
+    /// By resolved path: a Flatpak libraryfolders.vdf can list the main library through a different
+    /// route than the one the root was found by
     fn deduplicate_paths(paths: Vec<PathBuf>) -> Vec<PathBuf> {
         let mut seen = HashSet::new();
         paths.into_iter()
-            .filter(|path| seen.insert(path.to_string_lossy().to_string()))
+            .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
             .collect()
     }
 
+    /// Libraries are checked in parallel, on slow disks each one takes a few stats. The first
+    /// match in library order wins, however the lookups finish
     fn find_game_by_appid(&self, app_id: &str) -> Option<(PathBuf, PathBuf)> {
-        for library_path in &self.library_folders {
-            if let Some(game_info) = self.check_library_for_game(library_path, app_id) {
-                return Some(game_info);
-            }
-        }
-        None
+        thread::scope(|scope| {
+            let lookups: Vec<_> = self.library_folders
+                .iter()
+                .map(|library_path| scope.spawn(move || self.check_library_for_game(library_path, app_id)))
+                .collect();
+            lookups.into_iter().find_map(|lookup| lookup.join().ok().flatten())
+        })
     }
 
-    fn check_library_for_game(&self, library_path: &PathBuf, app_id: &str) -> Option<(PathBuf, PathBuf)> {
+    fn check_library_for_game(&self, library_path: &Path, app_id: &str) -> Option<(PathBuf, PathBuf)> {
         let acf_file = library_path.join(format!("appmanifest_{}.acf", app_id));
-        
+        debug!("Scanning library {:?} for app {}", library_path, app_id);
+
         if !acf_file.exists() {
+            debug!("No {:?}", acf_file);
             return None;
         }
 
         let acf_data = VdfParser::parse_file(&acf_file);
-        let install_dir = acf_data.get("AppState.installdir")?;
+        let install_dir = VdfParser::get_ignore_case(&acf_data, "AppState.installdir")?;
         let game_path = library_path.join("common").join(install_dir);
         
-        game_path.exists().then_some((game_path, library_path.clone()))
+        game_path.exists().then_some((game_path, library_path.to_path_buf()))
     }
 
-    fn find_proton_prefix(&self, app_id: &str, preferred_library: Option<&PathBuf>) -> Option<PathBuf> {
+    fn find_proton_prefix(&self, app_id: &str, preferred_library: Option<&PathBuf>) -> Option<(PathBuf, String)> {
+        // Steam sets this for processes it launches, e.g. when we run as a launch option wrapper
+        if let Some(compat_data) = env::var_os(COMPAT_DATA_ENV).map(PathBuf::from)
+            && compat_data.file_name().is_some_and(|name| name == app_id)
+            && compat_data.join("pfx").exists()
+        {
+            return Some((compat_data.join("pfx"), format!("{} env", COMPAT_DATA_ENV)));
+        }
+
+        if let Some(found) = self.prefix_roots().into_iter().find_map(|(root, via)| {
+            let prefix = root.join(app_id).join("pfx");
+            prefix.exists().then_some((prefix, via))
+        }) {
+            return Some(found);
+        }
+
         // Check preferred library first
         if let Some(prefix) = preferred_library.and_then(|lib| Self::check_compatdata(lib, app_id)) {
-            return Some(prefix);
+            return Some((prefix, "compatdata in the game's library".into()));
         }
 
         // Fall back to searching all libraries
         self.library_folders.iter()
-            .find_map(|lib| Self::check_compatdata(lib, app_id))
+            .find_map(|lib| {
+                let prefix = Self::check_compatdata(lib, app_id)?;
+                Some((prefix, format!("compatdata in {}", self.describe_library(lib))))
+            })
     }
 
-    fn check_compatdata(library_path: &PathBuf, app_id: &str) -> Option<PathBuf> {
+    /// Directories holding `<app id>/pfx` prefixes outside the libraries: `--prefix-root`, then
+    /// a relocated compat data directory from the environment
+    fn prefix_roots(&self) -> Vec<(PathBuf, String)> {
+        let mut roots = Vec::new();
+        if let Some(root) = &self.options.prefix_root {
+            roots.push((root.clone(), "--prefix-root".to_string()));
+        }
+        if let Some(root) = env::var_os(COMPAT_DATA_ENV).filter(|value| !value.is_empty()) {
+            roots.push((PathBuf::from(root), format!("{} env", COMPAT_DATA_ENV)));
+        }
+        roots
+    }
+
+    fn describe_library(&self, library: &Path) -> String {
+        match self.library_source(library) {
+            LibrarySource::Main => "the main library".into(),
+            source => format!("{:?} (from {})", library, source),
+        }
+    }
+
+    fn library_source(&self, library: &Path) -> LibrarySource {
+        self.library_sources.get(library).copied().unwrap_or(LibrarySource::LibraryFoldersVdf)
+    }
+
+    fn check_compatdata(library_path: &Path, app_id: &str) -> Option<PathBuf> {
         let compatdata_path = library_path
             .join("compatdata")
             .join(app_id)
@@ -150,133 +751,3 @@ impl Default for SteamGameFinder {
         Self::new()
     }
 }
-
-/// VDF (Valve Data Format) parser
-struct VdfParser;
-
-impl VdfParser {
-    fn parse_file(path: &PathBuf) -> HashMap<String, String> {
-        if !path.exists() {
-            return HashMap::new();
-        }
-
-        let content = match fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(_) => return HashMap::new(),
-        };
-
-        let mut result = HashMap::new();
-        let mut pos = 0;
-        Self::parse_recursive(&content, &mut pos, &mut result, String::new());
-        result
-    }
-
-    fn parse_recursive(
-        content: &str,
-        pos: &mut usize,
-        result: &mut HashMap<String, String>,
-        prefix: String,
-    ) {
-        let chars: Vec<char> = content.chars().collect();
-
-        while *pos < chars.len() {
-            Self::skip_whitespace(&chars, pos);
-            
-            if *pos >= chars.len() {
-                break;
-            }
-
-            if Self::skip_comment(&chars, pos) {
-                continue;
-            }
-
-            if Self::handle_closing_brace(&chars, pos) {
-                return;
-            }
-
-            if Self::handle_opening_brace(&chars, pos) {
-                continue;
-            }
-
-            if chars[*pos] == '"' {
-                Self::parse_key_value(&chars, pos, result, &prefix, content);
-            } else {
-                *pos += 1;
-            }
-        }
-    }
-
-    fn skip_whitespace(chars: &[char], pos: &mut usize) {
-        while *pos < chars.len() && chars[*pos].is_whitespace() {
-            *pos += 1;
-        }
-    }
-
-    fn skip_comment(chars: &[char], pos: &mut usize) -> bool {
-        if *pos + 1 < chars.len() && chars[*pos] == '/' && chars[*pos + 1] == '/' {
-            while *pos < chars.len() && chars[*pos] != '\n' {
-                *pos += 1;
-            }
-            return true;
-        }
-        false
-    }
-
-    fn handle_closing_brace(chars: &[char], pos: &mut usize) -> bool {
-        if chars[*pos] == '}' {
-            *pos += 1;
-            return true;
-        }
-        false
-    }
-
-    fn handle_opening_brace(chars: &[char], pos: &mut usize) -> bool {
-        if chars[*pos] == '{' {
-            *pos += 1;
-            return true;
-        }
-        false
-    }
-
-    fn parse_key_value(
-        chars: &[char],
-        pos: &mut usize,
-        result: &mut HashMap<String, String>,
-        prefix: &str,
-        content: &str,
-    ) {
-        *pos += 1; // Skip opening quote
-        
-        let key = Self::read_quoted_string(chars, pos);
-        Self::skip_whitespace(chars, pos);
-
-        if *pos < chars.len() && chars[*pos] == '"' {
-            *pos += 1;
-            let value = Self::read_quoted_string(chars, pos);
-            let full_key = Self::build_key(prefix, &key);
-            result.insert(full_key, value);
-        } else if *pos < chars.len() && chars[*pos] == '{' {
-            *pos += 1;
-            let new_prefix = Self::build_key(prefix, &key);
-            Self::parse_recursive(content, pos, result, new_prefix);
-        }
-    }
-
-    fn read_quoted_string(chars: &[char], pos: &mut usize) -> String {
-        let mut s = String::new();
-        while *pos < chars.len() && chars[*pos] != '"' {
-            s.push(chars[*pos]);
-            *pos += 1;
-        }
-        *pos += 1; // Skip closing quote
-        s
-    }
-
-    fn build_key(prefix: &str, key: &str) -> String {
-        if prefix.is_empty() {
-            key.to_string()
-        } else {
-            format!("{}.{}", prefix, key)
-        }
-    }
-}
\ No newline at end of file
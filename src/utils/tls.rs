@@ -0,0 +1,56 @@
+use crate::errors::InstallerError;
+use rustls::{ClientConfig, RootCertStore};
+use std::sync::Arc;
+
+/// Root CAs the Geode API and GitHub (releases API and download hosts) chain up to.
+/// With pinning on, only these are trusted, so a certificate issued by any other CA
+/// (e.g. an intercepting proxy's) is rejected. Update when a host changes CA.
+pub const PINNED_ROOTS: &[&str] = &[
+    // Let's Encrypt
+    "ISRG Root X1",
+    "ISRG Root X2",
+    // Google Trust Services (Cloudflare)
+    "GTS Root R1",
+    "GTS Root R4",
+    // Sectigo (github.com, api.github.com)
+    "USERTrust ECC Certification Authority",
+    "USERTrust RSA Certification Authority",
+    // DigiCert (release asset downloads)
+    "DigiCert Global Root CA",
+    "DigiCert Global Root G2",
+];
+
+/// TLS settings that only trust the pinned root CAs
+pub struct CertificatePins;
+
+impl CertificatePins {
+    /// The bundled root certificates whose subject names one of the pinned CAs
+    pub fn root_store() -> RootCertStore {
+        let roots = webpki_roots::TLS_SERVER_ROOTS
+            .iter()
+            .filter(|anchor| PINNED_ROOTS.iter().any(|name| contains(anchor.subject.as_ref(), name.as_bytes())))
+            .cloned();
+
+        RootCertStore { roots: roots.collect() }
+    }
+
+    pub fn client_config() -> Result<ClientConfig, InstallerError> {
+        let roots = Self::root_store();
+        if roots.is_empty() {
+            return Err(InstallerError::Init("None of the pinned root certificates are available".into()));
+        }
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| InstallerError::Init(format!("TLS setup failed: {}", e)))?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Ok(config)
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
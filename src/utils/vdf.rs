@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// VDF (Valve Data Format) parser
+pub struct VdfParser;
+
+impl VdfParser {
+    pub fn parse_file(path: &Path) -> HashMap<String, String> {
+        if !path.exists() {
+            return HashMap::new();
+        }
+
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    /// Flatten into `parent.child` keys, with `\"`, `\\`, `\n` and `\t` escapes decoded
+    pub fn parse(content: &str) -> HashMap<String, String> {
+        let mut result = HashMap::new();
+        let mut pos = 0;
+        Self::parse_recursive(content, &mut pos, &mut result, String::new());
+        result
+    }
+
+    /// Look up a flattened key ignoring case, Steam writes both `installdir` and `installDir`
+    pub fn get_ignore_case<'a>(data: &'a HashMap<String, String>, key: &str) -> Option<&'a str> {
+        data.get(key)
+            .or_else(|| data.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v))
+            .map(String::as_str)
+    }
+
+    fn parse_recursive(
+        content: &str,
+        pos: &mut usize,
+        result: &mut HashMap<String, String>,
+        prefix: String,
+    ) {
+        let chars: Vec<char> = content.chars().collect();
+
+        while *pos < chars.len() {
+            Self::skip_whitespace(&chars, pos);
+            
+            if *pos >= chars.len() {
+                break;
+            }
+
+            if Self::skip_comment(&chars, pos) {
+                continue;
+            }
+
+            if Self::handle_closing_brace(&chars, pos) {
+                return;
+            }
+
+            if Self::handle_opening_brace(&chars, pos) {
+                continue;
+            }
+
+            if chars[*pos] == '"' {
+                Self::parse_key_value(&chars, pos, result, &prefix, content);
+            } else {
+                *pos += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace(chars: &[char], pos: &mut usize) {
+        while *pos < chars.len() && chars[*pos].is_whitespace() {
+            *pos += 1;
+        }
+    }
+
+    fn skip_comment(chars: &[char], pos: &mut usize) -> bool {
+        if *pos + 1 < chars.len() && chars[*pos] == '/' && chars[*pos + 1] == '/' {
+            while *pos < chars.len() && chars[*pos] != '\n' {
+                *pos += 1;
+            }
+            return true;
+        }
+        false
+    }
+
+    fn handle_closing_brace(chars: &[char], pos: &mut usize) -> bool {
+        if chars[*pos] == '}' {
+            *pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn handle_opening_brace(chars: &[char], pos: &mut usize) -> bool {
+        if chars[*pos] == '{' {
+            *pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn parse_key_value(
+        chars: &[char],
+        pos: &mut usize,
+        result: &mut HashMap<String, String>,
+        prefix: &str,
+        content: &str,
+    ) {
+        *pos += 1; // Skip opening quote
+        
+        let key = read_escaped_string(chars, pos);
+        Self::skip_whitespace(chars, pos);
+
+        if *pos < chars.len() && chars[*pos] == '"' {
+            *pos += 1;
+            let value = read_escaped_string(chars, pos);
+            let full_key = Self::build_key(prefix, &key);
+            result.insert(full_key, value);
+        } else if *pos < chars.len() && chars[*pos] == '{' {
+            *pos += 1;
+            let new_prefix = Self::build_key(prefix, &key);
+            Self::parse_recursive(content, pos, result, new_prefix);
+        }
+    }
+
+    fn build_key(prefix: &str, key: &str) -> String {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    }
+}
+
+/// A VDF value: either a string or a nested block of key/value pairs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfValue {
+    String(String),
+    Block(Vec<(String, VdfValue)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VdfToken {
+    Str(String),
+    Open,
+    Close,
+}
+
+/// Order-preserving VDF tree, used where Steam's files have to be edited and written back.
+/// Key lookups are case-insensitive since Steam isn't consistent about casing (`Apps` vs `apps`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VdfDocument {
+    entries: Vec<(String, VdfValue)>,
+}
+
+impl VdfDocument {
+    pub fn parse(content: &str) -> Self {
+        let tokens = Self::tokenize(content);
+        let mut pos = 0;
+        Self {
+            entries: Self::parse_block(&tokens, &mut pos),
+        }
+    }
+
+    pub fn get(&self, path: &[&str]) -> Option<&VdfValue> {
+        let (last, parents) = path.split_last()?;
+        let mut entries = &self.entries;
+        for key in parents {
+            match find_entry(entries, key)? {
+                VdfValue::Block(children) => entries = children,
+                VdfValue::String(_) => return None,
+            }
+        }
+        find_entry(entries, last)
+    }
+
+    pub fn get_string(&self, path: &[&str]) -> Option<&str> {
+        match self.get(path)? {
+            VdfValue::String(value) => Some(value),
+            VdfValue::Block(_) => None,
+        }
+    }
+
+    /// Set a string value, creating any missing blocks along the way
+    pub fn set_string(&mut self, path: &[&str], value: &str) {
+        let Some((last, parents)) = path.split_last() else {
+            return;
+        };
+
+        let mut entries = &mut self.entries;
+        for key in parents {
+            let index = match entries.iter().position(|(k, v)| k.eq_ignore_ascii_case(key) && matches!(v, VdfValue::Block(_))) {
+                Some(index) => index,
+                None => {
+                    entries.push((key.to_string(), VdfValue::Block(Vec::new())));
+                    entries.len() - 1
+                }
+            };
+            entries = match &mut entries[index].1 {
+                VdfValue::Block(children) => children,
+                VdfValue::String(_) => unreachable!("matched a block above"),
+            };
+        }
+
+        let value = VdfValue::String(value.to_string());
+        match entries.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(last)) {
+            Some(entry) => entry.1 = value,
+            None => entries.push((last.to_string(), value)),
+        }
+    }
+
+    /// Serialize in the same tab-indented layout Steam writes
+    pub fn serialize(&self) -> String {
+        let mut out = String::new();
+        Self::write_entries(&self.entries, 0, &mut out);
+        out
+    }
+
+    fn write_entries(entries: &[(String, VdfValue)], depth: usize, out: &mut String) {
+        let indent = "\t".repeat(depth);
+        for (key, value) in entries {
+            match value {
+                VdfValue::String(s) => {
+                    out.push_str(&format!("{}\"{}\"\t\t\"{}\"\n", indent, escape(key), escape(s)));
+                }
+                VdfValue::Block(children) => {
+                    out.push_str(&format!("{}\"{}\"\n{}{{\n", indent, escape(key), indent));
+                    Self::write_entries(children, depth + 1, out);
+                    out.push_str(&format!("{}}}\n", indent));
+                }
+            }
+        }
+    }
+
+    fn parse_block(tokens: &[VdfToken], pos: &mut usize) -> Vec<(String, VdfValue)> {
+        let mut entries = Vec::new();
+
+        while *pos < tokens.len() {
+            let token = &tokens[*pos];
+            *pos += 1;
+
+            let key = match token {
+                VdfToken::Close => return entries,
+                VdfToken::Open => continue, // Block without a key, nothing to attach it to
+                VdfToken::Str(key) => key.clone(),
+            };
+
+            match tokens.get(*pos) {
+                Some(VdfToken::Str(value)) => {
+                    *pos += 1;
+                    entries.push((key, VdfValue::String(value.clone())));
+                }
+                Some(VdfToken::Open) => {
+                    *pos += 1;
+                    entries.push((key, VdfValue::Block(Self::parse_block(tokens, pos))));
+                }
+                Some(VdfToken::Close) | None => {}
+            }
+        }
+
+        entries
+    }
+
+    fn tokenize(content: &str) -> Vec<VdfToken> {
+        let chars: Vec<char> = content.chars().collect();
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+
+        while pos < chars.len() {
+            let c = chars[pos];
+            if c.is_whitespace() {
+                pos += 1;
+            } else if c == '/' && chars.get(pos + 1) == Some(&'/') {
+                while pos < chars.len() && chars[pos] != '\n' {
+                    pos += 1;
+                }
+            } else if c == '{' {
+                tokens.push(VdfToken::Open);
+                pos += 1;
+            } else if c == '}' {
+                tokens.push(VdfToken::Close);
+                pos += 1;
+            } else if c == '"' {
+                pos += 1;
+                tokens.push(VdfToken::Str(read_escaped_string(&chars, &mut pos)));
+            } else {
+                let start = pos;
+                while pos < chars.len() && !chars[pos].is_whitespace() && !matches!(chars[pos], '{' | '}' | '"') {
+                    pos += 1;
+                }
+                let word: String = chars[start..pos].iter().collect();
+                // Platform conditionals like [$WIN32] don't affect us
+                if !(word.starts_with('[') && word.ends_with(']')) {
+                    tokens.push(VdfToken::Str(word));
+                }
+            }
+        }
+
+        tokens
+    }
+}
+
+fn find_entry<'a>(entries: &'a [(String, VdfValue)], key: &str) -> Option<&'a VdfValue> {
+    entries.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+fn read_escaped_string(chars: &[char], pos: &mut usize) -> String {
+    let mut s = String::new();
+    while *pos < chars.len() && chars[*pos] != '"' {
+        if chars[*pos] == '\\' && *pos + 1 < chars.len() {
+            *pos += 1;
+            s.push(match chars[*pos] {
+                'n' => '\n',
+                't' => '\t',
+                other => other,
+            });
+        } else {
+            s.push(chars[*pos]);
+        }
+        *pos += 1;
+    }
+    *pos += 1; // Skip closing quote
+    s
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
@@ -0,0 +1,66 @@
+use crate::errors::InstallerError;
+use crate::utils::fs_utils::find_executable;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Header of an empty `user.reg`, what Wine itself writes before any key exists
+pub const MINIMAL_USER_REG: &str = "WINE REGISTRY Version 2\n;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\n#arch=win64\n";
+
+/// A Wine installation found on `PATH`
+pub struct Wine {
+    binary: PathBuf,
+}
+
+impl Wine {
+    pub fn find() -> Option<Self> {
+        let path = env::var_os("PATH")?;
+        find_executable("wine", &path).map(|binary| Self { binary })
+    }
+
+    pub fn binary(&self) -> &Path {
+        &self.binary
+    }
+
+    /// Run `wineboot --init` in `prefix` and wait for wineserver to flush the registry to disk
+    pub fn boot(&self, prefix: &Path) -> Result<(), InstallerError> {
+        let status = Command::new(&self.binary)
+            .args(["wineboot", "--init"])
+            .env("WINEPREFIX", prefix)
+            .env("WINEDEBUG", "-all")
+            .status()?;
+        if !status.success() {
+            return Err(InstallerError::Installation(format!("wineboot failed in {:?} ({})", prefix, status)));
+        }
+
+        self.wait_for_server(prefix)
+    }
+
+    /// Import a `.reg` file into `prefix` with `regedit /S`
+    pub fn regedit(&self, prefix: &Path, reg_file: &Path) -> Result<(), InstallerError> {
+        let status = Command::new(&self.binary)
+            .args(["regedit", "/S"])
+            .arg(Self::windows_path(reg_file))
+            .env("WINEPREFIX", prefix)
+            .env("WINEDEBUG", "-all")
+            .status()?;
+        if !status.success() {
+            return Err(InstallerError::Registry(format!("wine regedit failed to import {:?} ({})", reg_file, status)));
+        }
+        self.wait_for_server(prefix)
+    }
+
+    /// The registry files are written when wineserver shuts down
+    fn wait_for_server(&self, prefix: &Path) -> Result<(), InstallerError> {
+        let wineserver = self.binary.with_file_name("wineserver");
+        if wineserver.is_file() {
+            Command::new(wineserver).arg("-w").env("WINEPREFIX", prefix).status()?;
+        }
+        Ok(())
+    }
+
+    /// Every prefix maps `Z:` to the Unix root
+    fn windows_path(path: &Path) -> String {
+        format!("Z:{}", path.to_string_lossy().replace('/', "\\"))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::utils::bottles::Bottles;
+use crate::utils::heroic::Heroic;
+use crate::utils::lutris::Lutris;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Game executable the launchers are searched for
+pub const GD_EXECUTABLE: &str = "GeometryDash.exe";
+
+/// Where a Wine copy of the game was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Launcher {
+    Lutris,
+    Bottles,
+    Heroic,
+}
+
+impl fmt::Display for Launcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Launcher::Lutris => write!(f, "Lutris"),
+            Launcher::Bottles => write!(f, "Bottles"),
+            Launcher::Heroic => write!(f, "Heroic"),
+        }
+    }
+}
+
+/// A copy of Geometry Dash a launcher runs in its own Wine prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WineGame {
+    pub launcher: Launcher,
+    /// What the launcher calls it
+    pub name: String,
+    pub prefix: PathBuf,
+    pub game_dir: PathBuf,
+}
+
+/// Every copy of the game a supported launcher knows about, for the Wine install flow
+pub fn find_wine_games(home: &Path) -> Vec<WineGame> {
+    let mut games = Lutris::find_games(home);
+    games.extend(Bottles::find_games(home));
+    games.extend(Heroic::find_games(home));
+    games
+}
+
+/// The prefix a Windows path inside one lives in: the directory holding its `drive_c`
+pub fn prefix_of(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| ancestor.file_name().is_some_and(|name| name == "drive_c"))
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+}
@@ -0,0 +1,430 @@
+use crate::errors::InstallerError;
+use crate::utils::fs_utils::atomic_write;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DLL_OVERRIDES_SECTION: &str = "[Software\\\\Wine\\\\DllOverrides]";
+const SNAPSHOT_FILE: &str = "geode/.dll_overrides_snapshot.json";
+const UTF16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const BACKUP_INFIX: &str = ".geode.bak.";
+/// How many backups of each registry file are kept
+pub const REGISTRY_BACKUPS_KEPT: usize = 5;
+
+/// Which of a prefix's registry files get the DLL override. `user.reg` is what Wine reads
+/// first, but some Proton setups only honor overrides in `system.reg`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RegistryScope {
+    #[default]
+    User,
+    System,
+    Both,
+}
+
+impl RegistryScope {
+    pub fn files(&self) -> &'static [&'static str] {
+        match self {
+            RegistryScope::User => &["user.reg"],
+            RegistryScope::System => &["system.reg"],
+            RegistryScope::Both => &["user.reg", "system.reg"],
+        }
+    }
+}
+
+/// How DLL overrides get into the registry: by editing the `.reg` files directly, or by
+/// importing them with `wine regedit` (needs Wine on PATH)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RegistryMethod {
+    #[default]
+    File,
+    Regedit,
+}
+
+/// How a registry file was encoded on disk. Wine writes UTF-8 (really ASCII with `\x` escapes),
+/// but files touched by other tools can be UTF-16 or contain stray non-UTF-8 bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryEncoding {
+    Utf8,
+    Utf16Le { bom: bool },
+    /// Not valid UTF-8; decoded byte-for-byte so unknown bytes survive the round trip
+    Latin1,
+}
+
+impl std::fmt::Display for RegistryEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryEncoding::Utf8 => write!(f, "UTF-8"),
+            RegistryEncoding::Utf16Le { .. } => write!(f, "UTF-16"),
+            RegistryEncoding::Latin1 => write!(f, "non-UTF-8 (Latin-1)"),
+        }
+    }
+}
+
+/// A Wine `.reg` file decoded to text, remembering its encoding for the write back
+#[derive(Debug, Clone)]
+pub struct RegistryFile {
+    pub content: String,
+    pub encoding: RegistryEncoding,
+}
+
+impl RegistryFile {
+    pub fn read(path: &Path) -> Result<Self, InstallerError> {
+        Ok(Self::decode(&fs::read(path)?))
+    }
+
+    /// Replace the file atomically, encoded the way it was read
+    pub fn write(&self, path: &Path) -> Result<(), InstallerError> {
+        atomic_write(path, &self.encode())?;
+        Ok(())
+    }
+
+    pub fn decode(bytes: &[u8]) -> Self {
+        if let Some(body) = bytes.strip_prefix(&UTF16_LE_BOM) {
+            return Self::from_utf16(body, true);
+        }
+        // Without a BOM, "WINE REGISTRY" in UTF-16 shows up as every other byte being zero
+        if bytes.len() >= 4 && bytes[1] == 0 && bytes[3] == 0 && bytes[0] != 0 {
+            return Self::from_utf16(bytes, false);
+        }
+
+        match std::str::from_utf8(bytes) {
+            Ok(content) => Self {
+                content: content.to_string(),
+                encoding: RegistryEncoding::Utf8,
+            },
+            Err(_) => Self {
+                content: bytes.iter().map(|&b| b as char).collect(),
+                encoding: RegistryEncoding::Latin1,
+            },
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        match self.encoding {
+            RegistryEncoding::Utf8 => self.content.as_bytes().to_vec(),
+            RegistryEncoding::Utf16Le { bom } => {
+                let mut bytes = if bom { UTF16_LE_BOM.to_vec() } else { Vec::new() };
+                bytes.extend(self.content.encode_utf16().flat_map(u16::to_le_bytes));
+                bytes
+            }
+            // Only ASCII is ever added, so every char still fits in a byte
+            RegistryEncoding::Latin1 => self.content.chars().map(|c| u8::try_from(c).unwrap_or(b'?')).collect(),
+        }
+    }
+
+    fn from_utf16(bytes: &[u8], bom: bool) -> Self {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        Self {
+            content: String::from_utf16_lossy(&units),
+            encoding: RegistryEncoding::Utf16Le { bom },
+        }
+    }
+}
+
+/// Line endings of a registry file. Wine writes LF, but files edited on Windows or by
+/// some Proton versions use CRLF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn detect(content: &str) -> Self {
+        if content.contains("\r\n") {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// Run `edit` on an LF copy of `content`, then convert the result back to the original endings
+    fn edit_as_lf<T>(content: &mut String, edit: impl FnOnce(&mut String) -> T) -> T {
+        if Self::detect(content) == LineEnding::Lf {
+            return edit(content);
+        }
+        let mut lf = content.replace("\r\n", "\n");
+        let result = edit(&mut lf);
+        *content = lf.replace('\n', "\r\n");
+        result
+    }
+}
+
+/// Edits to the `DllOverrides` section of a decoded `user.reg` or `system.reg`. The file's line endings are kept.
+pub struct DllOverrides;
+
+impl DllOverrides {
+    /// Add `"<dll>"="<value>"`, or rewrite an existing override for the DLL that has another value.
+    /// True if the section changed.
+    pub fn ensure(content: &mut String, dll: &str, value: &str) -> bool {
+        LineEnding::edit_as_lf(content, |content| Self::ensure_lf(content, dll, value))
+    }
+
+    /// `ensure` every `(dll, value)` pair, returns the DLLs whose override was added or changed
+    pub fn ensure_all<'a>(content: &mut String, overrides: &'a [(String, String)]) -> Vec<&'a str> {
+        overrides
+            .iter()
+            .filter(|(dll, value)| Self::ensure(content, dll, value))
+            .map(|(dll, _)| dll.as_str())
+            .collect()
+    }
+
+    /// A `.reg` script for `regedit` setting `overrides` in the hive `registry_file` holds
+    /// (`user.reg` is HKEY_CURRENT_USER, `system.reg` HKEY_LOCAL_MACHINE)
+    pub fn regedit_script(registry_file: &Path, overrides: &[(&str, &str)]) -> String {
+        let root = match registry_file.file_name().and_then(|name| name.to_str()) {
+            Some("system.reg") => "HKEY_LOCAL_MACHINE",
+            _ => "HKEY_CURRENT_USER",
+        };
+        let mut script = format!("Windows Registry Editor Version 5.00\r\n\r\n[{}\\Software\\Wine\\DllOverrides]\r\n", root);
+        for (dll, value) in overrides {
+            script.push_str(&format!("\"{}\"=\"{}\"\r\n", dll, value));
+        }
+        script
+    }
+
+    /// Drop the override line for `dll`, true if there was one
+    pub fn remove(content: &mut String, dll: &str) -> bool {
+        LineEnding::edit_as_lf(content, |content| Self::remove_lf(content, dll))
+    }
+
+    /// Put back a section returned by `section`, or drop the section when there was none
+    pub fn restore_section(content: &mut String, section: Option<&str>) {
+        let section = section.map(|section| section.replace("\r\n", "\n"));
+        LineEnding::edit_as_lf(content, |content| Self::restore_section_lf(content, section.as_deref()))
+    }
+
+    /// The value of the override for `dll`, matched case-insensitively like Wine does
+    pub fn get<'a>(content: &'a str, dll: &str) -> Option<&'a str> {
+        let section = Self::section(content)?;
+        let key = format!("\"{}\"=", dll).to_ascii_lowercase();
+        section
+            .lines()
+            .find(|line| line.to_ascii_lowercase().starts_with(&key))
+            .map(|line| line[key.len()..].trim().trim_matches('"'))
+    }
+
+    /// The whole section, header to the blank line before the next section
+    pub fn section(content: &str) -> Option<&str> {
+        Self::section_span(content).map(|(start, end)| &content[start..end])
+    }
+
+    fn ensure_lf(content: &mut String, dll: &str, value: &str) -> bool {
+        let entry = format!("\"{}\"=\"{}\"", dll, value);
+
+        let Some((start, end)) = Self::section_span(content) else {
+            Self::add_section(content, &entry);
+            return true;
+        };
+
+        // Wine treats value names case-insensitively
+        let key = format!("\n\"{}\"=", dll).to_ascii_lowercase();
+        let Some(pos) = content[start..end].to_ascii_lowercase().find(&key) else {
+            Self::add_entry_to_section(content, DLL_OVERRIDES_SECTION, &entry);
+            return true;
+        };
+
+        let line_start = start + pos + 1;
+        let line_end = content[line_start..end].find('\n').map(|pos| line_start + pos).unwrap_or(end);
+        let current = content[line_start + key.len() - 1..line_end].trim().trim_matches('"');
+        if current == value {
+            return false;
+        }
+        // Left behind by another tool (e.g. "builtin"), which would keep Geode from loading
+        content.replace_range(line_start..line_end, &entry);
+        true
+    }
+
+    fn remove_lf(content: &mut String, dll: &str) -> bool {
+        let Some(section_pos) = content.find(DLL_OVERRIDES_SECTION) else {
+            return false;
+        };
+        let section_end = content[section_pos..]
+            .find("\n[")
+            .map(|pos| section_pos + pos)
+            .unwrap_or(content.len());
+
+        // Case-insensitively, like `ensure_lf` and `get`
+        let key = format!("\n\"{}\"=", dll).to_ascii_lowercase();
+        let Some(line_start) = content[section_pos..section_end]
+            .to_ascii_lowercase()
+            .find(&key)
+            .map(|pos| section_pos + pos + 1)
+        else {
+            return false;
+        };
+        let line_end = content[line_start..]
+            .find('\n')
+            .map(|pos| line_start + pos + 1)
+            .unwrap_or(content.len());
+
+        content.replace_range(line_start..line_end, "");
+        true
+    }
+
+    fn restore_section_lf(content: &mut String, section: Option<&str>) {
+        match (Self::section_span(content), section) {
+            (Some((start, end)), Some(section)) => content.replace_range(start..end, section),
+            (Some((start, end)), None) => {
+                content.replace_range(start..end, "");
+                if start == content.len() {
+                    content.truncate(content.trim_end().len());
+                    content.push('\n');
+                }
+            }
+            (None, Some(section)) => {
+                content.push_str("\n\n");
+                content.push_str(section);
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn section_span(content: &str) -> Option<(usize, usize)> {
+        let start = content.find(DLL_OVERRIDES_SECTION)?;
+        let end = content[start..]
+            .find("\n[")
+            .map(|pos| start + pos + 1)
+            .unwrap_or(content.len());
+        Some((start, end))
+    }
+
+    fn add_section(content: &mut String, entry: &str) {
+        let timestamp = current_timestamp();
+        let hex_time = current_hex_timestamp();
+        content.push_str(&format!(
+            "\n\n[Software\\\\Wine\\\\DllOverrides] {}\n#time={}\n{}\n",
+            timestamp, hex_time, entry
+        ));
+    }
+
+    fn add_entry_to_section(content: &mut String, section: &str, entry: &str) {
+        if let Some(section_pos) = content.find(section) {
+            let search_start = section_pos + section.len();
+            let insert_pos = content[search_start..]
+                .find("\n[")
+                .map(|pos| search_start + pos)
+                .unwrap_or(content.len());
+
+            let entry_with_newline = if insert_pos == content.len() {
+                format!("\n{}\n", entry)
+            } else {
+                format!("{}\n", entry)
+            };
+            content.insert_str(insert_pos, &entry_with_newline);
+        }
+    }
+}
+
+/// The DllOverrides section as it was before Geode first touched it, kept in the game
+/// directory so uninstalling can put back exactly that without undoing unrelated registry changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DllOverridesSnapshot {
+    /// `None` if the prefix had no DllOverrides section
+    pub section: Option<String>,
+}
+
+impl DllOverridesSnapshot {
+    pub fn capture(content: &str) -> Self {
+        Self {
+            section: DllOverrides::section(content).map(String::from),
+        }
+    }
+
+    pub fn path(game_dir: &Path) -> PathBuf {
+        game_dir.join(SNAPSHOT_FILE)
+    }
+
+    /// Save unless a snapshot already exists: only the state from before the first install is worth keeping
+    pub fn save_if_missing(&self, game_dir: &Path) -> Result<bool, InstallerError> {
+        let path = Self::path(game_dir);
+        if path.exists() {
+            return Ok(false);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, serde_json::to_string_pretty(&json!({ "section": self.section }))?)?;
+        Ok(true)
+    }
+
+    pub fn load(game_dir: &Path) -> Result<Option<Self>, InstallerError> {
+        let content = match fs::read_to_string(Self::path(game_dir)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let json: Value = serde_json::from_str(&content)?;
+        Ok(Some(Self {
+            section: json["section"].as_str().map(String::from),
+        }))
+    }
+
+    pub fn restore(&self, content: &mut String) {
+        DllOverrides::restore_section(content, self.section.as_deref());
+    }
+}
+
+/// Timestamped copies of a registry file, `user.reg.geode.bak.<millis>` next to the original
+pub struct RegistryBackups;
+
+impl RegistryBackups {
+    /// Copy `path` before it gets modified, then delete all but the newest `REGISTRY_BACKUPS_KEPT` copies
+    pub fn create(path: &Path) -> Result<PathBuf, InstallerError> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| InstallerError::Unknown(e.to_string()))?
+            .as_millis();
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!("{}{}", BACKUP_INFIX, millis));
+        let backup = path.with_file_name(name);
+        fs::copy(path, &backup)?;
+
+        let backups = Self::list(path)?;
+        for old in &backups[..backups.len().saturating_sub(REGISTRY_BACKUPS_KEPT)] {
+            fs::remove_file(old)?;
+        }
+        Ok(backup)
+    }
+
+    /// Backups of `path`, oldest first
+    pub fn list(path: &Path) -> Result<Vec<PathBuf>, InstallerError> {
+        let Some(dir) = path.parent() else {
+            return Ok(Vec::new());
+        };
+        let prefix = format!("{}{}", path.file_name().unwrap_or_default().to_string_lossy(), BACKUP_INFIX);
+
+        let mut backups: Vec<(u128, PathBuf)> = fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let timestamp = entry.file_name().to_str()?.strip_prefix(&prefix)?.parse().ok()?;
+                Some((timestamp, entry.path()))
+            })
+            .collect();
+        backups.sort();
+        Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+    }
+
+    /// Put the newest backup back in place of `path`, returns the backup used
+    pub fn restore_latest(path: &Path) -> Result<PathBuf, InstallerError> {
+        let latest = Self::list(path)?
+            .pop()
+            .ok_or_else(|| InstallerError::Registry(format!("No backups of {:?} found", path)))?;
+        atomic_write(path, &fs::read(&latest)?)?;
+        Ok(latest)
+    }
+}
+
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn current_hex_timestamp() -> String {
+    format!("{:x}", current_timestamp())
+}
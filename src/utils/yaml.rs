@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+/// Enough YAML for the launcher configs of Lutris and Bottles
+pub struct YamlParser;
+
+impl YamlParser {
+    /// `key: value` pairs nested by indentation, flattened into `parent.child` keys like
+    /// `VdfParser` does. Lists and multi-line values are skipped
+    pub fn parse(content: &str) -> HashMap<String, String> {
+        let mut result = HashMap::new();
+        let mut parents: Vec<(usize, String)> = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+                continue;
+            }
+            let Some((key, value)) = trimmed.split_once(':') else {
+                continue;
+            };
+
+            let indent = line.len() - trimmed.len();
+            while parents.last().is_some_and(|(parent_indent, _)| *parent_indent >= indent) {
+                parents.pop();
+            }
+            let path: Vec<&str> = parents.iter().map(|(_, name)| name.as_str()).chain([key.trim()]).collect();
+
+            let value = value.trim();
+            if value.is_empty() {
+                parents.push((indent, key.trim().to_string()));
+            } else {
+                result.insert(path.join("."), Self::unquote(value).to_string());
+            }
+        }
+        result
+    }
+
+    fn unquote(value: &str) -> &str {
+        ['"', '\'']
+            .iter()
+            .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+            .unwrap_or(value)
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::api_cache::{ApiCache, CachedResponse, DEFAULT_TTL};
+    use reqwest::header::{HeaderMap, HeaderValue, CACHE_CONTROL, ETAG};
+    use std::time::Duration;
+
+    const URL: &str = "https://api.geode-sdk.org/v1/loader/versions/latest";
+
+    fn cache_control(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_ttl_from_cache_control() {
+        assert_eq!(CachedResponse::ttl(&HeaderMap::new()), Some(DEFAULT_TTL));
+        assert_eq!(CachedResponse::ttl(&cache_control("public, max-age=300")), Some(Duration::from_secs(300)));
+        // Never longer than the default, so a new release shows up within the hour
+        assert_eq!(CachedResponse::ttl(&cache_control("max-age=86400")), Some(DEFAULT_TTL));
+        assert_eq!(CachedResponse::ttl(&cache_control("no-cache")), Some(Duration::ZERO));
+        assert_eq!(CachedResponse::ttl(&cache_control("private, no-store")), None);
+    }
+
+    #[test]
+    fn test_cached_response_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ApiCache::at(dir.path().to_path_buf());
+        assert!(cache.get(URL).is_none());
+
+        let response = CachedResponse::new(URL, r#"{"payload":{"tag":"v4.2.0"}}"#.into(), &HeaderMap::new()).unwrap();
+        assert!(response.is_fresh());
+        cache.put(&response).unwrap();
+        assert_eq!(cache.get(URL), Some(response.clone()));
+        assert!(cache.get(&format!("{}?prerelease=true", URL)).is_none());
+
+        let stale = CachedResponse { fetched_at: response.fetched_at - DEFAULT_TTL.as_secs(), ..response };
+        assert!(!stale.is_fresh());
+    }
+
+    #[test]
+    fn test_revalidated_keeps_body_and_etag() {
+        let mut headers = cache_control("max-age=60");
+        headers.insert(ETAG, HeaderValue::from_static("W/\"v4.2.0\""));
+        let response = CachedResponse::new(URL, "{}".into(), &headers).unwrap();
+        assert_eq!(response.etag.as_deref(), Some("W/\"v4.2.0\""));
+
+        let stale = CachedResponse { fetched_at: response.fetched_at - 120, ..response };
+        assert!(!stale.is_fresh());
+        // A 304 without an ETag of its own still leaves the old one to send next time
+        let revalidated = stale.revalidated(&HeaderMap::new()).unwrap();
+        assert!(revalidated.is_fresh());
+        assert_eq!(revalidated.body, "{}");
+        assert_eq!(revalidated.max_age, DEFAULT_TTL.as_secs());
+        assert_eq!(revalidated.etag.as_deref(), Some("W/\"v4.2.0\""));
+    }
+}
@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::archive::{Archive, ArchiveFormat};
+    use geode_cli_installer::utils::observer::{InstallObserver, InstallStep, SilentObserver};
+    use std::sync::Mutex;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::Path;
+    use zip::write::SimpleFileOptions;
+
+    fn write_tar(path: &Path, files: &[(&str, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl InstallObserver for Recorder {
+        fn on_step(&self, step: InstallStep) {
+            self.events.lock().unwrap().push(format!("step {:?}", step));
+        }
+
+        fn on_step_done(&self, step: InstallStep) {
+            self.events.lock().unwrap().push(format!("done {:?}", step));
+        }
+
+        fn on_progress(&self, done: u64, total: u64) {
+            self.events.lock().unwrap().push(format!("{}/{}", done, total));
+        }
+
+        fn on_message(&self, message: &str) {
+            self.events.lock().unwrap().push(message.to_string());
+        }
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in files {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extracts_tar_release() {
+        let dir = tempfile::tempdir().unwrap();
+        // The extension lies on purpose: detection goes by magic bytes
+        let archive_path = dir.path().join("geode-v4.2.0-win.zip");
+        write_tar(&archive_path, &[("Geode.dll", b"dll"), ("geode/resources/icon.png", b"png")]);
+
+        let archive = Archive::open(&archive_path).unwrap();
+        assert_eq!(archive.format(), ArchiveFormat::Tar);
+
+        let files = archive.files().unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[1], Path::new("geode/resources/icon.png"));
+        assert_eq!(archive.unpacked_size().unwrap(), 6);
+
+        let destination = dir.path().join("game");
+        archive.extract(&destination, &SilentObserver, |name| !name.ends_with(".png")).unwrap();
+        assert_eq!(fs::read(destination.join("Geode.dll")).unwrap(), b"dll");
+        assert!(!destination.join("geode/resources/icon.png").exists());
+    }
+
+    #[test]
+    fn test_detects_formats_by_magic() {
+        assert_eq!(ArchiveFormat::from_magic(b"PK\x03\x04rest"), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::from_magic(&[0x1f, 0x8b, 8, 0]), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_magic(b"7z\xbc\xaf\x27\x1c\x00\x04"), Some(ArchiveFormat::SevenZip));
+        assert_eq!(ArchiveFormat::from_magic(b"<html>"), None);
+    }
+
+    #[test]
+    fn test_zip_entries_cannot_escape_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        let destination = dir.path().join("game");
+        fs::create_dir_all(&outside).unwrap();
+        fs::create_dir_all(&destination).unwrap();
+        std::os::unix::fs::symlink(&outside, destination.join("escape")).unwrap();
+
+        let archive_path = dir.path().join("evil.zip");
+        let absolute = outside.join("absolute.txt");
+        write_zip(&archive_path, &[
+            ("../outside/parent.txt", b"evil"),
+            (absolute.to_str().unwrap(), b"evil"),
+            ("escape/symlink.txt", b"evil"),
+            ("Geode.dll", b"dll"),
+        ]);
+
+        let written = Archive::open(&archive_path).unwrap().extract(&destination, &SilentObserver, |_| true).unwrap();
+        assert_eq!(written, vec![Path::new("Geode.dll")]);
+        assert_eq!(fs::read_dir(&outside).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_zip_symlinks_are_recreated_only_inside_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("game");
+        fs::create_dir_all(&destination).unwrap();
+
+        let archive_path = dir.path().join("links.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+        writer.start_file("geode/Geode.dll", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"dll").unwrap();
+        writer.add_symlink("Geode.dll", "geode/Geode.dll", SimpleFileOptions::default()).unwrap();
+        writer.add_symlink("passwd", "../../etc/passwd", SimpleFileOptions::default()).unwrap();
+        writer.add_symlink("root", "/", SimpleFileOptions::default()).unwrap();
+        writer.finish().unwrap();
+
+        let written = Archive::open(&archive_path).unwrap().extract(&destination, &SilentObserver, |_| true).unwrap();
+        assert_eq!(written, vec![Path::new("geode/Geode.dll"), Path::new("Geode.dll")]);
+        assert!(destination.join("Geode.dll").symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(fs::read(destination.join("Geode.dll")).unwrap(), b"dll");
+        assert!(destination.join("passwd").symlink_metadata().is_err());
+        assert!(destination.join("root").symlink_metadata().is_err());
+    }
+
+    #[test]
+    fn test_tar_symlinks_are_recreated_only_inside_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("game");
+        fs::create_dir_all(&destination).unwrap();
+
+        let archive_path = dir.path().join("links.tar");
+        let mut builder = tar::Builder::new(File::create(&archive_path).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(3);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "geode/Geode.dll", &b"dll"[..]).unwrap();
+        for (name, target) in [("Geode.dll", "geode/Geode.dll"), ("passwd", "../../etc/passwd"), ("root", "/")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            builder.append_link(&mut header, name, target).unwrap();
+        }
+        builder.finish().unwrap();
+        drop(builder);
+
+        let written = Archive::open(&archive_path).unwrap().extract(&destination, &SilentObserver, |_| true).unwrap();
+        assert_eq!(written, vec![Path::new("geode/Geode.dll"), Path::new("Geode.dll")]);
+        assert_eq!(fs::read(destination.join("Geode.dll")).unwrap(), b"dll");
+        assert!(destination.join("passwd").symlink_metadata().is_err());
+        assert!(destination.join("root").symlink_metadata().is_err());
+    }
+
+    #[test]
+    fn test_extraction_reports_to_observer() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("release.zip");
+        write_zip(&archive_path, &[("Geode.dll", b"dll"), ("../escape.txt", b"evil")]);
+
+        let recorder = Recorder::default();
+        Archive::open(&archive_path).unwrap().extract(&dir.path().join("game"), &recorder, |_| true).unwrap();
+
+        assert_eq!(*recorder.events.lock().unwrap(), [
+            "step Extract",
+            "1/2",
+            "2/2",
+            "Warning: skipping \"../escape.txt\", its path is absolute or contains '..'",
+            "done Extract",
+        ]);
+    }
+}
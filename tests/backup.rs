@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::backup::{Backup, BACKUPS_KEPT, BACKUP_DIR};
+    use geode_cli_installer::utils::install_manifest::InstallManifest;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backs_up_only_existing_files() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let files = vec![PathBuf::from("xinput1_4.dll"), PathBuf::from("geode/config.json")];
+        fs::write(game_dir.path().join("xinput1_4.dll"), "original").unwrap();
+
+        let backup = Backup::create(game_dir.path(), &files).unwrap();
+
+        assert!(backup.dir.starts_with(game_dir.path().join(BACKUP_DIR)));
+        assert_eq!(backup.files, vec![PathBuf::from("xinput1_4.dll")]);
+        assert_eq!(fs::read_to_string(backup.dir.join("xinput1_4.dll")).unwrap(), "original");
+    }
+
+    #[test]
+    fn test_restores_latest_backup() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        let user_reg = prefix.path().join("user.reg");
+        assert!(Backup::latest(game_dir.path()).unwrap().is_none());
+
+        fs::write(game_dir.path().join("xinput1_4.dll"), "original").unwrap();
+        fs::write(&user_reg, "WINE REGISTRY Version 2\n").unwrap();
+        let mut backup = Backup::create(game_dir.path(), &[PathBuf::from("xinput1_4.dll")]).unwrap();
+        backup.save_registry(&user_reg).unwrap();
+
+        fs::write(game_dir.path().join("xinput1_4.dll"), "geode").unwrap();
+        fs::write(&user_reg, "patched").unwrap();
+
+        let latest = Backup::latest(game_dir.path()).unwrap().unwrap();
+        assert_eq!(latest.dir, backup.dir);
+        latest.restore(game_dir.path()).unwrap();
+
+        assert_eq!(fs::read_to_string(game_dir.path().join("xinput1_4.dll")).unwrap(), "original");
+        assert_eq!(fs::read_to_string(&user_reg).unwrap(), "WINE REGISTRY Version 2\n");
+        assert!(Backup::latest(game_dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_incomplete_backup_restores_nothing() {
+        let game_dir = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("Geode.dll"), "original").unwrap();
+        let backup = Backup::create(game_dir.path(), &[PathBuf::from("Geode.dll")]).unwrap();
+
+        fs::remove_file(backup.dir.join("Geode.dll")).unwrap();
+        fs::write(game_dir.path().join("Geode.dll"), "geode").unwrap();
+
+        assert!(backup.restore(game_dir.path()).is_err());
+        assert_eq!(fs::read_to_string(game_dir.path().join("Geode.dll")).unwrap(), "geode");
+    }
+
+    #[test]
+    fn test_restore_removes_newly_added_files() {
+        let game_dir = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("Geode.dll"), "original").unwrap();
+        let backup = Backup::create(game_dir.path(), &[PathBuf::from("Geode.dll")]).unwrap();
+
+        fs::write(game_dir.path().join("Geode.dll"), "geode").unwrap();
+        fs::write(game_dir.path().join("GeodeUpdater.exe"), "new").unwrap();
+        fs::write(game_dir.path().join("notes.txt"), "the user's").unwrap();
+        InstallManifest {
+            files: vec![PathBuf::from("Geode.dll"), PathBuf::from("GeodeUpdater.exe")],
+            registry: Vec::new(),
+        }
+        .save(game_dir.path())
+        .unwrap();
+
+        backup.restore(game_dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(game_dir.path().join("Geode.dll")).unwrap(), "original");
+        assert!(!game_dir.path().join("GeodeUpdater.exe").exists());
+        assert!(game_dir.path().join("notes.txt").exists());
+        assert!(InstallManifest::load(game_dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_keeps_newest_backups() {
+        let game_dir = tempfile::tempdir().unwrap();
+        for i in 0..BACKUPS_KEPT + 2 {
+            fs::write(game_dir.path().join("Geode.dll"), format!("version {}", i)).unwrap();
+            Backup::create(game_dir.path(), &[PathBuf::from("Geode.dll")]).unwrap();
+            thread::sleep(Duration::from_millis(2));
+        }
+        assert_eq!(Backup::list(game_dir.path()).unwrap().len(), BACKUPS_KEPT);
+
+        let latest = Backup::latest(game_dir.path()).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(latest.dir.join("Geode.dll")).unwrap(), format!("version {}", BACKUPS_KEPT + 1));
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::bottles::Bottles;
+    use geode_cli_installer::utils::wine_games::Launcher;
+    use std::fs;
+
+    #[test]
+    fn test_finds_game_in_bottle() {
+        let home = tempfile::tempdir().unwrap();
+        let bottles = home.path().join(".var/app/com.usebottles.bottles/data/bottles/bottles");
+        let game_dir = bottles.join("Gaming/drive_c/Program Files (x86)/Steam/steamapps/common/Geometry Dash");
+        fs::create_dir_all(&game_dir).unwrap();
+        fs::write(game_dir.join("GeometryDash.exe"), "").unwrap();
+        fs::write(bottles.join("Gaming/bottle.yml"), "Name: My Games\nArch: win64\n").unwrap();
+        fs::create_dir_all(bottles.join("Empty/drive_c/windows")).unwrap();
+
+        let games = Bottles::find_games(home.path());
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].launcher, Launcher::Bottles);
+        assert_eq!(games[0].name, "My Games");
+        assert_eq!(games[0].prefix, bottles.join("Gaming"));
+        assert_eq!(games[0].game_dir, game_dir);
+    }
+
+    #[test]
+    fn test_prefers_program_added_in_bottles() {
+        let bottle = tempfile::tempdir().unwrap();
+        let added = bottle.path().join("drive_c/Games/GD");
+        fs::create_dir_all(&added).unwrap();
+        fs::write(added.join("GeometryDash.exe"), "").unwrap();
+        let config = format!(
+            "Name: gd\nExternal_Programs:\n  0b5f:\n    executable: GeometryDash.exe\n    path: {}\n",
+            added.join("GeometryDash.exe").display()
+        );
+        fs::write(bottle.path().join("bottle.yml"), config).unwrap();
+
+        assert_eq!(Bottles::game_in(bottle.path()).unwrap().game_dir, added);
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::errors::InstallerError;
+    use geode_cli_installer::utils::checksum::Checksum;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn test_streamed_digest() {
+        let mut checksum = Checksum::new();
+        checksum.update(b"a");
+        checksum.update(b"bc");
+        assert_eq!(checksum.finish(), ABC_SHA256);
+    }
+
+    #[test]
+    fn test_verify() {
+        assert!(Checksum::verify(&format!("sha256:{}", ABC_SHA256.to_uppercase()), ABC_SHA256).is_ok());
+        assert!(Checksum::normalize("abc").is_err());
+
+        let other = "0".repeat(64);
+        match Checksum::verify(&other, ABC_SHA256) {
+            Err(InstallerError::ChecksumMismatch { expected, got }) => {
+                assert_eq!(expected, other);
+                assert_eq!(got, ABC_SHA256);
+            }
+            result => panic!("expected a mismatch, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    fn run(args: &[&str]) -> (bool, String) {
+        let output = Command::new(env!("CARGO_BIN_EXE_geode-cli-installer")).args(args).output().unwrap();
+        (output.status.success(), String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+
+    #[test]
+    fn test_two_actions_are_rejected() {
+        for args in [
+            &["--steam", "--uninstall"][..],
+            &["--wine", "--uninstall", "--prefix", "/tmp/pfx", "--game-dir", "/tmp/gd"],
+            &["--doctor", "--reset"],
+            &["--reset", "--uninstall"],
+        ] {
+            let (success, stderr) = run(args);
+            assert!(!success, "{:?} was accepted", args);
+            assert!(stderr.contains("cannot be used with"), "{:?}: {}", args, stderr);
+        }
+    }
+
+    #[test]
+    fn test_unsafe_dll_overrides_are_rejected() {
+        for value in ["d3d9\"=\"native", "d3d9=native\n[Software]", "d3d9=native\"", "d3d9=n,b"] {
+            let (success, stderr) = run(&["--steam", "--dll-override", value]);
+            assert!(!success, "{:?} was accepted", value);
+            assert!(stderr.contains("--dll-override"), "{:?}: {}", value, stderr);
+        }
+    }
+
+    #[test]
+    fn test_keep_going_needs_a_batch_install() {
+        for args in [&["--keep-going", "--steam"][..], &["--keep-going", "--uninstall"]] {
+            let (success, stderr) = run(args);
+            assert!(!success, "{:?} was accepted", args);
+            assert!(stderr.contains("--keep-going"), "{:?}: {}", args, stderr);
+        }
+    }
+}
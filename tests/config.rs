@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::config::{validate_dll_override, Channel, InstallerConfig};
+    use std::fs;
+
+    #[test]
+    fn test_loads_per_install_config() {
+        let game_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            InstallerConfig::per_install_path(game_dir.path()),
+            "version = \"v4.1.0\"\nchannel = \"prerelease\"\nexclude = [\"*.pdb\"]\n",
+        )
+        .unwrap();
+
+        let config = InstallerConfig::load(&InstallerConfig::per_install_path(game_dir.path())).unwrap().unwrap();
+        assert_eq!(config.version.as_deref(), Some("v4.1.0"));
+        assert_eq!(config.channel(), Channel::Prerelease);
+        assert!(config.is_excluded("Geode.pdb"));
+        assert!(!config.is_excluded("Geode.dll"));
+        assert_eq!(config.dll_override(), "native,builtin");
+    }
+
+    #[test]
+    fn test_global_config_wins_over_per_install() {
+        let global = InstallerConfig {
+            dll_override: Some("native".into()),
+            ..InstallerConfig::default()
+        };
+        let per_install = InstallerConfig {
+            version: Some("v4.0.0".into()),
+            dll_override: Some("builtin".into()),
+            ..InstallerConfig::default()
+        };
+
+        let merged = global.merge(per_install);
+        assert_eq!(merged.version.as_deref(), Some("v4.0.0"));
+        assert_eq!(merged.dll_override(), "native");
+    }
+
+    #[test]
+    fn test_missing_config_is_none_and_unknown_keys_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = InstallerConfig::per_install_path(dir.path());
+        assert_eq!(InstallerConfig::load(&path).unwrap(), None);
+
+        fs::write(&path, "verison = \"v4.0.0\"\n").unwrap();
+        assert!(InstallerConfig::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_dll_overrides_from_config_and_command_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = InstallerConfig::per_install_path(dir.path());
+        fs::write(&path, "[dll-overrides]\nd3d9 = \"native\"\n").unwrap();
+        let config = InstallerConfig::load(&path).unwrap().unwrap();
+
+        assert_eq!(
+            InstallerConfig::default().dll_overrides("xinput1_4", &[]),
+            vec![("xinput1_4".to_string(), "native,builtin".to_string())]
+        );
+        assert_eq!(config.dll_overrides("xinput1_4", &[]).len(), 2);
+
+        let cli = vec![("XInput1_4".to_string(), "native".to_string()), ("dxgi".to_string(), "builtin".to_string())];
+        assert_eq!(
+            config.dll_overrides("xinput1_4", &cli),
+            vec![("xinput1_4".to_string(), "native".to_string()), ("dxgi".to_string(), "builtin".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_rejects_unsafe_dll_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = InstallerConfig::per_install_path(dir.path());
+        for table in [
+            "[dll-overrides]\n\"d3d9\\\"=\\\"\" = \"native\"\n",
+            "[dll-overrides]\nd3d9 = \"native\\n[Software]\"\n",
+            "[dll-overrides]\nd3d9 = \"native\\\"\"\n",
+            "dll-override = \"n,b\"\n",
+        ] {
+            fs::write(&path, table).unwrap();
+            assert!(InstallerConfig::load(&path).is_err(), "{}", table);
+        }
+
+        fs::write(&path, "dll-override = \"native\"\n[dll-overrides]\nd3d9 = \"disabled\"\ndxgi = \"\"\n").unwrap();
+        assert!(InstallerConfig::load(&path).is_ok());
+        assert!(validate_dll_override("xinput1_4", "native,builtin").is_ok());
+        assert!(validate_dll_override("xinput\n1_4", "native").is_err());
+    }
+}
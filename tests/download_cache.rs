@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::download_cache::DownloadCache;
+    use std::fs;
+
+    #[test]
+    fn test_cache_paths_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::at(dir.path().join("geode-installer"));
+        assert_eq!(cache.clear().unwrap(), 0);
+        assert!(cache.get("v4.2.0").is_none());
+
+        fs::create_dir_all(cache.dir()).unwrap();
+        fs::write(cache.partial_path("v4.2.0"), "partial").unwrap();
+        assert!(cache.get("v4.2.0").is_none());
+
+        fs::write(cache.path("v4.2.0"), "zip").unwrap();
+        assert_eq!(cache.get("v4.2.0"), Some(dir.path().join("geode-installer/geode-v4.2.0-win.zip")));
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert!(!cache.dir().exists());
+    }
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::errors::InstallerError;
+    use serde_json::json;
+
+    #[test]
+    fn test_errors_serialize_with_kind() {
+        let error = InstallerError::Http { status: 503, url: "https://example.com".into() };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "kind": "http", "message": "HTTP error 503 from https://example.com" })
+        );
+        assert_eq!(serde_json::to_value(InstallerError::PrefixNotFound).unwrap()["kind"], "prefix_not_found");
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::fs_utils::{atomic_write, available_space, find_executable, find_file_case_insensitive, is_case_insensitive, move_file, move_tree, WrittenPaths};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_atomic_write_replaces_file_and_keeps_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user.reg");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        atomic_write(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1, "temp file should be gone");
+    }
+
+    #[test]
+    fn test_case_insensitive_lookups() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("GEOMETRYDASH.EXE"), "").unwrap();
+
+        // tmp is case-sensitive here, so simulate a folding mount for the collision check
+        assert!(!is_case_insensitive(dir.path()).unwrap());
+        assert!(find_file_case_insensitive(dir.path(), "GeometryDash.exe").is_some());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1, "probe file should be gone");
+
+        let mut folded = WrittenPaths::new(true);
+        assert!(folded.insert("geode/Resources/icon.png"));
+        assert!(!folded.insert("geode/resources/ICON.png"));
+
+        let mut exact = WrittenPaths::new(false);
+        assert!(exact.insert("Geode.dll"));
+        assert!(exact.insert("geode.dll"));
+    }
+
+    #[test]
+    fn test_finds_executable_on_path() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        fs::write(first.path().join("wine"), "not executable").unwrap();
+        fs::write(second.path().join("wine"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(second.path().join("wine"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_executable("wine", &path), Some(second.path().join("wine")));
+        assert_eq!(find_executable("wineserver", &path), None);
+    }
+
+    #[test]
+    fn test_available_space_of_missing_path_uses_its_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let space = available_space(dir.path()).unwrap();
+        assert!(space > 0);
+        assert_eq!(available_space(&dir.path().join("not/created/yet.zip")).unwrap() / (1 << 20), space / (1 << 20));
+    }
+
+    #[test]
+    fn test_move_tree_merges_into_existing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let staging = dir.path().join(".game.geode-extract");
+        let game = dir.path().join("game");
+        fs::create_dir_all(staging.join("geode/resources")).unwrap();
+        fs::create_dir_all(game.join("geode/resources")).unwrap();
+        fs::write(staging.join("Geode.dll"), "new").unwrap();
+        fs::write(staging.join("geode/resources/icon.png"), "new").unwrap();
+        fs::write(game.join("Geode.dll"), "old").unwrap();
+        fs::write(game.join("geode/resources/saved.json"), "keep").unwrap();
+
+        move_tree(&staging, &game).unwrap();
+
+        assert_eq!(fs::read_to_string(game.join("Geode.dll")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(game.join("geode/resources/icon.png")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(game.join("geode/resources/saved.json")).unwrap(), "keep");
+        assert!(!staging.join("Geode.dll").exists());
+    }
+
+    #[test]
+    fn test_move_file_into_cache() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let download = temp.path().join("geode_temp_v4.2.0.zip");
+        fs::write(&download, "archive").unwrap();
+
+        move_file(&download, &cache.path().join("v4.2.0.zip")).unwrap();
+        assert!(!download.exists());
+        assert_eq!(fs::read_to_string(cache.path().join("v4.2.0.zip")).unwrap(), "archive");
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::heroic::Heroic;
+    use geode_cli_installer::utils::wine_games::Launcher;
+    use serde_json::json;
+    use std::fs;
+    use std::path::Path;
+
+    fn write_json(path: &Path, value: serde_json::Value) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, value.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_finds_epic_game_and_prefix() {
+        let home = tempfile::tempdir().unwrap();
+        let config = home.path().join(".config/heroic");
+        let game_dir = home.path().join("Games/Heroic/GeometryDash");
+        let prefix = home.path().join("Games/Heroic/Prefixes/default/Geometry Dash");
+        fs::create_dir_all(&game_dir).unwrap();
+        fs::create_dir_all(&prefix).unwrap();
+        write_json(&config.join("legendaryConfig/legendary/installed.json"), json!({
+            "4a1b8cd7": { "title": "Geometry Dash", "install_path": game_dir, "executable": "GeometryDash.exe" },
+            "fn": { "title": "Fortnite", "install_path": "/games/fn", "executable": "FortniteLauncher.exe" },
+        }));
+
+        let games = Heroic::find_games(home.path());
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].launcher, Launcher::Heroic);
+        assert_eq!(games[0].game_dir, game_dir);
+        assert_eq!(games[0].prefix, prefix);
+
+        // Settings win over the default location, and Proton prefixes are one level down
+        let custom = home.path().join("proton/Geometry Dash");
+        fs::create_dir_all(custom.join("pfx")).unwrap();
+        write_json(&config.join("GamesConfig/4a1b8cd7.json"), json!({ "4a1b8cd7": { "winePrefix": custom } }));
+        assert_eq!(Heroic::find_games(home.path())[0].prefix, custom.join("pfx"));
+    }
+}
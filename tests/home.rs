@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::home::expand_path;
+    use std::env;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_expands_tilde_and_variables() {
+        let home = Path::new("/home/player");
+        let env_home = env::var("HOME").unwrap();
+
+        assert_eq!(expand_path("~/Games/GD", Some(home)).unwrap(), PathBuf::from("/home/player/Games/GD"));
+        assert_eq!(expand_path("~", Some(home)).unwrap(), PathBuf::from("/home/player"));
+        assert_eq!(expand_path("$HOME/prefix", None).unwrap(), Path::new(&env_home).join("prefix"));
+        assert_eq!(expand_path("${HOME}/pfx", None).unwrap(), Path::new(&env_home).join("pfx"));
+        assert_eq!(expand_path("/games/a$", None).unwrap(), PathBuf::from("/games/a$"));
+        assert_eq!(expand_path("/games/~old", None).unwrap(), PathBuf::from("/games/~old"));
+    }
+
+    #[test]
+    fn test_unknown_variable_or_user_is_an_error() {
+        assert!(expand_path("$GEODE_TEST_SURELY_UNSET/pfx", None).is_err());
+        assert!(expand_path("${HOME/pfx", None).is_err());
+        assert!(expand_path("~no-such-user-here/pfx", None).is_err());
+        assert!(expand_path("~/pfx", None).is_err());
+    }
+}
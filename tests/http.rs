@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::errors::InstallerError;
+    use geode_cli_installer::utils::geode_installer::{GeodeInstaller, InstallOptions};
+    use geode_cli_installer::utils::observer::{InstallObserver, InstallStep};
+    use reqwest::header::{HeaderMap, HeaderValue, CONTENT_RANGE};
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// Answer the first request with `response`, returning its URL and the request's headers
+    fn serve_once(response: String) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let headers: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect();
+            stream.write_all(response.as_bytes()).unwrap();
+            headers
+        });
+        (url, server)
+    }
+
+    /// Answer each request in turn with the next of `responses`, returning every request's headers
+    fn serve_all(responses: Vec<String>) -> (String, thread::JoinHandle<Vec<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let headers: Vec<String> = BufReader::new(&stream)
+                        .lines()
+                        .map(Result::unwrap)
+                        .take_while(|line| !line.is_empty())
+                        .collect();
+                    stream.write_all(response.as_bytes()).unwrap();
+                    headers
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    /// Installs a pinned release from `mirror`, without the cache or backups
+    fn installer(mirror: &str) -> Result<GeodeInstaller, InstallerError> {
+        GeodeInstaller::with_options(InstallOptions {
+            version: Some("v4.2.0".into()),
+            mirror: Some(mirror.to_string()),
+            timeout: Some(Duration::from_millis(300)),
+            retries: 1,
+            no_cache: true,
+            no_backup: true,
+            ..Default::default()
+        })
+    }
+
+    /// Install a pinned release downloaded from `mirror` into `game_dir` and a throwaway prefix
+    fn install_into(mirror: &str, game_dir: &Path) -> Result<(), InstallerError> {
+        let prefix = tempfile::tempdir().unwrap();
+        fs::write(game_dir.join("GeometryDash.exe"), "").unwrap();
+        installer(mirror)?.install_to_wine(prefix.path(), game_dir)
+    }
+
+    /// Keeps the download progress it's told about
+    #[derive(Default)]
+    struct Progress(Arc<Mutex<Vec<(u64, u64)>>>);
+
+    impl InstallObserver for Progress {
+        fn on_step(&self, _step: InstallStep) {}
+
+        fn on_progress(&self, done: u64, total: u64) {
+            self.0.lock().unwrap().push((done, total));
+        }
+
+        fn on_message(&self, _message: &str) {}
+    }
+
+    fn install_from(mirror: &str) -> Result<(), InstallerError> {
+        install_into(mirror, tempfile::tempdir().unwrap().path())
+    }
+
+    #[test]
+    fn test_stalled_download_times_out() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+        let error = install_from(&format!("http://{}", listener.local_addr().unwrap())).unwrap_err();
+        assert!(matches!(error, InstallerError::Timeout(_)), "{}", error);
+    }
+
+    #[test]
+    fn test_requests_identify_the_installer() {
+        let (mirror, server) = serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".into());
+        let error = install_from(&mirror).unwrap_err();
+        assert!(matches!(error, InstallerError::Download(_)), "{}", error);
+
+        let user_agent = format!("user-agent: geode-cli-installer/{}", env!("CARGO_PKG_VERSION"));
+        let headers = server.join().unwrap();
+        assert!(headers.iter().any(|line| line.eq_ignore_ascii_case(&user_agent)), "{:?}", headers);
+    }
+
+    #[test]
+    fn test_rate_limit_reports_reset_time() {
+        let reset = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 600;
+        let (mirror, server) = serve_once(format!(
+            "HTTP/1.1 403 Forbidden\r\nx-ratelimit-remaining: 0\r\nx-ratelimit-reset: {}\r\nContent-Length: 0\r\n\r\n",
+            reset
+        ));
+
+        let error = install_from(&mirror).unwrap_err();
+        server.join().unwrap();
+        assert!(matches!(error, InstallerError::RateLimited(_)), "{}", error);
+        assert!(error.to_string().contains("resets in 10 minute(s)"), "{}", error);
+    }
+
+    #[test]
+    fn test_broken_archive_is_removed_from_game_dir() {
+        let (mirror, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\nnot a zip".into());
+        let game_dir = tempfile::tempdir().unwrap();
+
+        let error = install_into(&mirror, game_dir.path()).unwrap_err();
+        server.join().unwrap();
+        assert!(matches!(error, InstallerError::Extract(_)), "{}", error);
+        assert!(!game_dir.path().join("geode_temp_v4.2.0.zip").exists());
+    }
+
+    #[test]
+    fn test_async_install_reports_progress_and_cleans_up() {
+        let (mirror, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\nnot a zip".into());
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("GeometryDash.exe"), "").unwrap();
+
+        let progress = Progress::default();
+        let seen = Arc::clone(&progress.0);
+        let mut installer = installer(&mirror).unwrap();
+        installer.set_observer(Box::new(progress));
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let install = Arc::new(installer).install_to_wine_async(prefix.path().to_path_buf(), game_dir.path().to_path_buf());
+        let error = runtime.block_on(runtime.spawn(install)).unwrap().unwrap_err();
+        server.join().unwrap();
+
+        assert!(matches!(error, InstallerError::Extract(_)), "{}", error);
+        assert!(!game_dir.path().join("geode_temp_v4.2.0.zip").exists());
+        assert_eq!(seen.lock().unwrap().last(), Some(&(9, 9)));
+    }
+
+    #[test]
+    fn test_async_install_reports_missing_release() {
+        let (mirror, server) = serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".into());
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("GeometryDash.exe"), "").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let install = Arc::new(installer(&mirror).unwrap())
+            .install_to_wine_async(prefix.path().to_path_buf(), game_dir.path().to_path_buf());
+        let error = runtime.block_on(install).unwrap_err();
+        server.join().unwrap();
+
+        assert!(matches!(error, InstallerError::Download(_)), "{}", error);
+        assert!(!game_dir.path().join("geode_temp_v4.2.0.zip").exists());
+    }
+
+    #[test]
+    fn test_mismatched_range_restarts_download() {
+        let (mirror, server) = serve_all(vec![
+            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 0-8/9\r\nContent-Length: 9\r\nConnection: close\r\n\r\nnot a zip".into(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 9\r\nConnection: close\r\n\r\nnot a zip".into(),
+        ]);
+        let game_dir = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("geode_temp_v4.2.0.zip"), "partial").unwrap();
+
+        let error = install_into(&mirror, game_dir.path()).unwrap_err();
+        let requests = server.join().unwrap();
+        assert!(matches!(error, InstallerError::Extract(_)), "{}", error);
+        assert!(requests[0].iter().any(|header| header.eq_ignore_ascii_case("range: bytes=7-")), "{:?}", requests[0]);
+        assert!(!requests[1].iter().any(|header| header.to_ascii_lowercase().starts_with("range:")));
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(GeodeInstaller::content_range_start(&headers), None);
+        headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes 7-8/9"));
+        assert_eq!(GeodeInstaller::content_range_start(&headers), Some(7));
+        headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes */9"));
+        assert_eq!(GeodeInstaller::content_range_start(&headers), None);
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::archive::Archive;
+    use geode_cli_installer::utils::diagnostics::CheckStatus;
+    use geode_cli_installer::utils::geode_installer::{GeodeInstaller, InstallOptions};
+    use geode_cli_installer::utils::geode_version::InstalledVersion;
+    use geode_cli_installer::utils::observer::{InstallObserver, InstallStep, SilentObserver};
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    /// Keeps the warnings it's given
+    struct Warnings(Arc<Mutex<Vec<String>>>);
+
+    impl InstallObserver for Warnings {
+        fn on_step(&self, _step: InstallStep) {}
+
+        fn on_progress(&self, _done: u64, _total: u64) {}
+
+        fn on_message(&self, _message: &str) {}
+
+        fn on_warning(&self, message: &str) {
+            self.0.lock().unwrap().push(message.to_string());
+        }
+    }
+
+    /// Names are written as given, `append_data` would drop a leading `./`
+    fn write_tar(path: &Path, names: &[&str]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for name in names {
+            let mut header = tar::Header::new_gnu();
+            header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(0);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, &[][..]).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_validate_prefix() {
+        let prefix = tempfile::tempdir().unwrap();
+        // Not created yet, wineboot will set it up
+        assert!(GeodeInstaller::validate_prefix(prefix.path()).is_ok());
+
+        fs::create_dir(prefix.path().join("Documents")).unwrap();
+        let error = GeodeInstaller::validate_prefix(prefix.path()).unwrap_err().to_string();
+        assert!(error.contains("drive_c and system.reg are missing"), "{}", error);
+
+        fs::create_dir(prefix.path().join("drive_c")).unwrap();
+        fs::write(prefix.path().join("system.reg"), "WINE REGISTRY Version 2\n").unwrap();
+        assert!(GeodeInstaller::validate_prefix(prefix.path()).is_ok());
+
+        assert!(GeodeInstaller::validate_prefix(&prefix.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_validate_game_dir() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let error = GeodeInstaller::validate_game_dir(game_dir.path()).unwrap_err().to_string();
+        assert!(error.contains("GeometryDash.exe") && error.contains("--force"), "{}", error);
+
+        fs::write(game_dir.path().join("geometrydash.exe"), "").unwrap();
+        assert!(GeodeInstaller::validate_game_dir(game_dir.path()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_release_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.zip");
+
+        write_tar(&path, &["geode.DLL", "xinput1_4.dll"]);
+        assert!(GeodeInstaller::validate_release_archive(&Archive::open(&path).unwrap()).is_ok());
+
+        write_tar(&path, &["geode/resources/icon.png"]);
+        assert!(GeodeInstaller::validate_release_archive(&Archive::open(&path).unwrap()).is_ok());
+
+        write_tar(&path, &["./Geode.dll", "./geode/resources/icon.png"]);
+        assert!(GeodeInstaller::validate_release_archive(&Archive::open(&path).unwrap()).is_ok());
+
+        write_tar(&path, &["index.html", "assets/Geode.dll"]);
+        let error = GeodeInstaller::validate_release_archive(&Archive::open(&path).unwrap()).unwrap_err();
+        assert!(error.to_string().contains("doesn't look like Geode"), "{}", error);
+    }
+
+    #[test]
+    fn test_verify_install() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        for name in ["Geode.dll", "XInput1_4.dll"] {
+            fs::write(game_dir.path().join(name), "").unwrap();
+        }
+        fs::create_dir(game_dir.path().join("geode")).unwrap();
+        let user_reg = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1\n\"xinput1_4\"=\"native,builtin\"\n";
+        fs::write(prefix.path().join("user.reg"), user_reg).unwrap();
+
+        let installer = GeodeInstaller::new().unwrap();
+        let report = installer.verify_install(prefix.path(), game_dir.path());
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.checks.len(), 4);
+
+        fs::write(prefix.path().join("user.reg"), user_reg.replace("native,builtin", "builtin")).unwrap();
+        fs::remove_dir(game_dir.path().join("geode")).unwrap();
+        let report = installer.verify_install(prefix.path(), game_dir.path());
+        assert!(!report.passed());
+        let failed: Vec<&str> = report.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Failed)
+            .map(|check| check.name.as_str())
+            .collect();
+        assert_eq!(failed, ["geode folder", "DllOverrides in user.reg"]);
+    }
+
+    #[test]
+    fn test_local_release() {
+        let named = Path::new("/downloads/geode-v4.2.0-win.zip");
+        assert_eq!(GeodeInstaller::local_release(named, None).unwrap().tag, "v4.2.0");
+        assert_eq!(GeodeInstaller::local_release(named, Some("v4.1.0")).unwrap().tag, "v4.1.0");
+
+        let error = GeodeInstaller::local_release(Path::new("/downloads/geode.zip"), None).unwrap_err();
+        assert!(error.to_string().contains("--version"), "{}", error);
+    }
+
+    #[test]
+    fn test_install_from_local_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("geode-v4.2.0-win.zip");
+        write_tar(&archive, &["Geode.dll", "xinput1_4.dll", "geode/resources/icon.png"]);
+
+        let game_dir = dir.path().join("game");
+        fs::create_dir(&game_dir).unwrap();
+        fs::write(game_dir.join("GeometryDash.exe"), "").unwrap();
+        let prefix = dir.path().join("pfx");
+        fs::create_dir_all(prefix.join("drive_c")).unwrap();
+        for name in ["system.reg", "user.reg"] {
+            fs::write(prefix.join(name), "WINE REGISTRY Version 2\n").unwrap();
+        }
+
+        let mut installer = GeodeInstaller::with_options(InstallOptions {
+            archive: Some(archive.clone()),
+            no_backup: true,
+            // An unroutable proxy, so any attempt to go online fails the install
+            proxy: Some("http://127.0.0.1:9".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        installer.set_observer(Box::new(SilentObserver));
+        installer.install_to_wine(&prefix, &game_dir).unwrap();
+
+        assert!(game_dir.join("Geode.dll").exists());
+        assert_eq!(InstalledVersion::detect(&game_dir).unwrap().unwrap().version, "v4.2.0");
+        assert!(archive.exists(), "the local archive isn't ours to delete");
+    }
+
+    #[test]
+    fn test_missing_local_archive_is_rejected() {
+        let options = InstallOptions { archive: Some("/nonexistent/geode.zip".into()), ..Default::default() };
+        assert!(GeodeInstaller::with_options(options).is_err());
+    }
+
+    #[test]
+    fn test_win32_prefix_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("geode-v4.2.0-win.zip");
+        write_tar(&archive, &["Geode.dll", "xinput1_4.dll"]);
+        let game_dir = dir.path().join("game");
+        fs::create_dir(&game_dir).unwrap();
+        fs::write(game_dir.join("GeometryDash.exe"), "").unwrap();
+        let prefix = dir.path().join("compatdata/322170/pfx");
+        fs::create_dir_all(prefix.join("drive_c")).unwrap();
+        fs::write(prefix.join("system.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win32\n").unwrap();
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let mut installer = GeodeInstaller::with_options(InstallOptions {
+            archive: Some(archive),
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+        installer.set_observer(Box::new(Warnings(Arc::clone(&warnings))));
+        installer.install_to_wine(&prefix, &game_dir).unwrap();
+
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("32-BIT") && warnings[0].contains("compatdata/322170"), "{}", warnings[0]);
+    }
+}
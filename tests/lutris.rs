@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::lutris::Lutris;
+    use geode_cli_installer::utils::wine_games::Launcher;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const CONFIG: &str = r#"
+game:
+  exe: /home/user/Games/geometry-dash/drive_c/Program Files/Geometry Dash/GeometryDash.exe
+  prefix: '/home/user/Games/geometry-dash'
+  args: ""
+system:
+  env:
+    DXVK_HUD: fps
+wine:
+  version: lutris-GE-Proton8-26-x86_64
+name: "Geometry Dash"
+"#;
+
+    #[test]
+    fn test_reads_game_config() {
+        let game = Lutris::from_config(CONFIG, "geometry-dash-1700000000").unwrap();
+        assert_eq!(game.launcher, Launcher::Lutris);
+        assert_eq!(game.name, "Geometry Dash");
+        assert_eq!(game.prefix, PathBuf::from("/home/user/Games/geometry-dash"));
+        assert_eq!(game.game_dir, PathBuf::from("/home/user/Games/geometry-dash/drive_c/Program Files/Geometry Dash"));
+    }
+
+    #[test]
+    fn test_prefix_falls_back_to_exe_location() {
+        let config = "game:\n  exe: /games/gd/drive_c/GD/geometrydash.exe\n";
+        let game = Lutris::from_config(config, "geometry-dash").unwrap();
+        assert_eq!(game.prefix, PathBuf::from("/games/gd"));
+        assert_eq!(game.name, "geometry-dash");
+
+        assert!(Lutris::from_config("game:\n  exe: /games/other/drive_c/Other.exe\n", "other").is_none());
+    }
+
+    #[test]
+    fn test_finds_games_in_config_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        let games_dir = home.path().join(".config/lutris/games");
+        fs::create_dir_all(&games_dir).unwrap();
+        fs::write(games_dir.join("geometry-dash-1.yml"), CONFIG).unwrap();
+        fs::write(games_dir.join("other-2.yml"), "game:\n  exe: /x/drive_c/Other.exe\n").unwrap();
+
+        let games = Lutris::find_games(home.path());
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].name, "Geometry Dash");
+    }
+}
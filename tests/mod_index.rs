@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::checksum::Checksum;
+    use geode_cli_installer::utils::mod_index::{ModIndex, ModRelease};
+    use serde_json::json;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_latest_version_url() {
+        assert_eq!(
+            ModIndex::latest_version_url("geode.node-ids", Some("2.2074"), "v4.2.0"),
+            "https://api.geode-sdk.org/v1/mods/geode.node-ids/versions/latest?platforms=win&geode=4.2.0&gd=2.2074"
+        );
+    }
+
+    #[test]
+    fn test_parse_release() {
+        let release = ModIndex::parse_release(&json!({
+            "error": "",
+            "payload": { "mod_id": "geode.node-ids", "version": "v1.20.0", "download_link": "https://example.com/x.geode" }
+        }))
+        .unwrap();
+        assert_eq!(release.version, "v1.20.0");
+        assert_eq!(release.hash, None);
+
+        assert!(ModIndex::parse_release(&json!({ "error": "Mod not found", "payload": null })).is_err());
+    }
+
+    #[test]
+    fn test_check_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("geode.node-ids.geode.part");
+        let mut writer = zip::ZipWriter::new(File::create(&package).unwrap());
+        writer.start_file("mod.json", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"{}").unwrap();
+        writer.finish().unwrap();
+
+        let sha256 = Checksum::of_file(&package).unwrap();
+        let mut release = ModRelease { version: "v1.20.0".into(), download_link: String::new(), hash: None };
+        ModIndex::check_package(&release, &package, &sha256).unwrap();
+        release.hash = Some(sha256.clone());
+        ModIndex::check_package(&release, &package, &sha256).unwrap();
+
+        let error_page = dir.path().join("error.geode.part");
+        fs::write(&error_page, "<html>502 Bad Gateway</html>").unwrap();
+        let error_sha256 = Checksum::of_file(&error_page).unwrap();
+        assert!(ModIndex::check_package(&release, &error_page, &error_sha256).is_err());
+        release.hash = None;
+        assert!(ModIndex::check_package(&release, &error_page, &error_sha256).is_err());
+    }
+}
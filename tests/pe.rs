@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::pe::{Machine, PeReader};
+    use std::io::Cursor;
+
+    fn pe_image(machine: u16) -> Vec<u8> {
+        let mut image = vec![0u8; 0x86];
+        image[..2].copy_from_slice(b"MZ");
+        image[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        image[0x80..0x84].copy_from_slice(b"PE\0\0");
+        image[0x84..0x86].copy_from_slice(&machine.to_le_bytes());
+        image
+    }
+
+    #[test]
+    fn test_reads_64_bit_machine() {
+        let machine = PeReader::machine_from_reader(&mut Cursor::new(pe_image(0x8664))).unwrap();
+        assert_eq!(machine, Machine::Amd64);
+        assert!(machine.is_64_bit());
+    }
+
+    #[test]
+    fn test_reads_32_bit_machine() {
+        let machine = PeReader::machine_from_reader(&mut Cursor::new(pe_image(0x014c))).unwrap();
+        assert_eq!(machine, Machine::I386);
+        assert!(!machine.is_64_bit());
+    }
+
+    #[test]
+    fn test_rejects_non_pe_data() {
+        let html = b"<!DOCTYPE html><html></html>".to_vec();
+        assert!(PeReader::machine_from_reader(&mut Cursor::new(html)).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::process::ProcessScanner;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_finds_game_started_through_wine() {
+        let proc_root = tempfile::tempdir().unwrap();
+        let game_dir = tempfile::tempdir().unwrap();
+        for (pid, cmdline) in [("1", "/sbin/init\0"), ("self", "ignored\0"), ("4242", "wine64-preloader\0Z:\\games\\GD\\GeometryDash.exe\0")] {
+            fs::create_dir(proc_root.path().join(pid)).unwrap();
+            fs::write(proc_root.path().join(pid).join("cmdline"), cmdline).unwrap();
+        }
+        assert_eq!(ProcessScanner::find_game_in(proc_root.path(), game_dir.path(), "GeometryDash.exe"), Some(4242));
+
+        fs::remove_dir_all(proc_root.path().join("4242")).unwrap();
+        assert_eq!(ProcessScanner::find_game_in(proc_root.path(), game_dir.path(), "GeometryDash.exe"), None);
+
+        // A native helper binary running from the game folder
+        fs::write(game_dir.path().join("helper"), "").unwrap();
+        fs::create_dir(proc_root.path().join("77")).unwrap();
+        symlink(game_dir.path().join("helper"), proc_root.path().join("77/exe")).unwrap();
+        assert_eq!(ProcessScanner::find_game_in(proc_root.path(), game_dir.path(), "GeometryDash.exe"), Some(77));
+    }
+}
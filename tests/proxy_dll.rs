@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::proxy_dll::{ProxyDll, ProxyDllSource};
+    use std::fs;
+
+    #[test]
+    fn test_detects_proxy_dll() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let detected = ProxyDll::detect(game_dir.path());
+        assert_eq!((detected.name.as_str(), detected.source), ("xinput1_4", ProxyDllSource::Default));
+
+        fs::write(game_dir.path().join("XInput9_1_0.dll"), "").unwrap();
+        let detected = ProxyDll::detect(game_dir.path());
+        assert_eq!((detected.name.as_str(), detected.source), ("xinput9_1_0", ProxyDllSource::FileScan));
+
+        let metadata = game_dir.path().join("geode/resources/geode.loader");
+        fs::create_dir_all(&metadata).unwrap();
+        fs::write(metadata.join("mod.json"), r#"{ "id": "geode.loader", "proxy-dll": "XINPUT1_3.dll" }"#).unwrap();
+        let detected = ProxyDll::detect(game_dir.path());
+        assert_eq!((detected.name.as_str(), detected.source), ("xinput1_3", ProxyDllSource::Metadata));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_metadata_name() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let metadata = game_dir.path().join("geode/resources/geode.loader");
+        fs::create_dir_all(&metadata).unwrap();
+        fs::write(metadata.join("mod.json"), r#"{ "proxy-dll": "xinput1_4\"=\"\n[Software\\Evil]" }"#).unwrap();
+        let detected = ProxyDll::detect(game_dir.path());
+        assert_eq!((detected.name.as_str(), detected.source), ("xinput1_4", ProxyDllSource::Default));
+
+        assert!(ProxyDll::is_valid_name("xinput9_1_0.dll"));
+        assert!(!ProxyDll::is_valid_name(""));
+        assert!(!ProxyDll::is_valid_name("a\\b"));
+    }
+}
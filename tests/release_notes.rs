@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::release_notes::ReleaseNotes;
+    use serde_json::json;
+
+    fn releases() -> Vec<ReleaseNotes> {
+        ReleaseNotes::parse_list(&json!([
+            { "tag_name": "v4.3.0", "name": "Geode v4.3.0", "body": "- newest", "published_at": "2025-03-01T00:00:00Z" },
+            { "tag_name": "v4.2.0", "name": "", "body": "- middle" },
+            { "tag_name": "v4.1.0", "name": "Geode v4.1.0", "body": "- installed" },
+            { "tag_name": "nightly", "name": "Nightly", "body": "- not a version" },
+        ]))
+    }
+
+    #[test]
+    fn test_selects_releases_after_installed_up_to_latest() {
+        let selected = ReleaseNotes::between(releases(), Some("4.1.0"), "v4.2.0");
+        let tags: Vec<&str> = selected.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, ["v4.2.0"]);
+        assert_eq!(selected[0].name, "v4.2.0");
+
+        let selected = ReleaseNotes::between(releases(), Some("v4.0.0"), "v4.3.0");
+        let tags: Vec<&str> = selected.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, ["v4.3.0", "v4.2.0", "v4.1.0"]);
+    }
+
+    #[test]
+    fn test_shows_only_latest_when_not_installed() {
+        let selected = ReleaseNotes::between(releases(), None, "v4.3.0");
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].tag, "v4.3.0");
+    }
+}
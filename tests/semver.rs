@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::semver::SemVer;
+
+    fn v(s: &str) -> SemVer {
+        SemVer::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_orders_release_tags() {
+        assert!(v("v4.1.0") < v("v4.2.0"));
+        assert!(v("v4.10.0") > v("v4.9.3"));
+        assert!(v("v4.0.0-beta.2") < v("v4.0.0"));
+        assert!(v("v4.0.0-beta.10") > v("v4.0.0-beta.9"));
+        assert!(v("v4.0.0-alpha.1") < v("v4.0.0-beta.1"));
+        assert_eq!(v("4.2"), v("v4.2.0"));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert_eq!(SemVer::parse("latest"), None);
+        assert_eq!(SemVer::parse("v4"), None);
+        assert_eq!(SemVer::parse("v4.2.0.1"), None);
+    }
+}
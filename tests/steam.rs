@@ -1,6 +1,53 @@
 #[cfg(test)]
 mod tests {
-    use geode_cli_installer::utils::steam_game_finder::SteamGameFinder;
+    use geode_cli_installer::utils::geode_installer::{GeodeInstaller, InstallOptions};
+    use geode_cli_installer::utils::steam_game_finder::{FinderOptions, LibrarySource, SteamGameFinder};
+    use geode_cli_installer::utils::vdf::VdfParser;
+    use std::fs;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    /// libraryfolders.vdf as Steam writes it since mid-2021
+    const NESTED_LIBRARY_FOLDERS: &str = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"/home/user/.local/share/Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"346693285"
+			"322170"		"185212371"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+		"totalsize"		"0"
+	}
+}
+"#;
+
+    /// The flat layout older Steam versions wrote
+    const FLAT_LIBRARY_FOLDERS: &str = r#"
+"LibraryFolders"
+{
+	"TimeNextStatsReport"		"1616798785"
+	"ContentStatsID"		"-4485209353016396434"
+	"1"		"/mnt/games/SteamLibrary"
+	"2"		"/media/external/Steam"
+}
+"#;
+
+    fn add_game(library: &Path) {
+        fs::create_dir_all(library.join("steamapps/common/Geometry Dash")).unwrap();
+        fs::write(
+            library.join("steamapps/appmanifest_322170.acf"),
+            "\"AppState\"\n{\n\t\"appid\"\t\t\"322170\"\n\t\"installdir\"\t\t\"Geometry Dash\"\n}\n",
+        )
+        .unwrap();
+    }
 
     #[test]
     fn test_steam_finder() {
@@ -8,4 +55,410 @@ mod tests {
         println!("Steam root: {:?}", finder.steam_root());
         println!("Library folders: {:?}", finder.library_folders());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_explicit_steam_root() {
+        let root = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        let game = root.path().join("steamapps/common/Geometry Dash");
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(finder.steam_root(), Some(&root.path().to_path_buf()));
+        assert_eq!(finder.get_game_info("322170").unwrap().game_path, game);
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(empty.path().to_path_buf()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_finds_game_without_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let game = root.path().join("steamapps/common/GD copy");
+        fs::create_dir_all(&game).unwrap();
+        fs::write(game.join("GeometryDash.exe"), "").unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(finder.get_game_info("322170").is_none());
+        let info = finder.find_in_common("322170", "GeometryDash.exe").unwrap();
+        assert_eq!(info.game_path, game);
+        assert!(info.manifest_missing);
+    }
+
+    #[test]
+    fn test_finds_copies_in_every_library() {
+        let root = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        add_game(second.path());
+        fs::create_dir_all(second.path().join("steamapps/compatdata/322170/pfx")).unwrap();
+        fs::write(
+            root.path().join("steamapps/libraryfolders.vdf"),
+            format!("\"libraryfolders\"\n{{\n\t\"1\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n}}\n", second.path().display()),
+        )
+        .unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let installs = finder.find_all_game_installs("322170");
+        assert_eq!(installs.len(), 2);
+        assert!(installs[0].proton_prefix.is_none());
+        assert_eq!(installs[1].proton_prefix, Some(second.path().join("steamapps/compatdata/322170/pfx")));
+        assert_eq!(installs[0].game_via, "appmanifest_322170.acf in the main library");
+        assert!(installs[1].game_via.contains("(from libraryfolders.vdf)"));
+        let sources: Vec<_> = finder.scan("322170").libraries.iter().map(|library| library.source).collect();
+        assert_eq!(sources, [LibrarySource::Main, LibrarySource::LibraryFoldersVdf]);
+        assert_eq!(finder.steam_root_via(), Some("--steam-root"));
+    }
+
+    #[test]
+    fn test_scan_is_serializable() {
+        let root = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        fs::write(
+            root.path().join("steamapps/libraryfolders.vdf"),
+            "\"libraryfolders\"\n{\n\t\"1\"\n\t{\n\t\t\"path\"\t\t\"/nonexistent/library\"\n\t}\n}\n",
+        )
+        .unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let scan = finder.scan("322170");
+        assert_eq!(scan.libraries.len(), 1);
+        assert!(scan.libraries[0].has_game);
+        assert_eq!(scan.libraries[0].source, LibrarySource::Main);
+        assert!(scan.prefixes.is_empty());
+        assert!(scan.warnings.iter().any(|w| w.contains("/nonexistent/library")));
+
+        let json = serde_json::to_value(&scan).unwrap();
+        assert_eq!(json["libraries"][0]["source"], "main");
+        assert_eq!(json["game_path"], root.path().join("steamapps/common/Geometry Dash").to_str().unwrap());
+    }
+
+    #[test]
+    fn test_flatpak_root() {
+        let home = tempfile::tempdir().unwrap();
+        let flatpak = home.path().join(".var/app/com.valvesoftware.Steam");
+        let root = flatpak.join(".local/share/Steam");
+        add_game(&root);
+        fs::create_dir_all(root.join("steamapps/compatdata/322170/pfx")).unwrap();
+        // The Flatpak's own ~/.steam/steam link, listed as the main library
+        fs::create_dir_all(flatpak.join(".steam")).unwrap();
+        std::os::unix::fs::symlink(&root, flatpak.join(".steam/steam")).unwrap();
+        fs::write(
+            root.join("steamapps/libraryfolders.vdf"),
+            format!("\"libraryfolders\"\n{{\n\t\"0\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n}}\n", flatpak.join(".steam/steam").display()),
+        )
+        .unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            home: Some(home.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(finder.steam_root(), Some(&root));
+        assert!(finder.steam_root_via().unwrap().starts_with("Flatpak install"));
+        let installs = finder.find_all_game_installs("322170");
+        assert_eq!(installs.len(), 1);
+        assert_eq!(installs[0].game_path, root.join("steamapps/common/Geometry Dash"));
+        assert_eq!(installs[0].proton_prefix, Some(root.join("steamapps/compatdata/322170/pfx")));
+    }
+
+    #[test]
+    fn test_snap_root() {
+        let home = tempfile::tempdir().unwrap();
+        let root = home.path().join("snap/steam/common/.local/share/Steam");
+        add_game(&root);
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            home: Some(home.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(finder.steam_root(), Some(&root));
+        assert!(finder.steam_root_via().unwrap().starts_with("Snap install"));
+        assert_eq!(finder.get_game_info("322170").unwrap().game_path, root.join("steamapps/common/Geometry Dash"));
+    }
+
+    #[test]
+    fn test_app_id_override() {
+        let root = tempfile::tempdir().unwrap();
+        let game = root.path().join("steamapps/common/GD Test");
+        fs::create_dir_all(&game).unwrap();
+        fs::write(
+            root.path().join("steamapps/appmanifest_480.acf"),
+            "\"AppState\"\n{\n\t\"appid\"\t\t\"480\"\n\t\"installdir\"\t\t\"GD Test\"\n}\n",
+        )
+        .unwrap();
+        let options = |app_id: Option<&str>| InstallOptions {
+            app_id: app_id.map(str::to_string),
+            finder: FinderOptions {
+                steam_root: Some(root.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let installer = GeodeInstaller::with_options(options(Some("480"))).unwrap();
+        assert_eq!(installer.app_id(), "480");
+        assert_eq!(installer.detect_game_dir().unwrap(), game);
+
+        assert!(GeodeInstaller::with_options(options(Some("gd"))).is_err());
+    }
+
+    #[test]
+    fn test_manifest_keys_are_case_insensitive() {
+        let root = tempfile::tempdir().unwrap();
+        let game = root.path().join("steamapps/common/Geometry Dash");
+        fs::create_dir_all(&game).unwrap();
+        fs::write(
+            root.path().join("steamapps/appmanifest_322170.acf"),
+            "\"appState\"\n{\n\t\"appid\"\t\t\"322170\"\n\t\"installDir\"\t\t\"Geometry Dash\"\n}\n",
+        )
+        .unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let info = finder.get_game_info("322170").unwrap();
+        assert_eq!(info.game_path, game);
+        assert!(!info.manifest_missing);
+    }
+
+    #[test]
+    fn test_select_game() {
+        let root = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        add_game(second.path());
+        fs::write(
+            root.path().join("steamapps/libraryfolders.vdf"),
+            format!("\"libraryfolders\"\n{{\n\t\"1\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n}}\n", second.path().display()),
+        )
+        .unwrap();
+
+        let mut installer = GeodeInstaller::with_options(InstallOptions {
+            finder: FinderOptions {
+                steam_root: Some(root.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+
+        let installs = installer.game_installs();
+        assert_eq!(installs.len(), 2);
+        assert_eq!(installer.detect_game_dir().unwrap(), installs[0].game_path);
+
+        installer.select_game(installs[1].game_path.clone());
+        assert_eq!(installer.detect_game_dir().unwrap(), second.path().join("steamapps/common/Geometry Dash"));
+    }
+
+    #[test]
+    fn test_proton_prefixes_most_recent_first() {
+        let root = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        for library in [root.path(), second.path()] {
+            fs::create_dir_all(library.join("steamapps/compatdata/322170/pfx")).unwrap();
+            fs::write(library.join("steamapps/compatdata/322170/pfx/user.reg"), "").unwrap();
+        }
+        let week_ago = SystemTime::now() - Duration::from_secs(7 * 86400);
+        fs::File::options()
+            .write(true)
+            .open(root.path().join("steamapps/compatdata/322170/pfx/user.reg"))
+            .unwrap()
+            .set_modified(week_ago)
+            .unwrap();
+        fs::write(
+            root.path().join("steamapps/libraryfolders.vdf"),
+            format!("\"libraryfolders\"\n{{\n\t\"1\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n}}\n", second.path().display()),
+        )
+        .unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let prefixes = finder.find_proton_prefixes("322170");
+        assert_eq!(prefixes.len(), 2);
+        assert_eq!(prefixes[0].path, second.path().join("steamapps/compatdata/322170/pfx"));
+        assert_eq!(prefixes[1].modified, Some(week_ago));
+    }
+
+    #[test]
+    fn test_proton_installs() {
+        let root = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        for tool in ["steamapps/common/Proton 9.0", "compatibilitytools.d/GE-Proton9-20"] {
+            fs::create_dir_all(root.path().join(tool)).unwrap();
+            fs::write(root.path().join(tool).join("proton"), "").unwrap();
+        }
+        // Not a Proton build, just a game
+        fs::create_dir_all(root.path().join("steamapps/common/Proton Pack")).unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let names: Vec<_> = finder.proton_installs().iter().map(|path| path.file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, ["GE-Proton9-20", "Proton 9.0"]);
+    }
+
+    #[test]
+    fn test_scan_mounts_finds_libraries_on_drives() {
+        let mounts = tempfile::tempdir().unwrap();
+        let external = mounts.path().join("run-media/user/Games/SteamLibrary");
+        add_game(&external);
+        let card = mounts.path().join("mnt/sd");
+        add_game(&card);
+        fs::create_dir_all(mounts.path().join("mnt/backup/photos")).unwrap();
+
+        let roots = [mounts.path().join("run-media"), mounts.path().join("mnt"), mounts.path().join("missing")];
+        assert_eq!(
+            SteamGameFinder::scan_mounts(&roots),
+            vec![card.join("steamapps"), external.join("steamapps")]
+        );
+    }
+
+    #[test]
+    fn test_library_paths_in_both_vdf_layouts() {
+        assert_eq!(
+            SteamGameFinder::library_paths(&VdfParser::parse(NESTED_LIBRARY_FOLDERS)),
+            vec![
+                Path::new("/home/user/.local/share/Steam/steamapps").to_path_buf(),
+                Path::new("/mnt/games/SteamLibrary/steamapps").to_path_buf(),
+            ]
+        );
+        assert_eq!(
+            SteamGameFinder::library_paths(&VdfParser::parse(FLAT_LIBRARY_FOLDERS)),
+            vec![
+                Path::new("/mnt/games/SteamLibrary/steamapps").to_path_buf(),
+                Path::new("/media/external/Steam/steamapps").to_path_buf(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finds_game_in_flat_library_folders() {
+        let root = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("steamapps")).unwrap();
+        add_game(second.path());
+        fs::write(
+            root.path().join("steamapps/libraryfolders.vdf"),
+            format!("\"LibraryFolders\"\n{{\n\t\"ContentStatsID\"\t\t\"-1\"\n\t\"1\"\t\t\"{}\"\n}}\n", second.path().display()),
+        )
+        .unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(finder.get_game_info("322170").unwrap().library_path, second.path().join("steamapps"));
+    }
+
+    #[test]
+    fn test_first_library_with_the_game_wins() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("steamapps")).unwrap();
+        let libraries: Vec<tempfile::TempDir> = (0..6).map(|_| tempfile::tempdir().unwrap()).collect();
+        for library in &libraries[2..] {
+            add_game(library.path());
+        }
+        let entries: String = libraries
+            .iter()
+            .enumerate()
+            .map(|(i, library)| format!("\t\"{}\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n", i + 1, library.path().display()))
+            .collect();
+        fs::write(root.path().join("steamapps/libraryfolders.vdf"), format!("\"libraryfolders\"\n{{\n{}}}\n", entries)).unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(finder.get_game_info("322170").unwrap().library_path, libraries[2].path().join("steamapps"));
+    }
+
+    #[test]
+    fn test_prefix_root() {
+        let root = tempfile::tempdir().unwrap();
+        add_game(root.path());
+        let prefixes = tempfile::tempdir().unwrap();
+        fs::create_dir_all(prefixes.path().join("322170/pfx")).unwrap();
+
+        let finder = SteamGameFinder::with_options(FinderOptions {
+            steam_root: Some(root.path().to_path_buf()),
+            prefix_root: Some(prefixes.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+        let game = finder.get_game_info("322170").unwrap();
+        assert_eq!(game.proton_prefix, Some(prefixes.path().join("322170/pfx")));
+        assert_eq!(game.prefix_via.as_deref(), Some("--prefix-root"));
+    }
+
+    #[test]
+    fn test_detect_proton_version() {
+        let compat_data = tempfile::tempdir().unwrap();
+        let prefix = compat_data.path().join("pfx");
+        fs::create_dir_all(&prefix).unwrap();
+        assert_eq!(SteamGameFinder::detect_proton_version(&prefix), None);
+
+        fs::write(compat_data.path().join("config_info"), "6.3-8\n/home/deck/.steam/steam/steamapps/common/Proton 6.3/dist/share/fonts/\n").unwrap();
+        assert_eq!(SteamGameFinder::detect_proton_version(&prefix).as_deref(), Some("6.3-8"));
+
+        fs::write(compat_data.path().join("version"), "GE-Proton9-20\n").unwrap();
+        assert_eq!(SteamGameFinder::detect_proton_version(&prefix).as_deref(), Some("GE-Proton9-20"));
+    }
+
+    #[test]
+    fn test_outdated_proton() {
+        assert!(SteamGameFinder::is_outdated_proton("6.3-8"));
+        assert!(SteamGameFinder::is_outdated_proton("5.13-6"));
+        assert!(!SteamGameFinder::is_outdated_proton("8.0-105"));
+        assert!(!SteamGameFinder::is_outdated_proton("GE-Proton9-20"));
+        assert!(!SteamGameFinder::is_outdated_proton("experimental-bleeding-edge"));
+    }
+
+    #[test]
+    fn test_most_recent_account() {
+        let login_users = VdfParser::parse(
+            "\"users\"\n{\n\t\"76561198000000001\"\n\t{\n\t\t\"AccountName\"\t\t\"old\"\n\t\t\"MostRecent\"\t\t\"0\"\n\t}\n\t\"76561198000000002\"\n\t{\n\t\t\"AccountName\"\t\t\"player\"\n\t\t\"MostRecent\"\t\t\"1\"\n\t}\n}\n",
+        );
+        assert_eq!(SteamGameFinder::most_recent_account(&login_users), Some(39734274));
+        assert_eq!(SteamGameFinder::most_recent_account(&Default::default()), None);
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::steam_deck::SteamDeck;
+    use geode_cli_installer::utils::steam_game_finder::LibrarySource;
+    use std::fs;
+
+    #[test]
+    fn test_is_steamos() {
+        let deck = "NAME=\"SteamOS\"\nID=steamos\nID_LIKE=arch\nVARIANT_ID=steamdeck\n";
+        assert!(SteamDeck::is_steamos(deck));
+        assert!(!SteamDeck::is_steamos("NAME=\"Arch Linux\"\nID=arch\n"));
+    }
+
+    #[test]
+    fn test_library_folders_on_mounted_cards() {
+        let media = tempfile::tempdir().unwrap();
+        fs::create_dir_all(media.path().join("deck/SDCARD/steamapps")).unwrap();
+        fs::create_dir_all(media.path().join("mmcblk0p1/steamapps")).unwrap();
+        fs::create_dir_all(media.path().join("deck/USB/Music")).unwrap();
+
+        assert_eq!(
+            SteamDeck::library_folders(media.path()),
+            vec![media.path().join("deck/SDCARD/steamapps"), media.path().join("mmcblk0p1/steamapps")]
+        );
+    }
+
+    #[test]
+    fn test_sd_card_library_source() {
+        assert_eq!(LibrarySource::SteamDeckSdCard.to_string(), "the Steam Deck SD card scan");
+        assert_eq!(serde_json::to_value(LibrarySource::SteamDeckSdCard).unwrap(), "steam_deck_sd_card");
+    }
+}
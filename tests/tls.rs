@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::tls::{CertificatePins, PINNED_ROOTS};
+
+    #[test]
+    fn test_every_pinned_root_is_bundled() {
+        let store = CertificatePins::root_store();
+        for name in PINNED_ROOTS {
+            assert!(
+                store.roots.iter().any(|anchor| anchor.subject.as_ref().windows(name.len()).any(|w| w == name.as_bytes())),
+                "{} missing from webpki-roots",
+                name
+            );
+        }
+        assert!(CertificatePins::client_config().is_ok());
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::backup::{Backup, BACKUP_DIR};
+    use geode_cli_installer::utils::geode_installer::GeodeInstaller;
+    use geode_cli_installer::utils::install_manifest::{InstallManifest, RegistryChange};
+    use geode_cli_installer::utils::wine_registry::RegistryBackups;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_uninstall_is_idempotent() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        for name in ["GeometryDash.exe", "Geode.dll", "XInput1_4.dll"] {
+            fs::write(game_dir.path().join(name), "").unwrap();
+        }
+        fs::create_dir_all(game_dir.path().join("geode/mods")).unwrap();
+        let user_reg = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1\n\"xinput1_4\"=\"native,builtin\"\n";
+        fs::write(prefix.path().join("user.reg"), user_reg).unwrap();
+
+        let installer = GeodeInstaller::new().unwrap();
+        let report = installer.uninstall_from_wine(prefix.path(), game_dir.path()).unwrap();
+        assert_eq!(report.removed.len(), 3);
+        assert_eq!(report.registry, vec![prefix.path().join("user.reg")]);
+        assert!(game_dir.path().join("GeometryDash.exe").exists());
+        assert!(!fs::read_to_string(prefix.path().join("user.reg")).unwrap().contains("xinput1_4"));
+
+        let report = installer.uninstall_from_wine(prefix.path(), game_dir.path()).unwrap();
+        assert!(report.removed.is_empty());
+        assert!(report.registry.is_empty());
+    }
+
+    #[test]
+    fn test_uninstall_removes_only_manifest_files() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        for name in ["Geode.dll", "Geode.lib", "notes.txt"] {
+            fs::write(game_dir.path().join(name), "").unwrap();
+        }
+        let user_reg = prefix.path().join("user.reg");
+        fs::write(&user_reg, "[Software\\\\Wine\\\\DllOverrides] 1\n\"d3d9\"=\"native\"\n\"xinput1_3\"=\"native\"\n").unwrap();
+
+        InstallManifest {
+            files: vec![PathBuf::from("Geode.dll"), PathBuf::from("../notes.txt")],
+            registry: vec![RegistryChange {
+                file: user_reg.clone(),
+                section: "[Software\\\\Wine\\\\DllOverrides]".into(),
+                value: "xinput1_3".into(),
+            }],
+        }
+        .save(game_dir.path())
+        .unwrap();
+
+        let report = GeodeInstaller::new().unwrap().uninstall_from_wine(prefix.path(), game_dir.path()).unwrap();
+        assert!(!game_dir.path().join("Geode.dll").exists());
+        assert!(game_dir.path().join("Geode.lib").exists(), "not in the manifest, so not ours");
+        assert!(game_dir.path().join("notes.txt").exists());
+        assert_eq!(report.removed.len(), 2, "Geode.dll and the geode/ directory");
+        assert_eq!(fs::read_to_string(&user_reg).unwrap(), "[Software\\\\Wine\\\\DllOverrides] 1\n\"d3d9\"=\"native\"\n");
+    }
+
+    #[test]
+    fn test_uninstall_removes_extracted_symlinks() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        fs::create_dir_all(game_dir.path().join("geode")).unwrap();
+        std::os::unix::fs::symlink("geode", game_dir.path().join("resources")).unwrap();
+        InstallManifest { files: vec![PathBuf::from("resources")], registry: Vec::new() }.save(game_dir.path()).unwrap();
+
+        GeodeInstaller::new().unwrap().uninstall_from_wine(prefix.path(), game_dir.path()).unwrap();
+        assert!(game_dir.path().join("resources").symlink_metadata().is_err());
+    }
+
+    #[test]
+    fn test_uninstall_reverts_system_reg() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        let system_reg = prefix.path().join("system.reg");
+        let original = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1\n\"d3d9\"=\"native\"\n";
+        fs::write(prefix.path().join("user.reg"), "WINE REGISTRY Version 2\n").unwrap();
+        fs::write(&system_reg, original.replace("\"d3d9\"", "\"xinput1_4\"=\"native,builtin\"\n\"d3d9\"")).unwrap();
+
+        InstallManifest {
+            files: Vec::new(),
+            registry: vec![RegistryChange {
+                file: system_reg.clone(),
+                section: "[Software\\\\Wine\\\\DllOverrides]".into(),
+                value: "xinput1_4".into(),
+            }],
+        }
+        .save(game_dir.path())
+        .unwrap();
+
+        let report = GeodeInstaller::new().unwrap().uninstall_from_wine(prefix.path(), game_dir.path()).unwrap();
+        assert_eq!(report.registry, vec![system_reg.clone()]);
+        assert_eq!(fs::read_to_string(&system_reg).unwrap(), original);
+    }
+
+    #[test]
+    fn test_reset_removes_backups() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        let user_reg = prefix.path().join("user.reg");
+        fs::write(game_dir.path().join("Geode.dll"), "old").unwrap();
+        fs::write(&user_reg, "WINE REGISTRY Version 2\n").unwrap();
+        Backup::create(game_dir.path(), &[PathBuf::from("Geode.dll")]).unwrap();
+        let registry_backup = RegistryBackups::create(&user_reg).unwrap();
+
+        let report = GeodeInstaller::new().unwrap().reset(Some(prefix.path()), game_dir.path()).unwrap();
+        assert!(!game_dir.path().join(BACKUP_DIR).exists());
+        assert!(!registry_backup.exists());
+        assert!(RegistryBackups::list(&user_reg).unwrap().is_empty());
+        assert!(report.removed.contains(&game_dir.path().join(BACKUP_DIR)));
+        assert!(report.removed.contains(&registry_backup));
+        assert!(user_reg.exists());
+    }
+}
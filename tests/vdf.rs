@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::launch_options::{LaunchOptionsEditor, RECOMMENDED_LAUNCH_OPTIONS};
+    use geode_cli_installer::utils::vdf::{VdfDocument, VdfParser};
+
+    const LOCALCONFIG: &str = r#""UserLocalConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"Apps"
+				{
+					"322170"
+					{
+						"LastPlayed"		"1700000000"
+						"LaunchOptions"		"-old"
+					}
+				}
+			}
+		}
+	}
+	"friends"
+	{
+		"PersonaName"		"player"
+	}
+}
+"#;
+
+    #[test]
+    fn test_round_trip_keeps_layout() {
+        let document = VdfDocument::parse(LOCALCONFIG);
+        assert_eq!(document.serialize(), LOCALCONFIG);
+    }
+
+    #[test]
+    fn test_sets_launch_options_with_escaped_quotes() {
+        let updated = LaunchOptionsEditor::apply_to_content(LOCALCONFIG, "322170").unwrap();
+        // The existing `-old` is kept, as an argument to the game
+        assert!(updated.contains(r#""LaunchOptions"		"WINEDLLOVERRIDES=\"xinput1_4=n,b\" %command% -old""#));
+        assert!(updated.contains(r#""PersonaName"		"player""#));
+
+        let reparsed = VdfDocument::parse(&updated);
+        let path = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", "322170", "LaunchOptions"];
+        assert_eq!(reparsed.get_string(&path), Some("WINEDLLOVERRIDES=\"xinput1_4=n,b\" %command% -old"));
+
+        assert!(LaunchOptionsEditor::apply_to_content(&updated, "322170").is_none());
+    }
+
+    #[test]
+    fn test_merges_existing_launch_options() {
+        assert_eq!(LaunchOptionsEditor::merge(""), RECOMMENDED_LAUNCH_OPTIONS);
+        assert_eq!(LaunchOptionsEditor::merge(RECOMMENDED_LAUNCH_OPTIONS), RECOMMENDED_LAUNCH_OPTIONS);
+        assert_eq!(
+            LaunchOptionsEditor::merge("gamemoderun %command%"),
+            "WINEDLLOVERRIDES=\"xinput1_4=n,b\" gamemoderun %command%"
+        );
+        assert_eq!(
+            LaunchOptionsEditor::merge("WINEDLLOVERRIDES=\"d3d11=n\" %command% -windowed"),
+            "WINEDLLOVERRIDES=\"xinput1_4=n,b;d3d11=n\" %command% -windowed"
+        );
+        assert_eq!(
+            LaunchOptionsEditor::merge("WINEDLLOVERRIDES=d3d11=n %command%"),
+            "WINEDLLOVERRIDES=\"xinput1_4=n,b;d3d11=n\" %command%"
+        );
+    }
+
+    #[test]
+    fn test_creates_missing_app_block() {
+        let updated = LaunchOptionsEditor::apply_to_content("\"UserLocalConfigStore\"\n{\n}\n", "322170").unwrap();
+        let document = VdfDocument::parse(&updated);
+        let path = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", "322170", "LaunchOptions"];
+        assert_eq!(document.get_string(&path), Some(RECOMMENDED_LAUNCH_OPTIONS));
+    }
+
+    #[test]
+    fn test_parser_decodes_escapes() {
+        let parsed = VdfParser::parse(
+            r#""libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Games\\\"GD\" Library"
+		"label"		"line\nbreak\tand tab"
+	}
+	"1"		"after"
+}
+"#,
+        );
+
+        assert_eq!(parsed["libraryfolders.0.path"], r#"C:\Games\"GD" Library"#);
+        assert_eq!(parsed["libraryfolders.0.label"], "line\nbreak\tand tab");
+        assert_eq!(parsed["libraryfolders.1"], "after");
+    }
+}
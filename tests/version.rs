@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::geode_installer::{GeodeInstaller, InstallOptions, InstallerUpdate, UpdateStatus};
+    use geode_cli_installer::utils::geode_version::{InstalledVersion, LoaderRelease, VersionSource};
+    use geode_cli_installer::utils::observer::SilentObserver;
+    use geode_cli_installer::utils::pe::PeReader;
+    use std::fs;
+
+    #[test]
+    fn test_reads_marker_written_at_install() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let release = LoaderRelease {
+            tag: "v4.2.0".into(),
+            commit: Some("0123456789abcdef".into()),
+            created_at: Some("2025-01-20T12:00:00Z".into()),
+            gd: None,
+            sha256: None,
+            download_url: None,
+        };
+        InstalledVersion::write_marker(game_dir.path(), &release).unwrap();
+
+        let installed = InstalledVersion::detect(game_dir.path()).unwrap().unwrap();
+        assert_eq!(installed.version, "v4.2.0");
+        assert_eq!(installed.commit.as_deref(), Some("0123456789abcdef"));
+        assert_eq!(installed.build_date.as_deref(), Some("2025-01-20T12:00:00Z"));
+        assert_eq!(installed.source, VersionSource::Marker);
+    }
+
+    #[test]
+    fn test_accepts_marker_with_bare_version() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let marker = InstalledVersion::marker_path(game_dir.path());
+        fs::create_dir_all(marker.parent().unwrap()).unwrap();
+        fs::write(&marker, r#"{"version": "3.9.2"}"#).unwrap();
+
+        let installed = InstalledVersion::detect(game_dir.path()).unwrap().unwrap();
+        assert_eq!(installed.version, "v3.9.2");
+        assert_eq!(installed.commit, None);
+    }
+
+    #[test]
+    fn test_malformed_marker_without_dll_is_not_installed() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let marker = InstalledVersion::marker_path(game_dir.path());
+        fs::create_dir_all(marker.parent().unwrap()).unwrap();
+        fs::write(&marker, "not json").unwrap();
+
+        assert_eq!(InstalledVersion::detect(game_dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parses_fixed_file_info() {
+        let mut blob = vec![0u8; 8];
+        blob.extend_from_slice(&0xFEEF04BDu32.to_le_bytes());
+        blob.extend_from_slice(&0x0001_0000u32.to_le_bytes());
+        blob.extend_from_slice(&((4 << 16) | 2u32).to_le_bytes());
+        blob.extend_from_slice(&(1u32 << 16).to_le_bytes());
+
+        assert_eq!(PeReader::parse_fixed_file_version(&blob).as_deref(), Some("4.2.1"));
+    }
+
+    #[test]
+    fn test_validates_release_tags() {
+        assert!(GeodeInstaller::validate_tag("v4.2.0").is_ok());
+        assert!(GeodeInstaller::validate_tag("v4.0.0-beta.1").is_ok());
+        assert!(GeodeInstaller::validate_tag("4.2.0").is_err());
+        assert!(GeodeInstaller::validate_tag("vlatest").is_err());
+        assert!(GeodeInstaller::validate_tag("v4.2.0.1").is_err());
+    }
+
+    #[test]
+    fn test_release_from_api_payload() {
+        let payload = serde_json::json!({
+            "tag": "v4.3.0-beta.1",
+            "commit_hash": "abc123",
+            "gd": { "win": "2.2074" },
+            "assets": [
+                { "name": "geode-v4.3.0-beta.1-mac.zip", "browser_download_url": "https://example.com/mac.zip" },
+                { "name": "geode-v4.3.0-beta.1-win.zip", "browser_download_url": "https://example.com/win.zip" },
+            ],
+        });
+        let release = LoaderRelease::from_api(&payload).unwrap();
+        assert_eq!(release.tag, "v4.3.0-beta.1");
+        assert_eq!(release.gd.as_deref(), Some("2.2074"));
+        assert_eq!(release.download_url.as_deref(), Some("https://example.com/win.zip"));
+
+        let release = LoaderRelease::from_api(&serde_json::json!({ "tag": "v4.2.0" })).unwrap();
+        assert_eq!(release.download_url, None);
+        assert!(LoaderRelease::from_api(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_validates_mirror_urls() {
+        assert_eq!(GeodeInstaller::validate_mirror("https://mirror.example/geode/").unwrap(), "https://mirror.example/geode");
+        assert!(GeodeInstaller::validate_mirror("HTTP://10.0.0.1:8080").is_ok());
+        assert!(GeodeInstaller::validate_mirror("ftp://mirror.example").is_err());
+        assert!(GeodeInstaller::validate_mirror("mirror.example").is_err());
+        assert!(GeodeInstaller::validate_mirror("https://").is_err());
+    }
+
+    #[test]
+    fn test_parses_proxy_urls() {
+        assert!(GeodeInstaller::parse_proxy("http://proxy.example:3128").is_ok());
+        assert!(GeodeInstaller::parse_proxy("proxy.example:3128").is_ok());
+        assert!(GeodeInstaller::parse_proxy("socks5://proxy.example:1080").is_err());
+        assert!(GeodeInstaller::parse_proxy("http://[bad").is_err());
+    }
+
+    #[test]
+    fn test_compares_installed_with_target_release() {
+        let status = |installed: Option<&str>, latest: &str| UpdateStatus {
+            installed: installed.map(|version| InstalledVersion {
+                version: version.into(),
+                commit: None,
+                build_date: None,
+                source: VersionSource::Marker,
+            }),
+            latest: LoaderRelease { tag: latest.into(), ..Default::default() },
+        };
+
+        assert!(status(Some("4.2.0"), "v4.2.0").up_to_date());
+        assert!(!status(Some("v4.1.0"), "v4.2.0").up_to_date());
+        assert!(status(Some("v4.1.0"), "v4.2.0").update_available());
+        assert!(!status(Some("v4.3.0"), "v4.2.0").update_available());
+        assert!(!status(None, "v4.2.0").up_to_date());
+    }
+
+    #[test]
+    fn test_installer_update() {
+        let json = serde_json::json!({
+            "tag_name": "v0.2.0",
+            "html_url": "https://github.com/relativemodder/geode-cli-installer-rs/releases/tag/v0.2.0",
+        });
+        let update = InstallerUpdate::from_github(&json, "0.1.1").unwrap();
+        assert!(update.update_available());
+        assert!(update.url.ends_with("/tag/v0.2.0"));
+
+        assert!(!InstallerUpdate::from_github(&json, "0.2.0").unwrap().update_available());
+        assert!(!InstallerUpdate::from_github(&json, "0.3.0").unwrap().update_available());
+        assert!(InstallerUpdate::from_github(&serde_json::json!({}), "0.1.1").is_err());
+    }
+
+    #[test]
+    fn test_corrupt_dll_does_not_block_install() {
+        let game_dir = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("Geode.dll"), "<html>502 Bad Gateway</html>").unwrap();
+        assert!(InstalledVersion::detect(game_dir.path()).is_err());
+
+        let mut installer = GeodeInstaller::with_options(InstallOptions {
+            version: Some("v4.2.0".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        installer.set_observer(Box::new(SilentObserver));
+        let status = installer.install_status(game_dir.path()).unwrap();
+        assert!(status.installed.is_none());
+        assert_eq!(status.latest.tag, "v4.2.0");
+    }
+
+    #[test]
+    fn test_display_non_ascii_commit() {
+        let installed = InstalledVersion {
+            version: "v4.2.0".into(),
+            commit: Some("ééééééééé".into()),
+            build_date: None,
+            source: VersionSource::Marker,
+        };
+        assert_eq!(installed.to_string(), "v4.2.0 (ééééééé)");
+    }
+}
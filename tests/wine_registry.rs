@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use geode_cli_installer::utils::wine_registry::{
+        DllOverrides, DllOverridesSnapshot, LineEnding, RegistryBackups, RegistryEncoding, RegistryFile, REGISTRY_BACKUPS_KEPT,
+    };
+    use std::fs;
+    use std::path::Path;
+    use std::thread;
+    use std::time::Duration;
+
+    const USER_REG: &str = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1\n#time=1\n\"d3d9\"=\"native\"\n\n[Other] 2\n";
+
+    #[test]
+    fn test_patches_non_utf8_registry_without_losing_bytes() {
+        let mut bytes = USER_REG.as_bytes().to_vec();
+        bytes.extend(b"\"Name\"=\"Caf\xe9\"\n");
+
+        let mut registry = RegistryFile::decode(&bytes);
+        assert_eq!(registry.encoding, RegistryEncoding::Latin1);
+
+        DllOverrides::ensure(&mut registry.content, "xinput1_4", "native,builtin");
+        let written = registry.encode();
+        assert!(written.ends_with(b"\"Name\"=\"Caf\xe9\"\n"));
+        assert!(written.windows(27).any(|w| w == b"\"xinput1_4\"=\"native,builtin"));
+    }
+
+    #[test]
+    fn test_round_trips_utf16_registry() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(USER_REG.encode_utf16().flat_map(u16::to_le_bytes));
+
+        let mut registry = RegistryFile::decode(&bytes);
+        assert_eq!(registry.encoding, RegistryEncoding::Utf16Le { bom: true });
+        assert_eq!(registry.encode(), bytes);
+
+        DllOverrides::ensure(&mut registry.content, "xinput1_4", "native,builtin");
+        let reread = RegistryFile::decode(&registry.encode());
+        assert!(reread.content.contains("\"d3d9\"=\"native\"\n\"xinput1_4\"=\"native,builtin\"\n"));
+    }
+
+    #[test]
+    fn test_removes_only_the_override() {
+        let mut content = USER_REG.to_string();
+        DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin");
+        assert!(DllOverrides::remove(&mut content, "xinput1_4"));
+        assert_eq!(content, USER_REG);
+        assert!(!DllOverrides::remove(&mut content, "xinput1_4"));
+    }
+
+    #[test]
+    fn test_removes_override_in_any_case() {
+        let mut content = USER_REG.to_string();
+        DllOverrides::ensure(&mut content, "XInput1_4", "native,builtin");
+        assert_eq!(DllOverrides::get(&content, "xinput1_4"), Some("native,builtin"));
+        assert!(DllOverrides::remove(&mut content, "xinput1_4"));
+        assert_eq!(content, USER_REG);
+    }
+
+    #[test]
+    fn test_snapshot_restores_only_dll_overrides() {
+        let game_dir = tempfile::tempdir().unwrap();
+        let mut content = USER_REG.to_string();
+
+        assert!(DllOverridesSnapshot::capture(&content).save_if_missing(game_dir.path()).unwrap());
+        DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin");
+        content = content.replace("\"d3d9\"=\"native\"", "\"d3d9\"=\"builtin\"");
+        content.push_str("\"Added\"=\"later\"\n");
+
+        // A second install must not replace the pre-Geode snapshot
+        assert!(!DllOverridesSnapshot::capture(&content).save_if_missing(game_dir.path()).unwrap());
+
+        DllOverridesSnapshot::load(game_dir.path()).unwrap().unwrap().restore(&mut content);
+        assert_eq!(content, format!("{}\"Added\"=\"later\"\n", USER_REG));
+    }
+
+    #[test]
+    fn test_snapshot_without_section_removes_it() {
+        let original = "WINE REGISTRY Version 2\n\n[Other] 2\n\"a\"=\"b\"\n";
+        let snapshot = DllOverridesSnapshot::capture(original);
+        assert_eq!(snapshot.section, None);
+
+        let mut content = original.to_string();
+        DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin");
+        snapshot.restore(&mut content);
+        assert_eq!(content, original);
+    }
+
+    #[test]
+    fn test_keeps_newest_registry_backups() {
+        let prefix = tempfile::tempdir().unwrap();
+        let user_reg = prefix.path().join("user.reg");
+
+        for i in 0..REGISTRY_BACKUPS_KEPT + 2 {
+            fs::write(&user_reg, format!("version {}", i)).unwrap();
+            RegistryBackups::create(&user_reg).unwrap();
+            thread::sleep(Duration::from_millis(2));
+        }
+        assert_eq!(RegistryBackups::list(&user_reg).unwrap().len(), REGISTRY_BACKUPS_KEPT);
+
+        fs::write(&user_reg, "corrupted").unwrap();
+        RegistryBackups::restore_latest(&user_reg).unwrap();
+        assert_eq!(fs::read_to_string(&user_reg).unwrap(), format!("version {}", REGISTRY_BACKUPS_KEPT + 1));
+    }
+
+    #[test]
+    fn test_patches_crlf_registry() {
+        let crlf = USER_REG.replace('\n', "\r\n");
+        let mut content = crlf.clone();
+        assert_eq!(LineEnding::detect(&content), LineEnding::CrLf);
+
+        assert!(DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin"));
+        assert!(content.contains("\"d3d9\"=\"native\"\r\n\"xinput1_4\"=\"native,builtin\"\r\n\r\n[Other] 2\r\n"));
+        assert!(!content.replace("\r\n", "").contains('\n'));
+
+        assert!(DllOverrides::remove(&mut content, "xinput1_4"));
+        assert_eq!(content, crlf);
+    }
+
+    #[test]
+    fn test_rewrites_stale_override_value() {
+        let mut content = USER_REG.replace("\"d3d9\"=\"native\"", "\"d3d9\"=\"native\"\n\"XInput1_4\"=\"builtin\"");
+
+        assert!(DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin"));
+        assert_eq!(content, USER_REG.replace("\"d3d9\"=\"native\"", "\"d3d9\"=\"native\"\n\"xinput1_4\"=\"native,builtin\""));
+        assert!(!DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin"));
+    }
+
+    #[test]
+    fn test_override_in_another_section_is_not_ours() {
+        let mut content = format!("{}\"xinput1_4\"=\"builtin\"\n", USER_REG);
+
+        assert!(DllOverrides::ensure(&mut content, "xinput1_4", "native,builtin"));
+        assert!(DllOverrides::section(&content).unwrap().contains("\"xinput1_4\"=\"native,builtin\""));
+        assert!(content.ends_with("[Other] 2\n\"xinput1_4\"=\"builtin\"\n"));
+    }
+
+    #[test]
+    fn test_regedit_script_targets_the_right_hive() {
+        let script = DllOverrides::regedit_script(Path::new("/pfx/system.reg"), &[("xinput1_4", "native,builtin")]);
+        assert_eq!(
+            script,
+            "Windows Registry Editor Version 5.00\r\n\r\n[HKEY_LOCAL_MACHINE\\Software\\Wine\\DllOverrides]\r\n\"xinput1_4\"=\"native,builtin\"\r\n"
+        );
+        assert!(DllOverrides::regedit_script(Path::new("/pfx/user.reg"), &[]).contains("[HKEY_CURRENT_USER\\"));
+    }
+
+    #[test]
+    fn test_reads_override_value() {
+        let content = USER_REG.replace("\"d3d9\"=\"native\"", "\"d3d9\"=\"native\"\n\"XInput1_4\"=\"native,builtin\"");
+
+        assert_eq!(DllOverrides::get(&content, "xinput1_4"), Some("native,builtin"));
+        assert_eq!(DllOverrides::get(&content, "d3d9"), Some("native"));
+        assert_eq!(DllOverrides::get(&content, "dinput8"), None);
+    }
+}